@@ -0,0 +1,70 @@
+"""Code of the Python SOP that houdini-debug-logger puts behind the points it exports through
+Houdini Engine, whose input geometry can only hold a single part of points:
+
+- With `houlog_set_stream_geometry` turned on, points with geometry in the `houlog_geometry`
+  attribute get a packed primitive holding that geometry relative to the point, like in
+  `houlog_receiver.py`. The attribute is removed afterwards.
+- With `ExportLayout::PerFrame`, the points of each frame are turned into a part of their own: every
+  `frame_<time>` point group becomes a packed primitive holding the points of that frame, in a
+  primitive group of the same name. The primitives are ordered like the points of a merged
+  recording: by frame, followed by the channel plots in `frame_0`. The detail attributes stay on
+  the outer geometry.
+"""
+
+import json
+import re
+
+import hou
+
+node = hou.pwd()
+geo = node.geometry()
+
+geometry_attrib = geo.findPointAttrib("houlog_geometry")
+if geometry_attrib is not None:
+    for pt in geo.points():
+        geometry = pt.attribValue(geometry_attrib)
+        if not geometry:
+            continue
+        geometry = json.loads(geometry)
+        embedded = hou.Geometry()
+        origin = pt.position()
+        points = []
+        for position in geometry["P"]:
+            embedded_pt = embedded.createPoint()
+            embedded_pt.setPosition(hou.Vector3(position) - origin)
+            points.append(embedded_pt)
+        vertices = iter(geometry["vertices"])
+        for count in geometry["counts"]:
+            poly = embedded.createPolygon()
+            for _ in range(count):
+                poly.addVertex(points[next(vertices)])
+        packed = geo.createPacked("PackedGeometry", pt)
+        packed.setEmbeddedGeometry(embedded)
+    geometry_attrib.destroy()
+
+frame_groups = []
+for group in geo.pointGroups():
+    match = re.match(r"frame_(\d+)$", group.name())
+    if match:
+        frame_groups.append((int(match.group(1)), group.name()))
+frame_groups.sort(key=lambda frame: (frame[0] == 0, frame[0]))
+
+if frame_groups:
+    blast = hou.sopNodeTypeCategory().nodeVerb("blast")
+    frames = []
+    for _, group_name in frame_groups:
+        # Keeps only the points of the group.
+        blast.setParms({"group": group_name, "grouptype": 3, "negate": 1})
+        embedded = hou.Geometry()
+        blast.execute(embedded, [geo])
+        frames.append((group_name, embedded))
+
+    geo.deletePrims(geo.prims())
+    geo.deletePoints(geo.points())
+    for _, group_name in frame_groups:
+        geo.findPointGroup(group_name).destroy()
+
+    for group_name, embedded in frames:
+        packed = geo.createPacked("PackedGeometry")
+        packed.setEmbeddedGeometry(embedded)
+        geo.createPrimGroup(group_name).add(packed)
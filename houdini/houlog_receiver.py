@@ -0,0 +1,249 @@
+"""Receiver for recordings streamed by houdini-debug-logger via `init_houlog_stream`.
+
+This doesn't require a Houdini Engine license. To set it up:
+
+1. Create a Python SOP and replace its code with:
+
+       import houlog_receiver
+       houlog_receiver.build(hou.pwd())
+
+2. Start the receiver, for example from a shelf tool or the Python shell, passing the Python SOP:
+
+       import houlog_receiver
+       houlog_receiver.start(hou.node("/obj/recordings/recording_stream"), port=9091)
+
+This file needs to be on Houdini's Python path, for example in `$HOUDINI_USER_PREF_DIR/scripts/python`.
+
+Each message consists of the length of the payload as a big-endian u32, followed by the payload as
+JSON: `{"points": [{"P": [x, y, z], "name": ..., "kind": ..., ..., "attributes": {...}}]}`. The first
+message of a connection contains the whole recording. Later ones only contain the frames that
+changed and the plots spanning the whole recording (with a `time` of 0), and describe the frames in
+`"frames": {"first": ..., "last": ..., "updated": [...]}` by their time. Points with a `geometry` (see
+`houlog_set_stream_geometry`) also get a packed primitive holding that geometry.
+"""
+
+import json
+import re
+import socket
+import struct
+import threading
+
+import hou
+
+_latest = {"points": []}
+_lock = threading.Lock()
+_server = None
+
+_STRING_ATTRIBUTES = ["name", "kind", "frame_label", "channel", "metadata", "src_file"]
+_FLOAT_ATTRIBUTES = ["time", "sim_time", "pscale"]
+_INT_ATTRIBUTES = ["level", "id", "src_line"]
+# Attributes with multiple components, with their defaults.
+_TUPLE_ATTRIBUTES = {
+    "orient": (0.0, 0.0, 0.0, 1.0),
+    "v": (0.0, 0.0, 0.0),
+    "N": (0.0, 0.0, 0.0),
+}
+
+
+def start(node, port=9091):
+    """Starts listening for connections in a background thread. `node` is recooked whenever a new
+    recording arrives."""
+    global _server
+    stop()
+    _server = socket.socket(socket.AF_INET, socket.SOCK_STREAM)
+    _server.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+    _server.bind(("0.0.0.0", port))
+    _server.listen(1)
+    threading.Thread(target=_accept, args=(_server, node), daemon=True).start()
+
+
+def stop():
+    """Stops listening for connections."""
+    global _server
+    if _server is not None:
+        _server.close()
+        _server = None
+
+
+def build(node):
+    """Builds the geometry of the latest recording. Call this from the code of a Python SOP."""
+    with _lock:
+        points = _latest["points"]
+
+    geo = node.geometry()
+    geo.clear()
+
+    for name in _STRING_ATTRIBUTES:
+        geo.addAttrib(hou.attribType.Point, name, "")
+    for name in _FLOAT_ATTRIBUTES:
+        geo.addAttrib(hou.attribType.Point, name, 0.0)
+    for name in _INT_ATTRIBUTES:
+        geo.addAttrib(hou.attribType.Point, name, 0)
+    for name, default in _TUPLE_ATTRIBUTES.items():
+        geo.addAttrib(hou.attribType.Point, name, default)
+
+    # The type of each custom attribute is determined by the first point that sets it.
+    custom_attributes = {}
+    for point in points:
+        for name, value in point["attributes"].items():
+            custom_attributes.setdefault(name, value)
+
+    # Custom attribute names are sanitized like in the Rust exporter, without colliding with each
+    # other or the built-in attributes. Renamed ones are recorded in the `orig_name` detail attribute.
+    taken = set(
+        ["P", "orig_name"]
+        + _STRING_ATTRIBUTES
+        + _FLOAT_ATTRIBUTES
+        + _INT_ATTRIBUTES
+        + list(_TUPLE_ATTRIBUTES)
+    )
+    identifiers = {}
+    for name in sorted(custom_attributes):
+        identifiers[name] = _unique_identifier(name, taken)
+    for name, value in custom_attributes.items():
+        default = "" if isinstance(value, str) else type(value)(0)
+        geo.addAttrib(hou.attribType.Point, identifiers[name], default)
+
+    # A group per kind and channel. Like the custom attributes, names that sanitize to the same
+    # identifier get a numbered suffix instead of being merged into one group.
+    group_names = [_point_group_names(point) for point in points]
+    group_identifiers = {}
+    group_taken = set()
+    for name in sorted(set(name for names in group_names for name in names)):
+        group_identifiers[name] = _unique_identifier(name, group_taken)
+
+    orig_names = {identifier: name for name, identifier in identifiers.items() if identifier != name}
+    orig_names.update(
+        (identifier, name) for name, identifier in group_identifiers.items() if identifier != name
+    )
+    if orig_names:
+        geo.addAttrib(hou.attribType.Global, "orig_name", "")
+        geo.setGlobalAttribValue("orig_name", json.dumps(orig_names, sort_keys=True))
+
+    groups = {}
+    for point, names in zip(points, group_names):
+        pt = geo.createPoint()
+        pt.setPosition(point["P"])
+        for name in _STRING_ATTRIBUTES + _FLOAT_ATTRIBUTES + _INT_ATTRIBUTES:
+            pt.setAttribValue(name, point[name])
+        for name in _TUPLE_ATTRIBUTES:
+            pt.setAttribValue(name, point[name])
+        for name, value in point["attributes"].items():
+            pt.setAttribValue(identifiers[name], value)
+        if point.get("geometry"):
+            _add_packed_geometry(geo, pt, point["geometry"])
+
+        for name in names:
+            group_name = group_identifiers[name]
+            if group_name not in groups:
+                groups[group_name] = geo.createPointGroup(group_name)
+            groups[group_name].add(pt)
+
+
+def _point_group_names(point):
+    names = ["kind_" + point["kind"]]
+    if point["channel"]:
+        names.append("chan_" + point["channel"])
+    return names
+
+
+def _add_packed_geometry(geo, pt, geometry):
+    """Adds a packed primitive on the point of an entry, holding the geometry of the entry relative
+    to the point."""
+    embedded = hou.Geometry()
+    origin = pt.position()
+    points = []
+    for position in geometry["P"]:
+        embedded_pt = embedded.createPoint()
+        embedded_pt.setPosition(hou.Vector3(position) - origin)
+        points.append(embedded_pt)
+    vertices = iter(geometry["vertices"])
+    for count in geometry["counts"]:
+        poly = embedded.createPolygon()
+        for _ in range(count):
+            poly.addVertex(points[next(vertices)])
+    packed = geo.createPacked("PackedGeometry", pt)
+    packed.setEmbeddedGeometry(embedded)
+
+
+def _sanitize_identifier(name):
+    name = re.sub(r"[^A-Za-z0-9_]", "_", name)
+    if name[:1].isdigit():
+        name = "_" + name
+    return name
+
+
+def _unique_identifier(name, taken):
+    sanitized = _sanitize_identifier(name)
+    identifier = sanitized
+    suffix = 1
+    while identifier in taken:
+        identifier = "{}_{}".format(sanitized, suffix)
+        suffix += 1
+    taken.add(identifier)
+    return identifier
+
+
+def _accept(server, node):
+    while True:
+        try:
+            connection, _ = server.accept()
+        except OSError:
+            # The server has been closed via `stop`.
+            return
+        with connection:
+            _receive(connection, node)
+
+
+def _receive(connection, node):
+    global _latest
+    while True:
+        header = _read_exactly(connection, 4)
+        if header is None:
+            return
+        (length,) = struct.unpack(">I", header)
+        payload = _read_exactly(connection, length)
+        if payload is None:
+            return
+        message = json.loads(payload.decode("utf-8"))
+        with _lock:
+            _latest = _merge(_latest, message)
+        _recook(node)
+
+
+def _merge(latest, message):
+    """Applies a message to the latest recording, see the module docs."""
+    frames = message.get("frames")
+    if frames is None:
+        return message
+    updated = set(frames["updated"])
+    kept = [
+        point
+        for point in latest["points"]
+        if point["time"] != 0
+        and frames["first"] <= point["time"] <= frames["last"]
+        and point["time"] not in updated
+    ]
+    # Ordered like a whole recording: by frame, followed by the plots.
+    points = sorted(kept + message["points"], key=lambda point: (point["time"] == 0, point["time"]))
+    return {"points": points}
+
+
+def _read_exactly(connection, length):
+    data = b""
+    while len(data) < length:
+        chunk = connection.recv(length - len(data))
+        if not chunk:
+            return None
+        data += chunk
+    return data
+
+
+def _recook(node):
+    try:
+        import hdefereval
+
+        hdefereval.executeDeferred(lambda: node.cook(force=True))
+    except ImportError:
+        # Without a UI, there is no main thread event loop to defer to.
+        node.cook(force=True)
@@ -0,0 +1,31 @@
+"""Code of the Python SOP that houdini-debug-logger uses when reading recordings back via Houdini
+Engine. It turns the packed primitives written by `houlog_pack.py` back into the points of all
+frames, in the order in which they were packed, and removes all primitives while keeping their
+points, so that the recording is a single part of loose points like one exported with
+`ExportLayout::Merged`.
+"""
+
+import re
+
+import hou
+
+node = hou.pwd()
+geo = node.geometry()
+
+frames = []
+for group in geo.primGroups():
+    match = re.match(r"frame_(\d+)$", group.name())
+    if match:
+        frames.append((int(match.group(1)), group))
+frames.sort(key=lambda frame: (frame[0] == 0, frame[0]))
+
+if frames:
+    embedded = [prim.getEmbeddedGeometry() for _, group in frames for prim in group.prims()]
+    geo.deletePrims(geo.prims())
+    geo.deletePoints(geo.points())
+    for _, group in frames:
+        group.destroy()
+    for frame in embedded:
+        geo.merge(frame)
+
+geo.deletePrims(geo.prims(), keep_points=True)
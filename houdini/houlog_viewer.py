@@ -0,0 +1,444 @@
+"""Builds the viewer HDA for recordings of houdini-debug-logger. Run it with hython:
+
+    hython houlog_viewer.py <output.hda> [version]
+
+This is what `write_viewer_hda` and `houlog-cli hda` do. The HDA is a SOP that takes the recording
+as its input and turns the metadata of each entry back into geometry (polylines for `line`, axes for
+`mat4`, ...), for the current frame or for all frames.
+"""
+
+import sys
+
+import hou
+
+# The code of the Python SOP inside the HDA.
+VIEWER_CODE = '''
+import json
+import math
+
+node = hou.pwd()
+geo = node.geometry()
+all_frames = node.parent().evalParm("all_frames")
+frame = int(round(hou.frame()))
+
+# The built-in attributes that have been renamed via `houlog_set_attribute_names`.
+renamed = {}
+if geo.findGlobalAttrib("houlog_attribute_names"):
+    renamed = json.loads(geo.attribValue("houlog_attribute_names"))
+
+
+def attribute(name):
+    return renamed.get(name, name)
+
+
+# Recordings exported with `ExportLayout::PerFrame` have a packed primitive per frame, in a
+# primitive group named after the frame, which avoids going through the points of all other frames.
+frame_prims = {}
+for group in geo.primGroups():
+    if group.name().startswith("frame_"):
+        frame_prims[group.name()] = group.prims()
+if frame_prims:
+    groups = frame_prims if all_frames else ["frame_%d" % frame, "frame_0"]
+    points = []
+    for group in groups:
+        for prim in frame_prims.get(group, ()):
+            points.extend(prim.getEmbeddedGeometry().points())
+else:
+    points = geo.points()
+
+entries = []
+for pt in points:
+    time = int(round(pt.attribValue(attribute("time"))))
+    if not all_frames and time != 0 and time != frame:
+        continue
+    metadata = pt.attribValue(attribute("metadata"))
+    if not isinstance(metadata, dict):
+        metadata = json.loads(metadata) if metadata else {}
+    entries.append(
+        (pt.attribValue(attribute("name")), pt.attribValue(attribute("kind")), pt.position(), metadata)
+    )
+
+geo.clear()
+geo.addAttrib(hou.attribType.Point, "name", "")
+geo.addAttrib(hou.attribType.Point, "kind", "")
+geo.addAttrib(hou.attribType.Point, "Cd", (1.0, 1.0, 1.0))
+geo.addAttrib(hou.attribType.Point, "orient", (0.0, 0.0, 0.0, 1.0))
+geo.addAttrib(hou.attribType.Point, "template", "")
+
+
+def create_points(positions):
+    points = []
+    for position in positions:
+        pt = geo.createPoint()
+        pt.setPosition(position)
+        points.append(pt)
+    return points
+
+
+def create_polyline(positions, closed=False):
+    points = create_points(positions)
+    poly = geo.createPolygon(is_closed=closed)
+    for pt in points:
+        poly.addVertex(pt)
+    return points
+
+
+def components(data):
+    return list(zip(data["x"], data["y"], data["z"]))
+
+
+def create_axes(origin, axes):
+    points = []
+    for axis, color in zip(axes, [(1, 0, 0), (0, 1, 0), (0, 0, 1)]):
+        end = hou.Vector3(origin) + hou.Vector3(axis)
+        line = create_polyline([origin, end])
+        for pt in line:
+            pt.setAttribValue("Cd", color)
+        points += line
+    return points
+
+
+def create_box(corners, transform=lambda corner: corner):
+    """Draws the edges of a box given by its min and max corner, optionally transformed."""
+    lo, hi = corners
+    points = []
+    for axis in range(3):
+        for a in (lo, hi):
+            for b in (lo, hi):
+                start, end = list(lo), list(hi)
+                start[(axis + 1) % 3] = end[(axis + 1) % 3] = a[(axis + 1) % 3]
+                start[(axis + 2) % 3] = end[(axis + 2) % 3] = b[(axis + 2) % 3]
+                points += create_polyline([transform(start), transform(end)])
+    return points
+
+
+def boxes(data, prefix=""):
+    mins = zip(data[prefix + "min_x"], data[prefix + "min_y"], data[prefix + "min_z"])
+    maxs = zip(data[prefix + "max_x"], data[prefix + "max_y"], data[prefix + "max_z"])
+    return list(zip(mins, maxs))
+
+
+def create_circles(center, radius, segments=24):
+    """Draws a circle in each of the three axis planes, as a stand-in for a sphere."""
+    points = []
+    center = hou.Vector3(center)
+    for u, v in [((1, 0, 0), (0, 1, 0)), ((0, 1, 0), (0, 0, 1)), ((0, 0, 1), (1, 0, 0))]:
+        positions = []
+        for i in range(segments):
+            angle = 2.0 * math.pi * i / segments
+            offset = hou.Vector3(u) * math.cos(angle) + hou.Vector3(v) * math.sin(angle)
+            positions.append(center + offset * radius)
+        points += create_polyline(positions, closed=True)
+    return points
+
+
+def rotate(vector, axis, angle):
+    """Rotates the vector around the X, Y or Z axis."""
+    c, s = math.cos(angle), math.sin(angle)
+    x, y, z = vector
+    if axis == "X":
+        return (x, c * y - s * z, s * y + c * z)
+    if axis == "Y":
+        return (c * x + s * z, y, -s * x + c * z)
+    return (c * x - s * y, s * x + c * y, z)
+
+
+def build_point(position, data):
+    return create_points([position])
+
+
+def build_vec3(position, data):
+    return create_points([data["pt"]])
+
+
+def build_line(position, data):
+    return create_polyline(components(data))
+
+
+def build_polygon(position, data):
+    return create_polyline(components(data), closed=True)
+
+
+def build_mesh(position, data):
+    points = create_points(components(data))
+    indices = iter(data["i"])
+    for count in data["c"]:
+        poly = geo.createPolygon()
+        for _ in range(count):
+            poly.addVertex(points[next(indices)])
+    return points
+
+
+def build_mat4(position, data):
+    m = data["xform"]
+    return create_axes(m[12:15], [m[0:3], m[4:7], m[8:11]])
+
+
+def build_euler(position, data):
+    # Yaw is about Y, pitch about X and roll about Z, applied in the given order like
+    # `Quat::from_euler` in glam. The proper and extrinsic orders of newer glam versions, such as
+    # `ZXZ` or `XYZEx`, take the angles in the order of the axes instead. Intrinsic rotations are
+    # applied to a vector starting with the last axis, extrinsic ones (`Ex`) with the first one.
+    order = data["order"]
+    if len(order) == 3 and len(set(order)) == 3:
+        by_axis = {"X": data["pitch"], "Y": data["yaw"], "Z": data["roll"]}
+        angles = [by_axis[axis] for axis in order]
+    else:
+        angles = [data["yaw"], data["pitch"], data["roll"]]
+    rotations = list(zip(order[:3], angles))
+    if not order.endswith("Ex"):
+        rotations.reverse()
+    axes = []
+    for axis in [(1, 0, 0), (0, 1, 0), (0, 0, 1)]:
+        for name, angle in rotations:
+            axis = rotate(axis, name, angle)
+        axes.append(axis)
+    return create_axes(position, axes)
+
+
+def build_quat(position, data):
+    x, y, z, w = data["quat"]
+    rotation = hou.Quaternion(x, y, z, w).extractRotationMatrix3()
+    axes = [hou.Vector3(axis) * rotation for axis in [(1, 0, 0), (0, 1, 0), (0, 0, 1)]]
+    return create_axes(position, axes)
+
+
+def build_bvh(position, data):
+    points = []
+    for corners in boxes(data):
+        points += create_box(corners)
+    return points
+
+
+def build_octree(position, data):
+    points = []
+    for center, size in zip(components(data), data["size"]):
+        half = size / 2.0
+        lo = [c - half for c in center]
+        hi = [c + half for c in center]
+        points += create_box((lo, hi))
+    return points
+
+
+def build_kdtree_trace(position, data):
+    points = []
+    for corners in boxes(data) + boxes(data, "split_"):
+        points += create_box(corners)
+    if data.get("nearest"):
+        points += create_polyline([data["query"], data["nearest"]])
+    return points
+
+
+def build_grid(position, data):
+    origin = hou.Vector3(data["origin"])
+    axis_u, axis_v = hou.Vector3(data["axis_u"]), hou.Vector3(data["axis_v"])
+    divisions_u, divisions_v = max(data["divisions_u"], 1), max(data["divisions_v"], 1)
+    points = []
+    for i in range(divisions_u + 1):
+        start = origin + axis_u * (float(i) / divisions_u)
+        points += create_polyline([start, start + axis_v])
+    for i in range(divisions_v + 1):
+        start = origin + axis_v * (float(i) / divisions_v)
+        points += create_polyline([start, start + axis_u])
+    return points
+
+
+def build_points(position, data):
+    return create_points(components(data))
+
+
+def build_sphere(position, data):
+    return create_circles(position, data["radius"])
+
+
+def build_capsule(position, data):
+    start, end = data["start"], data["end"]
+    return (
+        create_polyline([start, end])
+        + create_circles(start, data["radius"])
+        + create_circles(end, data["radius"])
+    )
+
+
+def build_obb(position, data):
+    x, y, z, w = data["quat"]
+    rotation = hou.Quaternion(x, y, z, w).extractRotationMatrix3()
+    center = hou.Vector3(position)
+    half = data["half_extents"]
+    corners = ([-h for h in half], half)
+    return create_box(corners, lambda corner: center + hou.Vector3(corner) * rotation)
+
+
+def build_kinematics(position, data):
+    points = build_mat4(position, data)
+    origin = hou.Vector3(position)
+    for velocity, color in [
+        (data["linear_velocity"], (1, 1, 0)),
+        (data["angular_velocity"], (1, 0, 1)),
+    ]:
+        arrow = create_polyline([origin, origin + hou.Vector3(velocity)])
+        for pt in arrow:
+            pt.setAttribValue("Cd", color)
+        points += arrow
+    return points
+
+
+def build_surface_hit(position, data):
+    origin = hou.Vector3(position)
+    return create_points([origin]) + create_polyline([origin, origin + hou.Vector3(data["normal"])])
+
+
+def build_compound(position, data):
+    points = []
+    for shape in data["shapes"]:
+        points += build_entry(shape["kind"], shape["pt"], shape["metadata"])
+    return points
+
+
+def build_assert_failed(position, data):
+    points = build_entry(data["kind"], position, data["metadata"])
+    for pt in points:
+        pt.setAttribValue("Cd", (1, 0, 0))
+    return points
+
+
+def build_graph(position, data):
+    points = create_points(components(data))
+    for start, end in zip(data["from"], data["to"]):
+        poly = geo.createPolygon(is_closed=False)
+        poly.addVertex(points[start])
+        poly.addVertex(points[end])
+    return points
+
+
+def build_heightfield(position, data):
+    width, cell_size, heights = data["width"], data["cell_size"], data["heights"]
+    positions = [
+        ((i % width) * cell_size, height, (i // width) * cell_size)
+        for i, height in enumerate(heights)
+    ]
+    points = create_points(positions)
+    for row in range(data["height"] - 1):
+        for column in range(width - 1):
+            i = row * width + column
+            poly = geo.createPolygon()
+            for corner in [i, i + 1, i + width + 1, i + width]:
+                poly.addVertex(points[corner])
+    return points
+
+
+def build_color_grid(position, data):
+    width = data["width"]
+    axis_u, axis_v = hou.Vector3(data["axis_u"]), hou.Vector3(data["axis_v"])
+    points = []
+    for i, color in enumerate(zip(data["r"], data["g"], data["b"])):
+        pt = create_points([axis_u * (i % width) + axis_v * (i // width)])[0]
+        pt.setAttribValue("Cd", color)
+        points.append(pt)
+    return points
+
+
+def build_instances(position, data):
+    # One point per copy, which can be fed into Copy to Points together with the template.
+    xforms = data["xforms"]
+    points = []
+    for i in range(0, len(xforms), 16):
+        xform = hou.Matrix4(xforms[i : i + 16])
+        rotation = hou.Quaternion(xform.extractRotationMatrix3())
+        pt = create_points([xform.extractTranslates()])[0]
+        pt.setAttribValue("orient", tuple(rotation[j] for j in range(4)))
+        pt.setAttribValue("template", data["template"])
+        points.append(pt)
+    return points
+
+
+def build_profile_bar(position, data):
+    x, y, z = position
+    width = data["duration"] * 1000.0
+    return create_polyline(
+        [(x, y, z), (x + width, y, z), (x + width, y + 1.0, z), (x, y + 1.0, z)],
+        closed=True,
+    )
+
+
+# Every built-in kind, which is checked by the tests of `snippets.rs`. Kinds without a shape of
+# their own, such as `float` or `event`, are shown as a point.
+BUILDERS = {
+    "vec3": build_vec3,
+    "mat4": build_mat4,
+    "quat": build_quat,
+    "euler": build_euler,
+    "float": build_point,
+    "int": build_point,
+    "duration": build_point,
+    "event": build_point,
+    "line": build_line,
+    "polygon": build_polygon,
+    "mesh": build_mesh,
+    "bvh": build_bvh,
+    "octree": build_octree,
+    "kdtree_trace": build_kdtree_trace,
+    "convex_hull": build_points,
+    "grid": build_grid,
+    "graph": build_graph,
+    "heightfield": build_heightfield,
+    "color_grid": build_color_grid,
+    "points": build_points,
+    "sphere": build_sphere,
+    "capsule": build_capsule,
+    "obb": build_obb,
+    "kinematics": build_kinematics,
+    "instances": build_instances,
+    "surface_hit": build_surface_hit,
+    "compound": build_compound,
+    "assert_failed": build_assert_failed,
+    "span": build_point,
+    "profile_bar": build_profile_bar,
+    "counter": build_point,
+    "gauge": build_point,
+}
+
+
+def build_entry(kind, position, data):
+    return BUILDERS.get(kind, build_point)(position, data)
+
+
+for name, kind, position, metadata in entries:
+    for pt in build_entry(kind, position, metadata):
+        pt.setAttribValue("name", name)
+        pt.setAttribValue("kind", kind)
+'''
+
+
+def build(output, version):
+    container = hou.node("/obj").createNode("geo", "houlog_viewer_build")
+    subnet = container.createNode("subnet", "houlog_viewer")
+    viewer = subnet.createNode("python", "viewer")
+    viewer.parm("python").set(VIEWER_CODE.strip() + "\n")
+    viewer.setInput(0, subnet.indirectInputs()[0])
+    output_node = subnet.createNode("output")
+    output_node.setInput(0, viewer)
+    output_node.setDisplayFlag(True)
+
+    asset = subnet.createDigitalAsset(
+        name="houlog_viewer",
+        hda_file_name=output,
+        description="Houlog Viewer",
+        min_num_inputs=1,
+        max_num_inputs=1,
+        version=version,
+    )
+
+    definition = asset.type().definition()
+    parms = definition.parmTemplateGroup()
+    parms.append(
+        hou.ToggleParmTemplate("all_frames", "All Frames", default_value=False)
+    )
+    definition.setParmTemplateGroup(parms)
+    definition.save(output)
+
+
+if __name__ == "__main__":
+    if len(sys.argv) < 2:
+        sys.exit("usage: hython houlog_viewer.py <output.hda> [version]")
+    build(sys.argv[1], sys.argv[2] if len(sys.argv) > 2 else "")
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+/// The names of the exported attributes, see
+/// [`houlog_set_attribute_names`](crate::houlog_set_attribute_names()). The built-in names, such as
+/// `name`, `time`, `kind` and `metadata`, can clash with attributes that a pipeline already uses.
+///
+/// ```no_run
+/// # use houdini_debug_logger::*;
+/// houlog_set_attribute_names(AttributeNames::with_prefix("houlog_").rename("kind", "type"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AttributeNames {
+    /// Prepended to the names of all attributes, except for the standard Houdini attributes `P`,
+    /// `pscale`, `orient`, `v` and `N`.
+    pub prefix: String,
+    /// New names for individual attributes, by their built-in name. The prefix is still applied.
+    pub renamed: BTreeMap<String, String>,
+}
+
+impl AttributeNames {
+    /// The standard Houdini attributes, which keep their names so that Houdini still picks them up.
+    const STANDARD: &'static [&'static str] = &["P", "pscale", "orient", "v", "N"];
+
+    pub fn with_prefix(prefix: &str) -> Self {
+        AttributeNames {
+            prefix: prefix.to_string(),
+            ..AttributeNames::default()
+        }
+    }
+
+    /// Exports the built-in attribute `name` as `new_name` instead.
+    pub fn rename(mut self, name: &str, new_name: &str) -> Self {
+        self.renamed.insert(name.to_string(), new_name.to_string());
+        self
+    }
+
+    /// The exported name of the built-in attribute `name`.
+    pub fn get(&self, name: &str) -> String {
+        if Self::STANDARD.contains(&name) {
+            return name.to_string();
+        }
+        let name = self.renamed.get(name).map_or(name, String::as_str);
+        format!("{}{}", self.prefix, name)
+    }
+}
@@ -0,0 +1,163 @@
+//! Converts, merges and inspects saved recordings, for example captures from headless runs.
+//!
+//! ```text
+//! houlog-cli convert <input> <output>
+//! houlog-cli merge <output> <input>...
+//! houlog-cli stats <input>
+//! houlog-cli diff <before> <after> [tolerance] [output]
+//! houlog-cli hda <output>
+//! houlog-cli serve <address> <output>
+//! ```
+//!
+//! The format is chosen by the file extension: `.ndjson` and `.jsonl` for NDJSON, everything else
+//! (typically `.bgeo`) is read and written via Houdini Engine.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Result};
+use houdini_debug_logger::{write_viewer_hda, HoulogServer, Recording};
+
+const USAGE: &str = "usage:
+    houlog-cli convert <input> <output>
+    houlog-cli merge <output> <input>...
+    houlog-cli stats <input>
+    houlog-cli diff <before> <after> [tolerance] [output]
+    houlog-cli hda <output>
+    houlog-cli serve <address> <output>";
+
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    run(&args.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+fn run(args: &[&str]) -> Result<()> {
+    match args {
+        ["convert", input, output] => Recording::load(input)?.save(output),
+        ["merge", output, inputs @ ..] if !inputs.is_empty() => {
+            let recordings = inputs
+                .iter()
+                .map(Recording::load)
+                .collect::<Result<Vec<_>>>()?;
+            Recording::merge(recordings).save(output)
+        }
+        ["stats", input] => {
+            print_stats(&Recording::load(input)?);
+            Ok(())
+        }
+        ["diff", before, after, rest @ ..] if rest.len() <= 2 => {
+            let tolerance = match rest.first() {
+                Some(tolerance) => tolerance.parse()?,
+                None => 1e-5,
+            };
+            let diff = Recording::load(before)?.diff(&Recording::load(after)?, tolerance);
+            print!("{}", diff);
+            if let Some(output) = rest.get(1) {
+                diff.to_recording().save(output)?;
+            }
+            Ok(())
+        }
+        ["hda", output] => write_viewer_hda(output),
+        ["serve", address, output] => {
+            let server = HoulogServer::bind(address, output)?;
+            println!("Listening on {}", server.local_addr()?);
+            server.run()
+        }
+        _ => Err(anyhow!("{}", USAGE)),
+    }
+}
+
+fn print_stats(recording: &Recording) {
+    let frames = recording
+        .points
+        .iter()
+        .filter(|point| point.time > 0.0)
+        .map(|point| point.time as u32)
+        .collect::<BTreeSet<_>>();
+    let metadata_bytes = recording
+        .points
+        .iter()
+        .map(|point| point.metadata.len())
+        .sum::<usize>();
+
+    println!("entries: {}", recording.points.len());
+    println!("frames: {}", frames.len());
+    println!("metadata: {} bytes", metadata_bytes);
+
+    let mut kinds = BTreeMap::<&str, usize>::new();
+    let mut names = BTreeMap::<&str, usize>::new();
+    for point in &recording.points {
+        *kinds.entry(&point.kind).or_default() += 1;
+        *names.entry(&point.name).or_default() += 1;
+    }
+    print_counts("kinds", kinds);
+    print_counts("names", names);
+}
+
+/// Prints the counts, most frequent first.
+fn print_counts(title: &str, counts: BTreeMap<&str, usize>) {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by_key(|&(_, count)| Reverse(count));
+    println!("{}:", title);
+    for (name, count) in counts {
+        println!("    {:>8}  {}", count, name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use houdini_debug_logger::RecordedPoint;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("houlog_cli_{}_{}", std::process::id(), name))
+    }
+
+    fn recording(names: &[(&str, f32)]) -> Recording {
+        Recording {
+            points: names
+                .iter()
+                .map(|&(name, time)| RecordedPoint {
+                    name: name.to_string(),
+                    time,
+                    ..RecordedPoint::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn converts_and_merges_recordings() -> Result<()> {
+        let a = temp_path("a.ndjson");
+        let b = temp_path("b.jsonl");
+        let converted = temp_path("converted.jsonl");
+        let merged = temp_path("merged.ndjson");
+        recording(&[("a1", 1.0), ("a2", 2.0)]).save(&a)?;
+        recording(&[("b1", 1.0)]).save(&b)?;
+
+        let path = |path: &PathBuf| path.to_str().unwrap().to_string();
+        let result = (|| -> Result<_> {
+            run(&["convert", &path(&a), &path(&converted)])?;
+            run(&["merge", &path(&merged), &path(&converted), &path(&b)])?;
+            Ok((Recording::load(&converted)?, Recording::load(&merged)?))
+        })();
+        for path in [&a, &b, &converted, &merged] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        let (converted, merged) = result?;
+        assert_eq!(converted, recording(&[("a1", 1.0), ("a2", 2.0)]));
+        assert_eq!(merged, recording(&[("a1", 1.0), ("b1", 1.0), ("a2", 2.0)]));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_commands() {
+        assert_eq!(
+            run(&["merge", "out.ndjson"]).unwrap_err().to_string(),
+            USAGE
+        );
+        assert_eq!(run(&[]).unwrap_err().to_string(), USAGE);
+    }
+}
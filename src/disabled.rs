@@ -0,0 +1,430 @@
+//! No-op versions of the logging functions, used when the `enabled` and `memory` features are
+//! turned off. All of them compile down to nothing, so logging calls can be left in shipping code
+//! without any cost and without linking against Houdini.
+
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::attribute_names::AttributeNames;
+use crate::drop_behavior::DropBehavior;
+use crate::export_layout::ExportLayout;
+use crate::frame_mode::FrameMode;
+use crate::level::Level;
+use crate::loggable::IntoLoggable;
+use crate::memory_policy::MemoryPolicy;
+use crate::metadata_format::MetadataFormat;
+use crate::query::HoulogFilter;
+use crate::recording::{RecordedPoint, Recording};
+use crate::stats::HoulogStats;
+use anyhow::Result;
+use glam::Vec3;
+
+/// Stand-in for the hapi-rs session, which isn't available without the `enabled` feature. It can't
+/// be constructed, so [`LiveSessionOptions::with_session`] can never be called.
+pub enum Session {}
+
+#[inline(always)]
+pub fn houlog<T: IntoLoggable>(_name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn try_houlog<T: IntoLoggable>(_name: &str, _v: T) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_at_frame<T: IntoLoggable>(_frame: u32, _name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_with_velocity<T: IntoLoggable>(_name: &str, _v: T, _velocity: Vec3) {}
+
+#[inline(always)]
+pub fn houlog_replace<T: IntoLoggable>(_name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_set_keep_replaced(_keep: bool) {}
+
+#[inline(always)]
+pub fn houlog_id<T: IntoLoggable>(_id: u64, _name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_at<T: IntoLoggable>(_level: Level, _name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_set_min_level(_level: Level) {}
+
+#[inline(always)]
+pub fn houlog_set_default_pscale(_pscale: f32) {}
+
+#[inline(always)]
+pub fn houlog_set_metadata_format(_format: MetadataFormat) {}
+
+#[inline(always)]
+pub fn houlog_set_attribute_names(_names: AttributeNames) {}
+
+#[inline(always)]
+pub fn try_houlog_set_attribute_names(_names: AttributeNames) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_set_export_layout(_layout: ExportLayout) {}
+
+#[inline(always)]
+pub fn houlog_set_stream_geometry(_enabled: bool) {}
+
+#[inline(always)]
+pub fn houlog_set_timeline(_fps: f32, _start_frame: i64) {}
+
+#[inline(always)]
+pub fn houlog_set_global(_key: &str, _value: impl Into<serde_json::Value>) {}
+
+#[inline(always)]
+pub fn houlog_in<T: IntoLoggable>(_channel: &str, _name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_enable_channel(_channel: &str) {}
+
+#[inline(always)]
+pub fn houlog_disable_channel(_channel: &str) {}
+
+#[inline(always)]
+pub fn houlog_channels() -> BTreeMap<String, bool> {
+    BTreeMap::new()
+}
+
+#[inline(always)]
+pub fn houlog_sampled<T: IntoLoggable>(_name: &str, _v: T, _time_secs: f32, _interval_secs: f32) {}
+
+#[inline(always)]
+pub fn houlog_every_n<T: IntoLoggable>(_name: &str, _v: T, _n: u64) {}
+
+#[inline(always)]
+pub fn houlog_entry<T: IntoLoggable>(_name: &str, _v: T) -> EntryBuilder {
+    EntryBuilder
+}
+
+#[must_use = "the entry is only logged once `commit` is called"]
+pub struct EntryBuilder;
+
+impl EntryBuilder {
+    #[inline(always)]
+    pub fn attr_f32(self, _name: &str, _value: f32) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn attr_i32(self, _name: &str, _value: i32) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn attr_str(self, _name: &str, _value: &str) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn commit(self) {}
+}
+
+#[inline(always)]
+pub fn houlog_once<T: IntoLoggable>(_name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_once_per_frame<T: IntoLoggable>(_name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_set_assert_panics(_panics: bool) {}
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn __houassert_failed<T: IntoLoggable>(_condition: &str, _name: &str, _v: T) {}
+
+#[inline(always)]
+pub fn houlog_iter<I>(_name: &str, _iter: I)
+where
+    I: IntoIterator,
+    I::Item: IntoLoggable,
+{
+}
+
+#[inline(always)]
+pub fn houlog_event(_message: impl Into<String>) {}
+
+#[inline(always)]
+pub fn houlog_channel_value(_name: &str, _value: f32) {}
+
+#[inline(always)]
+pub fn houlog_counter(_name: &str, _delta: i64) {}
+
+#[inline(always)]
+pub fn houlog_gauge(_name: &str, _value: f64) {}
+
+#[inline(always)]
+pub fn houlog_set_enabled(_enabled: bool) {}
+
+#[inline(always)]
+pub fn houlog_is_enabled() -> bool {
+    false
+}
+
+#[inline(always)]
+pub fn houlog_is_initialized() -> bool {
+    false
+}
+
+#[inline(always)]
+pub fn houlog_set_profiling(_profiling: bool) {}
+
+#[inline(always)]
+pub fn houlog_span(_name: &str) -> SpanGuard {
+    SpanGuard
+}
+
+#[must_use = "the span ends as soon as the guard is dropped"]
+pub struct SpanGuard;
+
+#[inline(always)]
+pub fn houlog_next_frame() {}
+
+#[inline(always)]
+pub fn houlog_set_frame_mode(_mode: FrameMode) {}
+
+#[inline(always)]
+pub fn try_houlog_next_frame() -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_frame_scope(_label: &str) -> FrameScope {
+    FrameScope
+}
+
+#[must_use = "the frame scope ends as soon as the guard is dropped"]
+pub struct FrameScope;
+
+#[inline(always)]
+pub fn houlog_set_frame(_frame: u32) {}
+
+#[inline(always)]
+pub fn try_houlog_set_frame(_frame: u32) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_set_thread_frame(_frame: u32) {}
+
+#[inline(always)]
+pub fn houlog_clear_thread_frame() {}
+
+#[inline(always)]
+pub fn houlog_set_frame_time(_seconds: f32) {}
+
+#[inline(always)]
+pub fn try_houlog_set_frame_time(_seconds: f32) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_next_frame_named(_label: &str) {}
+
+#[inline(always)]
+pub fn try_houlog_next_frame_named(_label: &str) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn init_houlog(_path: impl Into<PathBuf>) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn init_houlog_remote(_address: impl ToSocketAddrs, _source: impl Into<String>) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn init_houlog_memory() -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_exported() -> Recording {
+    Recording::default()
+}
+
+#[inline(always)]
+pub fn houlog_exported_snapshots() -> BTreeMap<String, Recording> {
+    BTreeMap::new()
+}
+
+#[inline(always)]
+pub fn init_houlog_stream(_address: impl ToSocketAddrs) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn init_houlog_live(_options: LiveSessionOptions) -> Result<()> {
+    Ok(())
+}
+
+pub enum HoulogConfig {
+    File(PathBuf),
+    Stream(SocketAddr),
+    Live(LiveSessionOptions),
+    Remote(SocketAddr, String),
+    Memory,
+}
+
+#[inline(always)]
+pub fn with_houlog<R>(_config: HoulogConfig, f: impl FnOnce() -> R) -> Result<R> {
+    Ok(f())
+}
+
+#[derive(Default)]
+pub struct LiveSessionOptions;
+
+impl LiveSessionOptions {
+    #[inline(always)]
+    pub fn with_session(self, session: Session) -> Self {
+        match session {}
+    }
+
+    #[inline(always)]
+    pub fn with_path(self, _path: impl Into<String>) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_node_name(self, _node_name: impl Into<String>) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_host(self, _host: impl Into<String>, _port: u16) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_check_nodes(self, _check_nodes: bool) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_shared_memory(self, _name: impl Into<String>) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_retries(self, _retries: u32) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_retry_interval(self, _retry_interval: Duration) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_connect_timeout(self, _connect_timeout: Duration) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_wait_for_houdini(self, _wait_for_houdini: bool) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_lazy_connect(self, _lazy_connect: bool) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub fn with_playback(self, _playback: bool) -> Self {
+        self
+    }
+}
+
+#[inline(always)]
+pub fn houlog_set_max_frames(_max_frames: Option<usize>) {}
+
+#[inline(always)]
+pub fn try_houlog_set_max_frames(_max_frames: Option<usize>) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_set_memory_budget(_budget: Option<usize>, _policy: MemoryPolicy) {}
+
+#[inline(always)]
+pub fn try_houlog_set_memory_budget(_budget: Option<usize>, _policy: MemoryPolicy) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_set_spill_threshold(_threshold: Option<usize>) {}
+
+#[inline(always)]
+pub fn try_houlog_set_spill_threshold(_threshold: Option<usize>) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_query(_filter: &HoulogFilter) -> Vec<RecordedPoint> {
+    Vec::new()
+}
+
+#[inline(always)]
+pub fn houlog_stats() -> HoulogStats {
+    HoulogStats::default()
+}
+
+#[inline(always)]
+pub fn houlog_snapshot(_name: &str) {}
+
+#[inline(always)]
+pub fn try_houlog_snapshot(_name: &str) -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_clear() {}
+
+#[inline(always)]
+pub fn try_houlog_clear() -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn houlog_set_drop_behavior(_behavior: DropBehavior) {}
+
+/// Callbacks into the host application. They are never called without the `enabled` feature.
+#[derive(Default)]
+pub struct Hooks {
+    pub on_frame: Option<Box<dyn Fn(u32) + Send + Sync>>,
+    pub on_save: Option<Box<dyn Fn() + Send + Sync>>,
+    pub on_error: Option<ErrorHook>,
+}
+
+pub type ErrorHook = Box<dyn Fn(&anyhow::Error) + Send + Sync>;
+
+#[inline(always)]
+pub fn houlog_set_hooks(_hooks: Hooks) {}
+
+#[inline(always)]
+pub fn houlog_shutdown() {}
+
+#[inline(always)]
+pub fn try_houlog_shutdown() -> Result<()> {
+    Ok(())
+}
+
+#[inline(always)]
+pub fn save_houlog() {}
+
+#[inline(always)]
+pub fn try_save_houlog() -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+/// What to do with the recording when the program exits, see
+/// [`houlog_set_drop_behavior`](crate::houlog_set_drop_behavior()) and
+/// [`houlog_shutdown`](crate::houlog_shutdown()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DropBehavior {
+    /// Save the recording and wait until saving is done, however long that takes. This can hang if
+    /// Houdini has gone away.
+    #[default]
+    Save,
+    /// Save the recording, but give up after the given time and let the program exit anyway.
+    SaveWithTimeout(Duration),
+    /// Don't save anything, only what has been saved via [`save_houlog`](crate::save_houlog())
+    /// ends up in Houdini.
+    Nothing,
+}
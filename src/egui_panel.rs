@@ -0,0 +1,99 @@
+//! A ready-made egui panel for controlling the logger at runtime, see [`HoulogPanel`].
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::houdini_debug_logger::*;
+use crate::stats::HoulogStats;
+
+/// How often the statistics shown in the panel are recomputed, since that walks all entries.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// An egui panel showing whether the logger is recording, the number of frames and entries, the
+/// memory usage and a toggle for each channel, with buttons to save and clear the recording. Keep
+/// it around between frames and draw it into an existing window via [`HoulogPanel::ui`], or into
+/// its own window via [`HoulogPanel::show`].
+///
+/// ```no_run
+/// # use houdini_debug_logger::HoulogPanel;
+/// # let ctx = egui::Context::default();
+/// let mut panel = HoulogPanel::default();
+/// // Every frame:
+/// panel.show(&ctx);
+/// ```
+#[derive(Debug, Default)]
+pub struct HoulogPanel {
+    stats: HoulogStats,
+    channels: BTreeMap<String, bool>,
+    refreshed: Option<Instant>,
+    /// The error of the last Save or Clear, shown until the next one succeeds.
+    error: Option<String>,
+}
+
+impl HoulogPanel {
+    /// Draws the panel into its own window.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Houdini Debug Logger").show(ctx, |ui| self.ui(ui));
+    }
+
+    /// Draws the panel into the given ui.
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if !houlog_is_initialized() {
+            ui.label("Not initialized");
+            return;
+        }
+        let stale = match self.refreshed {
+            Some(refreshed) => refreshed.elapsed() >= REFRESH_INTERVAL,
+            None => true,
+        };
+        if stale {
+            self.refresh();
+        }
+
+        let mut enabled = houlog_is_enabled();
+        if ui.checkbox(&mut enabled, "Recording").changed() {
+            houlog_set_enabled(enabled);
+        }
+        ui.label(format!("Frames: {}", self.stats.frames));
+        ui.label(format!("Entries: {}", self.stats.entries));
+        ui.label(format!(
+            "Memory: {:.1} MB",
+            self.stats.memory as f64 / (1024.0 * 1024.0)
+        ));
+
+        if !self.channels.is_empty() {
+            ui.separator();
+            ui.label("Channels");
+            for (channel, enabled) in &mut self.channels {
+                if ui.checkbox(enabled, channel.as_str()).changed() {
+                    if *enabled {
+                        houlog_enable_channel(channel);
+                    } else {
+                        houlog_disable_channel(channel);
+                    }
+                }
+            }
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("Save").clicked() {
+                self.error = try_save_houlog().err().map(|e| format!("{:#}", e));
+                self.refreshed = None;
+            }
+            if ui.button("Clear").clicked() {
+                self.error = try_houlog_clear().err().map(|e| format!("{:#}", e));
+                self.refreshed = None;
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.stats = houlog_stats();
+        self.channels = houlog_channels();
+        self.refreshed = Some(Instant::now());
+    }
+}
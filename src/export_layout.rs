@@ -0,0 +1,20 @@
+/// How the entries are laid out in the exported geometry, see
+/// [`houlog_set_export_layout`](crate::houlog_set_export_layout()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ExportLayout {
+    /// All entries are loose points, and frames are told apart by the `time` attribute.
+    #[default]
+    Merged = 0,
+    /// Every frame is a part of its own: a packed primitive holding the points of the frame, in a
+    /// primitive group named after the `time` of the frame (`frame_1`, `frame_2`, ...). Selecting a
+    /// frame then only needs to pick its primitive instead of filtering every point, which is a lot
+    /// faster for recordings with 100k+ points per frame. Entries that don't belong to a frame, such
+    /// as the channel plots, are in `frame_0`, which comes last. Within a part, the points are
+    /// still in the point group of their frame.
+    ///
+    /// The input geometry of Houdini Engine can only hold a single part, so the points are packed
+    /// by a Python SOP behind the exported node (`<node>__pack` in a live session). With
+    /// [`LiveSessionOptions::with_playback`](crate::LiveSessionOptions::with_playback), which only
+    /// exports one frame at a time, the points aren't packed.
+    PerFrame = 1,
+}
@@ -0,0 +1,14 @@
+/// How the logger advances to the next frame, see
+/// [`houlog_set_frame_mode`](crate::houlog_set_frame_mode()).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FrameMode {
+    /// Frames only advance via [`houlog_next_frame`](crate::houlog_next_frame()) and the other
+    /// frame functions.
+    #[default]
+    Manual,
+    /// Frames advance automatically with the wall clock, `fps` times per second since the logger
+    /// was initialized. This is meant for applications without a clean tick loop, such as editors
+    /// or async servers. Frames can still be advanced manually, which starts the next frame early.
+    /// `fps` has to be positive and finite.
+    Realtime { fps: f32 },
+}
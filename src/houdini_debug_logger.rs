@@ -1,411 +1,4798 @@
-use std::net::{Ipv4Addr, SocketAddrV4};
-use std::path::PathBuf;
-use std::sync::{Mutex, OnceLock};
+use std::cell::{Cell, RefCell};
+use std::collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::ops::Range;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{
+    AtomicBool, AtomicI64, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering,
+};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock, RwLockReadGuard};
+use std::thread::{JoinHandle, ThreadId};
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::loggable::DebugLoggable;
+use crate::attribute_names::AttributeNames;
+use crate::drop_behavior::DropBehavior;
+use crate::export_layout::ExportLayout;
+use crate::frame_mode::FrameMode;
+use crate::level::Level;
+use crate::loggable::{
+    AssertFailed, DebugLoggable, Event, IntoLoggable, Metric, Moving, Polyline, ProfileBar, Span,
+    DEFAULT_PSCALE,
+};
+use crate::memory_policy::MemoryPolicy;
+use crate::metadata_format::MetadataFormat;
+use crate::query::HoulogFilter;
+use crate::recording::{RecordedPoint, Recording};
+use crate::stats::{EntryStats, HoulogStats};
+#[cfg(feature = "websocket")]
+use crate::websocket::WebSocketServer;
 use anyhow::{anyhow, Result};
-use hapi_rs::attribute::{AttributeInfo, StorageType};
-use hapi_rs::enums::{AttributeOwner, AttributeTypeInfo, PartType};
+use glam::{Quat, Vec3};
+#[cfg(feature = "enabled")]
+use hapi_rs::attribute::{
+    AttribAccess, AttributeInfo, DictionaryAttr, NumericAttr, StorageType, StringAttr,
+};
+#[cfg(feature = "enabled")]
+use hapi_rs::enums::{AttributeOwner, AttributeTypeInfo, GroupType, PartType};
+#[cfg(feature = "enabled")]
 use hapi_rs::geometry::PartInfo;
+#[cfg(feature = "enabled")]
 use hapi_rs::node::{Geometry, HoudiniNode};
-use hapi_rs::session::{connect_to_socket, quick_session, Session};
+#[cfg(feature = "enabled")]
+use hapi_rs::parameter::Parameter;
+#[cfg(feature = "enabled")]
+use hapi_rs::session::{
+    connect_to_memory_server, connect_to_socket, quick_session, Session, TimelineOptions,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
-/// Trait that can be implemented for converting any types into a loggable type. Theoretically,
-/// DebugLoggable could be used instead, but that would require making the HDA aware of the new type.
-/// Using this one instead is typically preferred and mostly just a convenience helper to be able
-/// to pass custom types directly into [`houlog`].
-pub trait IntoLoggable {
-    /// The loggable type that this type can be converted into.
-    type LoggableType: DebugLoggable + 'static;
+/// Stand-in for the hapi-rs session, which isn't available without the `enabled` feature. It can't
+/// be constructed, so [`LiveSessionOptions::with_session`] can never be called.
+#[cfg(not(feature = "enabled"))]
+pub enum Session {}
 
-    /// Convert the type into a loggable type.
-    fn into_loggable(self) -> Self::LoggableType;
+/// The main logging function. Please note that this currently operates on global state.
+/// Errors are printed instead of being returned, so that a failure in the logger can't abort the
+/// program that is being debugged. Use [`try_houlog`] to handle them instead.
+#[track_caller]
+pub fn houlog<T: IntoLoggable>(name: &str, v: T) {
+    try_houlog(name, v).unwrap_or_else(warn);
 }
 
-impl<T: DebugLoggable + 'static> IntoLoggable for T {
-    type LoggableType = T;
-    fn into_loggable(self) -> Self::LoggableType {
-        self
-    }
+/// Like [`houlog`], but returns errors instead of printing them.
+#[track_caller]
+pub fn try_houlog<T: IntoLoggable>(name: &str, v: T) -> Result<()> {
+    let Some(logger) = enabled_logger() else {
+        return Ok(());
+    };
+    logger.log(logger.entry(name, v.into_loggable()))
 }
 
-/// The main logging function. Please note that this currently operates on global state.
-pub fn houlog<T: IntoLoggable>(name: &str, v: T) {
-    let logger = match HOUDINI_DEBUG_LOGGER.get() {
-        Some(logger) => logger,
-        None => {
-            println!("HoudiniDebugLogger not initialized");
-            return;
-        }
+/// Like [`houlog`], but logs into the given (0-based) frame without changing the current frame.
+/// Useful when a worker thread finishes computing data for a simulation step after the main thread
+/// has already advanced. Only the frames that entries are logged into are created, so far-away
+/// frames don't allocate the frames in between.
+#[track_caller]
+pub fn houlog_at_frame<T: IntoLoggable>(frame: u32, name: &str, v: T) {
+    let Some(logger) = enabled_logger() else {
+        return;
     };
-    logger.log(name, v.into_loggable()).unwrap();
+    logger
+        .log_to_frame(frame as usize, logger.entry(name, v.into_loggable()))
+        .unwrap_or_else(warn);
 }
 
-/// Advance the logger to the next frame. When first initializing the logger, it starts on frame 0,
-/// so typically this is only needed when you want to log data for multiple frames.
-/// This is the frames in the recording, it does not have to be actual frames in your code. For
-/// example, a world generation algorithm could separate the different stages of the generation into
-/// different frames.
-pub fn houlog_next_frame() -> Result<()> {
-    let logger = match HOUDINI_DEBUG_LOGGER.get() {
-        Some(logger) => logger,
-        None => {
-            println!("HoudiniDebugLogger not initialized");
-            return Ok(());
-        }
-    };
-    logger.next_frame()
+/// Like [`houlog`], but with the velocity of the value, which is exported as the `v` attribute.
+/// This is a shorthand for `houlog(name, Moving { value, velocity })`, see [`Moving`].
+#[track_caller]
+pub fn houlog_with_velocity<T: IntoLoggable>(name: &str, v: T, velocity: Vec3) {
+    houlog(
+        name,
+        Moving {
+            value: v.into_loggable(),
+            velocity,
+        },
+    );
 }
 
-/// This initializes houlog to write to a file. Typically, you'd want to use [`init_houlog_live`]
-/// instead which gives immediate feedback without needing to manually reload.
-pub fn init_houlog(path: impl Into<PathBuf>) -> Result<()> {
-    HOUDINI_DEBUG_LOGGER
-        .set(HoudiniDebugLogger::new_with_file(path.into()))
-        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))
+/// Like [`houlog`], but replaces the entry with the same name that has been logged in the current
+/// frame, if any. Values that are updated many times per frame, for example in an iterative
+/// solver, then end up as one final entry instead of dozens of overlapping ones. To see every
+/// iteration while debugging the solver, see [`houlog_set_keep_replaced`].
+#[track_caller]
+pub fn houlog_replace<T: IntoLoggable>(name: &str, v: T) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    logger
+        .replace(logger.entry(name, v.into_loggable()))
+        .unwrap_or_else(warn);
 }
 
-/// This initializes houlog to write to a live Houdini session. If you're already attached to a
-/// session for a different purpose (for example live-reloading), you can pass it in here.
-/// You must have a live session running in Houdini which you can start via the
-/// "Houdini Engine SessionSync" pane tab (which can be found clicking on the + and then under New Pane Tab Type -> Misc).
-pub fn init_houlog_live(session: Option<Session>) -> Result<()> {
-    HOUDINI_DEBUG_LOGGER
-        .set(HoudiniDebugLogger::new_with_live_session(session)?)
-        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))
+/// Make [`houlog_replace`] keep all entries like [`houlog`] does, instead of replacing them. Off by
+/// default.
+pub fn houlog_set_keep_replaced(keep: bool) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.keep_replaced.store(keep, Ordering::Relaxed);
 }
 
-/// Save the session and send it to Houdini.
-pub fn save_houlog() -> Result<()> {
-    let logger = match HOUDINI_DEBUG_LOGGER.get() {
-        Some(logger) => logger,
-        None => {
-            println!("HoudiniDebugLogger not initialized");
-            return Ok(());
-        }
+/// Like [`houlog`], but tags the entry with a stable id, exported as the `id` attribute. This lets
+/// Houdini track the same entity across frames (for example for motion trails), even when the
+/// order in which entries are logged changes. Entries without an id get `-1`.
+#[track_caller]
+pub fn houlog_id<T: IntoLoggable>(id: u64, name: &str, v: T) {
+    let Some(logger) = enabled_logger() else {
+        return;
     };
-    logger.save()
+    logger
+        .log(LogEntry {
+            id: Some(id),
+            ..logger.entry(name, v.into_loggable())
+        })
+        .unwrap_or_else(warn);
 }
 
-static HOUDINI_DEBUG_LOGGER: OnceLock<HoudiniDebugLogger> = OnceLock::new();
+/// Like [`houlog`], but with an explicit severity level. Entries below the minimum level (see
+/// [`houlog_set_min_level`]) are skipped without doing any work. The level is exported as the
+/// `level` attribute.
+#[track_caller]
+pub fn houlog_at<T: IntoLoggable>(level: Level, name: &str, v: T) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger.is_level_enabled(level) {
+        return;
+    }
+    logger
+        .log(LogEntry {
+            level,
+            ..logger.entry(name, v.into_loggable())
+        })
+        .unwrap_or_else(warn);
+}
 
-/// The method of exporting the data. This can either be a live session or a file.
-pub enum ExportMethod {
-    LiveSession {
-        /// The hapi-rs session to use.
-        session: Session,
+/// Set the minimum level of entries that are recorded. Defaults to [`Level::Debug`], so
+/// [`Level::Trace`] entries are disabled unless explicitly enabled.
+pub fn houlog_set_min_level(level: Level) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.min_level.store(level as u8, Ordering::Relaxed);
+}
 
-        /// The path to the subnet in which the node will be stored
-        path: String,
+/// Set the `pscale` attribute of entries that don't have a size of their own (see
+/// [`DebugLoggable::pscale`](crate::DebugLoggable::pscale)), which is how big their points are
+/// drawn in the viewport. Defaults to 0.1. Only affects entries logged afterwards.
+pub fn houlog_set_default_pscale(pscale: f32) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger
+        .default_pscale
+        .store(pscale.to_bits(), Ordering::Relaxed);
+}
 
-        /// The name of the node
-        node_name: String,
-    },
-    File {
-        /// The full filepath to the file to be created. Typically, this should end with `.bgeo`.
-        path: PathBuf,
-    },
+/// Set how the metadata of each entry is exported. Defaults to [`MetadataFormat::Json`].
+pub fn houlog_set_metadata_format(format: MetadataFormat) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger
+        .metadata_format
+        .store(format as u8, Ordering::Relaxed);
 }
 
-struct LogEntry {
-    name: String,
-    value: Box<dyn DebugLoggable>,
+/// Set how the entries are laid out in the exported geometry. Defaults to
+/// [`ExportLayout::Merged`]. Only affects exports to Houdini, not streaming.
+pub fn houlog_set_export_layout(layout: ExportLayout) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.export_layout.store(layout as u8, Ordering::Relaxed);
 }
 
-struct FrameData {
-    entries: Vec<LogEntry>,
+/// Turn on exporting the actual geometry of complex entries such as meshes along with them, see
+/// [`DebugLoggable::geometry`]. It's turned into a packed primitive on the point of each entry,
+/// which can be unpacked in Houdini when needed, while the layout of the points stays the same.
+///
+/// When streaming, the Python receiver builds the packed primitives. The input geometry of Houdini
+/// Engine can't hold packed primitives, so with [`init_houlog`] and [`init_houlog_live`] the
+/// geometry is exported as the `houlog_geometry` point attribute instead, which a Python SOP behind
+/// the exported node turns into the packed primitives, like for [`ExportLayout::PerFrame`]. With
+/// [`LiveSessionOptions::with_playback`], the geometry stays in that attribute. With
+/// [`init_houlog_memory`], it ends up in [`RecordedPoint::geometry`].
+pub fn houlog_set_stream_geometry(enabled: bool) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.stream_geometry.store(enabled, Ordering::Relaxed);
+    logger.stream_resend.store(true, Ordering::Relaxed);
 }
 
-impl FrameData {
-    fn new() -> Self {
-        FrameData {
-            entries: Vec::new(),
-        }
+/// Declare the frame rate at which the application captures frames, and the number of its first
+/// captured frame. Both are exported as the `fps` and `start_frame` detail attributes, which take
+/// precedence over globals of the same name. The `time` of the exported entries is offset so that
+/// the first frame of the recording is at `start_frame`. With [`init_houlog_live`], the frame rate
+/// and the frame range of the scene are also set to match the recording, so that it plays back at
+/// the right speed.
+///
+/// The frame rate has to be positive, and the start frame at least 1, since the channel plots are
+/// at frame 0.
+pub fn houlog_set_timeline(fps: f32, start_frame: i64) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    if !fps.is_finite() || fps <= 0.0 {
+        return warn(anyhow!("Invalid frame rate {}", fps));
+    }
+    if start_frame < 1 {
+        return warn(anyhow!("Invalid start frame {}", start_frame));
     }
+    logger.fps.store(fps.to_bits(), Ordering::Relaxed);
+    logger.start_frame.store(start_frame, Ordering::Relaxed);
+    logger.stream_resend.store(true, Ordering::Relaxed);
+    lock(&logger.data).modified = true;
 }
 
-struct LoggerData {
-    modified: bool,
-    frames: Vec<FrameData>,
+/// Set the names of the exported attributes, for example to add a prefix so that they don't clash
+/// with the attributes of a pipeline. Only affects exports to Houdini, not streaming. The renamed
+/// attributes are recorded in the `houlog_attribute_names` detail attribute, so that
+/// [`Recording::load`] and the viewer HDA still find them. Fails if a name isn't a valid attribute
+/// name or two built-in attributes would end up with the same name.
+/// Errors are printed instead of being returned, use [`try_houlog_set_attribute_names`] to handle them.
+pub fn houlog_set_attribute_names(names: AttributeNames) {
+    try_houlog_set_attribute_names(names).unwrap_or_else(warn);
 }
 
-struct HoudiniDebugLogger {
-    data: Mutex<LoggerData>,
-    export_method: ExportMethod,
+/// Like [`houlog_set_attribute_names`], but returns errors instead of printing them.
+pub fn try_houlog_set_attribute_names(names: AttributeNames) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    validate_attribute_names(&names)?;
+    *lock(&logger.attribute_names) = names;
+    Ok(())
 }
 
-impl HoudiniDebugLogger {
-    fn new_with_file(p: PathBuf) -> Self {
-        HoudiniDebugLogger {
-            export_method: ExportMethod::File { path: p },
-            data: Mutex::new(LoggerData {
-                modified: true,
-                frames: vec![FrameData::new()],
-            }),
-        }
+/// Set a value that describes the whole recording, such as the build hash, the random seed or the
+/// name of the map. Globals are exported as detail attributes, so that the recording stays
+/// self-describing when it ends up on someone else's machine. Setting a key again replaces its
+/// value.
+pub fn houlog_set_global(key: &str, value: impl Into<Value>) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    lock(&logger.globals).insert(key.to_string(), value.into());
+    lock(&logger.data).modified = true;
+}
+
+/// Like [`houlog`], but logs the value into a channel, for example `"physics"` or `"ai"`. The
+/// channel is exported as the `channel` attribute. Channels can be turned off at runtime using
+/// [`houlog_disable_channel`], in which case logging into them does no work at all.
+#[track_caller]
+pub fn houlog_in<T: IntoLoggable>(channel: &str, name: &str, v: T) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger.is_channel_enabled(channel) {
+        return;
     }
+    logger
+        .log(LogEntry {
+            channel: Some(channel.to_string()),
+            ..logger.entry(name, v.into_loggable())
+        })
+        .unwrap_or_else(warn);
+}
 
-    fn new_with_live_session(session: Option<Session>) -> Result<Self> {
-        let session = match session {
-            Some(session) => session,
-            None => {
-                let socket = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 9090);
-                connect_to_socket(socket, None)?
-            }
-        };
+/// Enable a channel that was previously disabled via [`houlog_disable_channel`]. All channels
+/// start out enabled.
+pub fn houlog_enable_channel(channel: &str) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger
+        .set_channel_enabled(channel, true)
+        .unwrap_or_else(warn);
+}
 
-        Ok(HoudiniDebugLogger {
-            export_method: ExportMethod::LiveSession {
-                session,
-                path: "/obj/recordings".to_string(),
-                node_name: "recording".to_string(),
-            },
-            data: Mutex::new(LoggerData {
-                modified: true,
-                frames: vec![FrameData::new()],
-            }),
-        })
-    }
+/// Disable a channel, so that everything logged into it via [`houlog_in`] is skipped.
+pub fn houlog_disable_channel(channel: &str) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger
+        .set_channel_enabled(channel, false)
+        .unwrap_or_else(warn);
+}
 
-    fn next_frame(&self) -> Result<()> {
-        let mut data = self.data.lock().map_err(|_| anyhow!("error during lock"))?;
-        data.modified = true;
-        data.frames.push(FrameData::new());
-        Ok(())
-    }
+/// The channels that have entries in the recording that is currently in memory or have been
+/// disabled, and whether each of them is enabled.
+pub fn houlog_channels() -> BTreeMap<String, bool> {
+    let Some(logger) = logger() else {
+        return BTreeMap::new();
+    };
+    logger.channels()
+}
 
-    fn log<T: DebugLoggable + 'static>(&self, name: &str, v: T) -> Result<()> {
-        let mut data = self.data.lock().map_err(|_| anyhow!("error during lock"))?;
-        data.modified = true;
-        let frame_data = data
-            .frames
-            .last_mut()
-            .ok_or_else(|| anyhow!("For some reason no active frame was found"))?;
-        frame_data.entries.push(LogEntry {
-            name: name.to_string(),
-            value: Box::new(v),
-        });
-        Ok(())
+/// Like [`houlog`], but logs a given name at most once per `interval_secs` seconds of `time_secs`,
+/// dropping all calls in between. `time_secs` is the caller's own clock, typically the simulation
+/// time, so that the same entries are kept on every run instead of depending on how long each step
+/// took. Useful for data that is produced every physics step, for example
+/// `houlog_sampled("velocity", v, sim_time, 1.0 / 60.0)`. If the time goes backwards, for example
+/// when a replay is rewound, the next call is logged.
+#[track_caller]
+pub fn houlog_sampled<T: IntoLoggable>(name: &str, v: T, time_secs: f32, interval_secs: f32) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger
+        .sample_interval(name, time_secs, interval_secs)
+        .unwrap_or_else(warn)
+    {
+        return;
     }
+    logger
+        .log(logger.entry(name, v.into_loggable()))
+        .unwrap_or_else(warn);
+}
 
-    fn save(&self) -> Result<()> {
-        let mut data = self.data.lock().map_err(|_| anyhow!("error during lock"))?;
-        if !data.modified {
-            // Avoid saving overly often
-            return Ok(());
-        }
-        data.modified = false;
-
-        let node = Self::create_output_node(&self.export_method)?;
-        node.cook()?;
-        let geom = node
-            .geometry()?
-            .ok_or_else(|| anyhow!("No geometry on node"))?;
+/// Like [`houlog`], but only logs every `n`th call for a given name, starting with the first one.
+#[track_caller]
+pub fn houlog_every_n<T: IntoLoggable>(name: &str, v: T, n: u64) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger.sample_every_n(name, n).unwrap_or_else(warn) {
+        return;
+    }
+    logger
+        .log(logger.entry(name, v.into_loggable()))
+        .unwrap_or_else(warn);
+}
 
-        let num_points = data
-            .frames
-            .iter()
-            .map(|frame| frame.entries.len())
-            .sum::<usize>();
+/// Start building an entry with additional per-entry attributes, which are exported as their own
+/// point attributes so they can be used for filtering and coloring in Houdini:
+///
+/// ```no_run
+/// # use glam::Vec3;
+/// # use houdini_debug_logger::*;
+/// # let position = Vec3::ZERO;
+/// houlog_entry("agent", position)
+///     .attr_f32("speed", 4.2)
+///     .attr_str("state", "chase")
+///     .commit();
+/// ```
+///
+/// Entries that don't set an attribute get `0` or an empty string for it.
+#[track_caller]
+pub fn houlog_entry<T: IntoLoggable>(name: &str, v: T) -> EntryBuilder {
+    // Closures don't forward the caller location, so it has to be captured up front.
+    let location = Location::caller();
+    EntryBuilder {
+        entry: enabled_logger().map(|logger| {
+            let entry = LogEntry {
+                location: location.into(),
+                ..logger.entry(name, v.into_loggable())
+            };
+            (logger, entry)
+        }),
+    }
+}
 
-        let part_info = PartInfo::default()
-            .with_part_type(PartType::Mesh)
-            .with_point_count(num_points as i32);
+/// Builder for an entry with custom attributes, see [`houlog_entry`].
+#[must_use = "the entry is only logged once `commit` is called"]
+pub struct EntryBuilder {
+    entry: Option<(Arc<HoudiniDebugLogger>, LogEntry)>,
+}
 
-        geom.set_part_info(&part_info)?;
+impl EntryBuilder {
+    /// Add a float attribute.
+    pub fn attr_f32(self, name: &str, value: f32) -> Self {
+        self.with_attribute(name, AttributeValue::Float(value))
+    }
 
-        Self::add_positions(&geom, &data.frames)?;
-        Self::add_names(&geom, &data.frames)?;
-        Self::add_frame_times(&geom, &data.frames)?;
-        Self::add_metadata(&geom, &data.frames)?;
-        Self::add_kinds(&geom, &data.frames)?;
+    /// Add an integer attribute.
+    pub fn attr_i32(self, name: &str, value: i32) -> Self {
+        self.with_attribute(name, AttributeValue::Int(value))
+    }
 
-        geom.commit()?;
+    /// Add a string attribute.
+    pub fn attr_str(self, name: &str, value: &str) -> Self {
+        self.with_attribute(name, AttributeValue::String(value.to_string()))
+    }
 
-        if let ExportMethod::File { path } = &self.export_method {
-            geom.save_to_file(
-                path.to_str()
-                    .ok_or_else(|| anyhow!("Could not convert path to string"))?,
-            )?;
+    /// Log the entry.
+    pub fn commit(self) {
+        if let Some((logger, entry)) = self.entry {
+            logger.log(entry).unwrap_or_else(warn);
         }
-
-        Ok(())
     }
 
-    fn add_positions(geom: &Geometry, frames: &[FrameData]) -> Result<()> {
-        let point_positions = frames
-            .iter()
-            .flat_map(|frame| frame.entries.iter().map(|entry| entry.value.position()))
-            .flat_map(|v| vec![v.x, v.y, v.z])
-            .collect::<Vec<f32>>();
-
-        let p_attr_info = AttributeInfo::default()
-            .with_count(point_positions.len() as i32 / 3)
-            .with_tuple_size(3)
-            .with_storage(StorageType::Float)
-            .with_type_info(AttributeTypeInfo::Point)
-            .with_owner(AttributeOwner::Point);
-
-        let p_attrib = geom.add_numeric_attribute::<f32>("P", 0, p_attr_info)?;
-
-        if !point_positions.is_empty() {
-            p_attrib.set(0, &point_positions)?;
+    fn with_attribute(mut self, name: &str, value: AttributeValue) -> Self {
+        if let Some((_, entry)) = &mut self.entry {
+            entry.attributes.push((name.to_string(), value));
         }
-
-        Ok(())
+        self
     }
+}
 
-    fn add_names(geom: &Geometry, frames: &[FrameData]) -> Result<()> {
-        let point_names = frames
-            .iter()
-            .flat_map(|frame| frame.entries.iter().map(|entry| entry.name.clone()))
-            .collect::<Vec<String>>();
-
-        let name_attr_info = AttributeInfo::default()
-            .with_count(point_names.len() as i32)
-            .with_tuple_size(1)
-            .with_storage(StorageType::String)
-            .with_owner(AttributeOwner::Point);
+/// Like [`houlog`], but only logs the first time this call site is reached (until the recording is
+/// cleared). Useful for static level geometry and configuration that shouldn't be duplicated every
+/// tick.
+#[track_caller]
+pub fn houlog_once<T: IntoLoggable>(name: &str, v: T) {
+    let location = Location::caller();
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger.first_call(location, false).unwrap_or_else(warn) {
+        return;
+    }
+    logger
+        .log(logger.entry(name, v.into_loggable()))
+        .unwrap_or_else(warn);
+}
 
-        let name_attrib = geom.add_string_attribute("name", 0, name_attr_info)?;
+/// Like [`houlog_once`], but logs once per frame instead of once per recording.
+#[track_caller]
+pub fn houlog_once_per_frame<T: IntoLoggable>(name: &str, v: T) {
+    let location = Location::caller();
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger.first_call(location, true).unwrap_or_else(warn) {
+        return;
+    }
+    logger
+        .log(logger.entry(name, v.into_loggable()))
+        .unwrap_or_else(warn);
+}
 
-        if !point_names.is_empty() {
-            name_attrib.set(
-                0,
-                point_names
-                    .iter()
-                    .map(|name| name.as_str())
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            )?;
-        }
+/// Set whether a failing [`houassert!`](crate::houassert!) panics after saving the recording.
+/// Defaults to `true`.
+pub fn houlog_set_assert_panics(panics: bool) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.assert_panics.store(panics, Ordering::Relaxed);
+}
 
-        Ok(())
+/// Called by [`houassert!`](crate::houassert!) when the condition doesn't hold.
+#[doc(hidden)]
+#[track_caller]
+pub fn __houassert_failed<T: IntoLoggable>(condition: &str, name: &str, v: T) {
+    let logger = logger();
+    if let Some(logger) = &logger {
+        let value = AssertFailed {
+            condition: condition.to_string(),
+            value: Box::new(v.into_loggable()),
+        };
+        logger.log(logger.entry(name, value)).unwrap_or_else(warn);
+        logger
+            .save()
+            .unwrap_or_else(|e| warn(e.context("Failed to save Houdini Debug Log")));
     }
 
-    fn add_kinds(geom: &Geometry, frames: &[FrameData]) -> Result<()> {
-        let point_kinds = frames
-            .iter()
-            .flat_map(|frame| frame.entries.iter().map(|entry| entry.value.kind().clone()))
-            .collect::<Vec<String>>();
-
-        let kind_attr_info = AttributeInfo::default()
-            .with_count(point_kinds.len() as i32)
-            .with_tuple_size(1)
-            .with_storage(StorageType::String)
-            .with_owner(AttributeOwner::Point);
+    let panics = match logger {
+        Some(logger) => logger.assert_panics.load(Ordering::Relaxed),
+        None => true,
+    };
+    if panics {
+        panic!("houassert failed: {}", condition);
+    }
+}
 
-        let kind_attrib = geom.add_string_attribute("kind", 0, kind_attr_info)?;
+/// Log every item of an iterator as its own entry. The index of the item is appended to the name
+/// as a path segment, so logging all agents as `"agent"` results in `"agent/0"`, `"agent/1"`, etc.
+#[track_caller]
+pub fn houlog_iter<I>(name: &str, iter: I)
+where
+    I: IntoIterator,
+    I::Item: IntoLoggable,
+{
+    for (i, v) in iter.into_iter().enumerate() {
+        houlog(&format!("{}/{}", name, i), v);
+    }
+}
 
-        if !point_kinds.is_empty() {
-            kind_attrib.set(
-                0,
-                point_kinds
-                    .iter()
-                    .map(|name| name.as_str())
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            )?;
-        }
+/// Log an [`Event`] marker on the current frame. This is a shorthand for
+/// `houlog("event", Event { message })`.
+#[track_caller]
+pub fn houlog_event(message: impl Into<String>) {
+    houlog(
+        "event",
+        Event {
+            message: message.into(),
+        },
+    );
+}
 
-        Ok(())
+/// Logs an [`Event`] into the `log` channel for [`HoulogAppender`](crate::HoulogAppender), with
+/// the source location of the `log` call if it's known.
+#[cfg(feature = "log")]
+pub(crate) fn log_record_event(name: &str, message: String, location: Option<(&'static str, u32)>) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    if !logger.is_channel_enabled("log") {
+        return;
+    }
+    let mut entry = LogEntry {
+        channel: Some("log".to_string()),
+        ..logger.entry(name, Event { message })
+    };
+    if let Some((file, line)) = location {
+        entry.location = SourceLocation { file, line };
     }
+    logger.log(entry).unwrap_or_else(warn);
+}
 
-    fn add_frame_times(geom: &Geometry, frames: &[FrameData]) -> Result<()> {
-        let point_times = frames
-            .iter()
-            .enumerate()
-            .flat_map(|(frame, d)| d.entries.iter().map(move |_| (frame + 1) as f32))
-            .collect::<Vec<f32>>();
+/// Record the value of a scalar channel (for example health, speed or a cost metric) on the current
+/// frame. On export, each channel becomes a single polyline spanning the whole recording, with the
+/// frame on X and the value on Y, so it can be plotted directly in Houdini.
+pub fn houlog_channel_value(name: &str, value: f32) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    logger.log_channel_value(name, value).unwrap_or_else(warn);
+}
 
-        let time_attr_info = AttributeInfo::default()
-            .with_count(point_times.len() as i32)
-            .with_tuple_size(1)
-            .with_storage(StorageType::Float)
-            .with_owner(AttributeOwner::Point);
+/// Increment a counter on the current frame, for example the number of spawned entities. All
+/// increments within a frame are summed up (saturating at the limits of `i64`) and exported as a
+/// single `counter` point per frame. In live sessions, counters and gauges go into a node of their
+/// own next to the recording, named `<node>__metrics`.
+pub fn houlog_counter(name: &str, delta: i64) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    logger
+        .log_metric(name, Metric::Counter(delta))
+        .unwrap_or_else(warn);
+}
 
-        let time_attrib = geom.add_numeric_attribute::<f32>("time", 0, time_attr_info)?;
+/// Set a gauge on the current frame, for example the number of active agents. Only the last value
+/// within a frame is kept and exported as a single `gauge` point per frame.
+pub fn houlog_gauge(name: &str, value: f64) {
+    let Some(logger) = enabled_logger() else {
+        return;
+    };
+    logger
+        .log_metric(name, Metric::Gauge(value))
+        .unwrap_or_else(warn);
+}
 
-        if !point_times.is_empty() {
-            time_attrib.set(0, point_times.as_slice())?;
-        }
+/// Turn logging on or off at runtime, for example from an in-game console. While turned off, all
+/// logging calls return immediately, but the session and everything recorded so far are kept.
+/// Frames still advance, so the recording stays in sync with the application.
+pub fn houlog_set_enabled(enabled: bool) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.enabled.store(enabled, Ordering::Relaxed);
+}
 
-        Ok(())
-    }
+/// Whether logging is turned on, see [`houlog_set_enabled`]. `false` if the logger hasn't been
+/// initialized.
+pub fn houlog_is_enabled() -> bool {
+    current_logger().is_some_and(|logger| logger.enabled.load(Ordering::Relaxed))
+}
 
-    fn add_metadata(geom: &Geometry, frames: &[FrameData]) -> Result<()> {
-        let pt_metadata = frames
-            .iter()
-            .flat_map(|frame| frame.entries.iter().map(|entry| entry.value.as_json()))
-            .collect::<Vec<String>>();
+/// Whether one of the `init_houlog` functions has been called. Unlike the other functions, this
+/// doesn't print a message if it hasn't.
+pub fn houlog_is_initialized() -> bool {
+    current_logger().is_some()
+}
 
-        let metadata_attr_info = AttributeInfo::default()
-            .with_count(pt_metadata.len() as i32)
-            .with_tuple_size(1)
-            .with_storage(StorageType::String)
-            .with_owner(AttributeOwner::Point);
+/// Start timing a section of code. When the returned guard is dropped, a `span` entry with the
+/// start time and the duration is logged into the frame in which the span started, and the
+/// duration is recorded as the scalar channel `span/<name>`, so it can be plotted over the frames
+/// (see [`houlog_channel_value`]). Spans can be nested, the depth and the enclosing span are part
+/// of the metadata.
+///
+/// ```no_run
+/// # use houdini_debug_logger::*;
+/// let _span = houlog_span("pathfinding");
+/// ```
+#[track_caller]
+pub fn houlog_span(name: &str) -> SpanGuard {
+    let location = Location::caller();
+    let span = enabled_logger().map(|logger| {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let (depth, parent) = SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let parent = stack.last().map(|(_, name)| name.clone());
+            stack.push((id, name.to_string()));
+            (stack.len() as u32 - 1, parent)
+        });
+        ActiveSpan {
+            id,
+            frame: logger.cursor().unwrap_or_default(),
+            logger,
+            name: name.to_string(),
+            location,
+            start: Instant::now(),
+            depth,
+            parent,
+        }
+    });
+    SpanGuard { span }
+}
 
-        let name_attrib = geom.add_string_attribute("metadata", 0, metadata_attr_info)?;
+/// Guard returned by [`houlog_span`]. Dropping it also ends the spans that have been started within
+/// it and are still active, so that guards dropped out of order don't mess up the nesting of later
+/// spans. It should be dropped on the thread that started the span, guards dropped on other threads
+/// leave the spans of that thread alone.
+#[must_use = "the span ends as soon as the guard is dropped"]
+pub struct SpanGuard {
+    span: Option<ActiveSpan>,
+}
 
-        if !pt_metadata.is_empty() {
-            name_attrib.set(
-                0,
-                pt_metadata
-                    .iter()
-                    .map(|name| name.as_str())
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            )?;
-        }
+struct ActiveSpan {
+    /// Identifies the span in [`SPAN_STACK`].
+    id: u64,
+    logger: Arc<HoudiniDebugLogger>,
+    name: String,
+    location: &'static Location<'static>,
+    start: Instant,
+    frame: usize,
+    depth: u32,
+    parent: Option<String>,
+}
 
-        Ok(())
-    }
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let Some(span) = self.span.take() else {
+            return;
+        };
+        SPAN_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let depth = span.depth as usize;
+            if stack.get(depth).is_some_and(|&(id, _)| id == span.id) {
+                stack.truncate(depth);
+            }
+        });
 
-    fn create_output_node(export_method: &ExportMethod) -> Result<HoudiniNode> {
-        let node = match export_method {
-            ExportMethod::LiveSession {
-                session,
-                path,
-                node_name,
-            } => {
-                let parent = session.get_node_from_path(path, None)?.unwrap();
-                if let Some(handle) = session.get_node_from_path(node_name, Some(parent.handle))? {
-                    session.delete_node(handle)?;
-                }
-                session
-                    .node_builder("null")
-                    .with_parent(parent)
-                    .with_label(node_name)
-                    .create()?
+        let duration = span.start.elapsed().as_secs_f32();
+        let entry = if span.logger.profiling.load(Ordering::Relaxed) {
+            let track = thread_name();
+            let (frame_start, track_index) = span.logger.profile_track(span.frame, &track);
+            let value = ProfileBar {
+                start: span
+                    .start
+                    .saturating_duration_since(frame_start.unwrap_or(span.start))
+                    .as_secs_f32(),
+                duration,
+                depth: span.depth,
+                track: track.clone(),
+                track_index,
+                parent: span.parent,
+            };
+            LogEntry {
+                location: span.location.into(),
+                attributes: vec![
+                    ("track".to_string(), AttributeValue::String(track)),
+                    ("depth".to_string(), AttributeValue::Int(span.depth as i32)),
+                    ("duration".to_string(), AttributeValue::Float(duration)),
+                ],
+                ..span.logger.entry(&span.name, value)
             }
-            ExportMethod::File { .. } => {
-                let session = quick_session(None)?;
-                let parent = session.create_node("Object/geo")?;
-                session.node_builder("null").with_parent(parent).create()?
+        } else {
+            let value = Span {
+                start: span.start.duration_since(span.logger.created).as_secs_f32(),
+                duration,
+                depth: span.depth,
+                parent: span.parent,
+            };
+            LogEntry {
+                location: span.location.into(),
+                ..span.logger.entry(&span.name, value)
             }
         };
-        Ok(node)
+        span.logger
+            .log_to_frame(span.frame, entry)
+            .and_then(|_| {
+                span.logger
+                    .log_channel_value(&format!("span/{}", span.name), duration)
+            })
+            .unwrap_or_else(|e| warn(e.context("Failed to log Houdini Debug Log span")));
     }
 }
 
-impl Drop for HoudiniDebugLogger {
-    fn drop(&mut self) {
-        self.save().unwrap_or_else(|e| {
-            println!("Failed to save Houdini Debug Log: {}", e);
-        });
-    }
+/// Turn profiling on or off. While it's on, spans (see [`houlog_span`]) are logged as
+/// [`ProfileBar`]s instead, which lay out the spans of each frame as stacked bars per thread, so
+/// the profile can be inspected in the same recording as the spatial data. The bars get the point
+/// attributes `track`, `depth` and `duration` for coloring and filtering them in Houdini.
+pub fn houlog_set_profiling(profiling: bool) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    logger.profiling.store(profiling, Ordering::Relaxed);
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{Line, Polygon, Polyline};
-    use glam::{Mat4, Quat, Vec3};
+/// Advance the logger to the next frame. When first initializing the logger, it starts on frame 0,
+/// so typically this is only needed when you want to log data for multiple frames.
+/// This is the frames in the recording, it does not have to be actual frames in your code. For
+/// example, a world generation algorithm could separate the different stages of the generation into
+/// different frames.
+/// Errors are printed instead of being returned, use [`try_houlog_next_frame`] to handle them.
+pub fn houlog_next_frame() {
+    try_houlog_next_frame().unwrap_or_else(warn);
+}
 
-    #[test]
-    fn test() -> Result<()> {
+/// Like [`houlog_next_frame`], but returns errors instead of printing them.
+pub fn try_houlog_next_frame() -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.next_frame(None)
+}
+
+/// Set how the logger advances to the next frame. Defaults to [`FrameMode::Manual`]. With
+/// [`FrameMode::Realtime`], entries are binned into frames by the time since the logger was
+/// initialized, so [`houlog_next_frame`] doesn't need to be called. The `on_frame` hook isn't
+/// called for frames that are advanced this way. Frame rates that aren't positive are rejected,
+/// keeping the previous mode.
+pub fn houlog_set_frame_mode(mode: FrameMode) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    if let FrameMode::Realtime { fps } = mode {
+        if !fps.is_finite() || fps <= 0.0 {
+            return warn(anyhow!("Invalid frame rate {}", fps));
+        }
+    }
+    let mut data = lock(&logger.data);
+    data.frame_mode = mode;
+    data.clock_origin = logger.created;
+}
+
+/// Advance to a new frame labeled `label` and return a guard that advances to another new frame
+/// when it's dropped, so that everything logged while the guard is alive ends up in its own frame.
+/// Scopes can be nested: when an inner scope ends, the frame that follows it gets the label of the
+/// enclosing scope again.
+///
+/// ```no_run
+/// # use houdini_debug_logger::*;
+/// let _frame = houlog_frame_scope("navgen/relax");
+/// ```
+pub fn houlog_frame_scope(label: &str) -> FrameScope {
+    let logger = logger();
+    if let Some(logger) = &logger {
+        logger.push_frame_scope(label).unwrap_or_else(warn);
+    }
+    FrameScope { logger }
+}
+
+/// Guard returned by [`houlog_frame_scope`].
+#[must_use = "the frame scope ends as soon as the guard is dropped"]
+pub struct FrameScope {
+    logger: Option<Arc<HoudiniDebugLogger>>,
+}
+
+impl Drop for FrameScope {
+    fn drop(&mut self) {
+        if let Some(logger) = &self.logger {
+            logger
+                .pop_frame_scope()
+                .unwrap_or_else(|e| warn(e.context("Failed to end Houdini Debug Log frame scope")));
+        }
+    }
+}
+
+/// Set the frame that subsequent entries are logged into, for example when replaying a network
+/// buffer out of order. Frames are 0-based, and only the frames that entries are logged into are
+/// created.
+/// [`houlog_next_frame`] continues from the frame set here.
+/// Errors are printed instead of being returned, use [`try_houlog_set_frame`] to handle them.
+pub fn houlog_set_frame(frame: u32) {
+    try_houlog_set_frame(frame).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_set_frame`], but returns errors instead of printing them.
+pub fn try_houlog_set_frame(frame: u32) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.set_frame(frame as usize)
+}
+
+/// Set the frame that the calling thread logs into, independently of the frame of other threads.
+/// This lets worker threads log for "their" simulation step while the main thread has already
+/// moved on. Entries of different threads within the same frame are ordered by thread name when
+/// saving, followed by unnamed threads in the order they first logged this way. Worker threads
+/// should be named for a deterministic order.
+pub fn houlog_set_thread_frame(frame: u32) {
+    THREAD_FRAME.with(|f| f.set(Some(frame as usize)));
+}
+
+/// Make the calling thread log into the global current frame again, undoing
+/// [`houlog_set_thread_frame`].
+pub fn houlog_clear_thread_frame() {
+    THREAD_FRAME.with(|f| f.set(None));
+}
+
+/// Record the actual simulation time (in seconds) of the current frame. It's exported as the
+/// `sim_time` attribute in addition to the 1-based `time` frame index, so that recordings with a
+/// variable timestep can be mapped onto Houdini's timeline correctly. Frames without a recorded
+/// time get `0`.
+/// Errors are printed instead of being returned, use [`try_houlog_set_frame_time`] to handle them.
+pub fn houlog_set_frame_time(seconds: f32) {
+    try_houlog_set_frame_time(seconds).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_set_frame_time`], but returns errors instead of printing them.
+pub fn try_houlog_set_frame_time(seconds: f32) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.set_frame_time(seconds)
+}
+
+/// Like [`houlog_next_frame`], but gives the new frame a label, for example the name of a stage
+/// of a generation pipeline. The label is exported as the `frame_label` attribute so that frames
+/// can be selected by name in Houdini.
+/// Errors are printed instead of being returned, use [`try_houlog_next_frame_named`] to handle them.
+pub fn houlog_next_frame_named(label: &str) {
+    try_houlog_next_frame_named(label).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_next_frame_named`], but returns errors instead of printing them.
+pub fn try_houlog_next_frame_named(label: &str) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.next_frame(Some(label.to_string()))
+}
+
+/// This initializes houlog to write to a file. Typically, you'd want to use [`init_houlog_live`]
+/// instead which gives immediate feedback without needing to manually reload.
+pub fn init_houlog(path: impl Into<PathBuf>) -> Result<()> {
+    HOUDINI_DEBUG_LOGGER
+        .set(Arc::new(HoudiniDebugLogger::new_with_file(path.into())?))
+        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))
+}
+
+/// This initializes houlog to stream the recording over a plain TCP connection to the Python
+/// receiver in [`HOULOG_PYTHON_RECEIVER`](crate::HOULOG_PYTHON_RECEIVER), which rebuilds the
+/// geometry inside Houdini. Unlike the other methods, this doesn't require a Houdini Engine license.
+pub fn init_houlog_stream(address: impl ToSocketAddrs) -> Result<()> {
+    let address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("Could not resolve address"))?;
+    HOUDINI_DEBUG_LOGGER
+        .set(Arc::new(HoudiniDebugLogger::new_with_stream(address)))
+        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))
+}
+
+/// This initializes houlog to send the recording to a [`HoulogServer`](crate::HoulogServer) (for
+/// example `houlog-cli serve`), which merges the recordings of multiple processes into one. This
+/// lets headless runs, for example on cloud machines, stream into a workstation collecting the
+/// recording. `source` names this process in the combined recording, for example `"server"`.
+pub fn init_houlog_remote(address: impl ToSocketAddrs, source: impl Into<String>) -> Result<()> {
+    let address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("Could not resolve address"))?;
+    HOUDINI_DEBUG_LOGGER
+        .set(Arc::new(HoudiniDebugLogger::new_remote(
+            address,
+            source.into(),
+        )))
+        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))
+}
+
+/// This initializes houlog to keep what would be exported in memory instead of sending it to
+/// Houdini, so logging can be unit-tested without Houdini or a network connection. Each save
+/// replaces the recording returned by [`houlog_exported`]. This only needs the `memory` feature,
+/// so it also works without Houdini installed.
+///
+/// ```no_run
+/// # use glam::Vec3;
+/// # use houdini_debug_logger::*;
+/// init_houlog_memory()?;
+/// houlog("target", Vec3::new(1.0, 0.0, 1.0));
+/// save_houlog();
+/// houlog_exported().assert_logged("target");
+/// # anyhow::Ok(())
+/// ```
+pub fn init_houlog_memory() -> Result<()> {
+    HOUDINI_DEBUG_LOGGER
+        .set(Arc::new(HoudiniDebugLogger::new_in_memory()))
+        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))
+}
+
+/// The recording as of the last save with [`init_houlog_memory`] or [`HoulogConfig::Memory`].
+/// Empty for the other export methods.
+pub fn houlog_exported() -> Recording {
+    let Some(logger) = logger() else {
+        return Recording::default();
+    };
+    match &logger.export_method {
+        ExportMethod::Memory { recording, .. } => lock(recording).clone(),
+        _ => Recording::default(),
+    }
+}
+
+/// The snapshots taken via [`houlog_snapshot`] with [`init_houlog_memory`] or
+/// [`HoulogConfig::Memory`], by name. Empty for the other export methods.
+pub fn houlog_exported_snapshots() -> BTreeMap<String, Recording> {
+    let Some(logger) = logger() else {
+        return BTreeMap::new();
+    };
+    match &logger.export_method {
+        ExportMethod::Memory { snapshots, .. } => lock(snapshots).clone(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Serve the recording over a WebSocket endpoint in addition to the configured export method, so
+/// that viewers on other machines (for example a browser or a Houdini Python panel) can subscribe
+/// to it. Every save sends the whole recording as a text message in the same JSON format as
+/// [`init_houlog_stream`], and new subscribers immediately receive the latest one.
+#[cfg(feature = "websocket")]
+pub fn houlog_serve_websocket(address: impl ToSocketAddrs) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger
+        .websocket
+        .set(WebSocketServer::bind(address)?)
+        .map_err(|_| anyhow!("WebSocket server already started"))
+}
+
+/// This initializes houlog to write to a live Houdini session, see [`LiveSessionOptions`] for the
+/// available options. You must have a live session running in Houdini which you can start via the
+/// "Houdini Engine SessionSync" pane tab (which can be found clicking on the + and then under New Pane Tab Type -> Misc).
+pub fn init_houlog_live(mut options: LiveSessionOptions) -> Result<()> {
+    HOUDINI_DEBUG_LOGGER
+        .set(Arc::new(HoudiniDebugLogger::new_with_live_session(
+            &mut options,
+        )?))
+        .map_err(|_| anyhow!("HoudiniDebugLogger already initialized"))?;
+    if options.lazy_connect {
+        if let Some(logger) = HOUDINI_DEBUG_LOGGER.get() {
+            // The global logger lives as long as the process, so the thread is detached.
+            drop(logger.connect_in_background(options));
+        }
+    }
+    Ok(())
+}
+
+/// Where [`with_houlog`] exports the recording to, matching the `init_houlog` functions.
+pub enum HoulogConfig {
+    /// See [`init_houlog`].
+    File(PathBuf),
+    /// See [`init_houlog_stream`].
+    Stream(SocketAddr),
+    /// See [`init_houlog_live`].
+    Live(LiveSessionOptions),
+    /// See [`init_houlog_remote`], with the address of the server and the name of this process.
+    Remote(SocketAddr, String),
+    /// See [`init_houlog_memory`].
+    Memory,
+}
+
+/// Runs `f` with its own logger, which is initialized from `config` and saved and torn down
+/// afterwards, even if `f` panics. Inside of `f`, all logging functions called from the current
+/// thread use this logger instead of the global one, so tests and one-shot tools can capture a
+/// recording without touching global state. Threads spawned by `f` still log into the global
+/// logger. Scopes can be nested.
+///
+/// ```no_run
+/// # use houdini_debug_logger::*;
+/// # let mesh = vec![glam::Vec3::ZERO];
+/// with_houlog(HoulogConfig::File("erosion.bgeo".into()), || {
+///     houlog("heightmap", &mesh);
+/// })?;
+/// # anyhow::Ok(())
+/// ```
+pub fn with_houlog<R>(config: HoulogConfig, f: impl FnOnce() -> R) -> Result<R> {
+    let (logger, lazy_connect) = match config {
+        HoulogConfig::File(path) => (HoudiniDebugLogger::new_with_file(path)?, None),
+        HoulogConfig::Stream(address) => (HoudiniDebugLogger::new_with_stream(address), None),
+        HoulogConfig::Remote(address, source) => {
+            (HoudiniDebugLogger::new_remote(address, source), None)
+        }
+        HoulogConfig::Memory => (HoudiniDebugLogger::new_in_memory(), None),
+        HoulogConfig::Live(mut options) => {
+            let logger = HoudiniDebugLogger::new_with_live_session(&mut options)?;
+            (logger, Some(options).filter(|options| options.lazy_connect))
+        }
+    };
+    // Guards such as spans keep their own reference, so the logger is freed once the scope and
+    // all of its guards are gone.
+    let logger = Arc::new(logger);
+    let connecting = lazy_connect.and_then(|options| logger.connect_in_background(options));
+    let scope = LoggerScope {
+        previous: SCOPED_LOGGER.with(|scoped| scoped.replace(Some(Arc::clone(&logger)))),
+        logger,
+        connecting,
+        finished: false,
+    };
+    let result = f();
+    scope.finish()?;
+    Ok(result)
+}
+
+/// Ends a [`with_houlog`] scope when dropped, including when unwinding.
+struct LoggerScope {
+    logger: Arc<HoudiniDebugLogger>,
+    /// The logger of the enclosing scope.
+    previous: Option<Arc<HoudiniDebugLogger>>,
+    /// The thread connecting to the live session, see [`LiveSessionOptions::with_lazy_connect`].
+    connecting: Option<JoinHandle<()>>,
+    finished: bool,
+}
+
+impl LoggerScope {
+    /// Saves the recording, returning the error instead of printing it.
+    fn finish(mut self) -> Result<()> {
+        self.finished = true;
+        self.logger.shutdown()
+    }
+}
+
+impl Drop for LoggerScope {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.logger.shutdown().unwrap_or_else(warn);
+        }
+        SCOPED_LOGGER.with(|scoped| *scoped.borrow_mut() = self.previous.take());
+        match self.connecting.take() {
+            Some(connecting) if connecting.is_finished() => {
+                let _ = connecting.join();
+            }
+            // Still connecting, so nothing has been saved yet. The thread holds on to the logger
+            // and saves the recording once it's connected, so it's detached instead of blocking
+            // the end of the scope until Houdini is reachable.
+            Some(connecting) => drop(connecting),
+            None => {}
+        }
+    }
+}
+
+/// Options for [`init_houlog_live`].
+pub struct LiveSessionOptions {
+    session: Option<Session>,
+    path: String,
+    node_name: String,
+    host: String,
+    port: u16,
+    check_nodes: bool,
+    shared_memory: Option<String>,
+    retries: u32,
+    retry_interval: Duration,
+    connect_timeout: Option<Duration>,
+    wait_for_houdini: bool,
+    lazy_connect: bool,
+    playback: bool,
+}
+
+impl Default for LiveSessionOptions {
+    fn default() -> Self {
+        LiveSessionOptions {
+            session: None,
+            path: "/obj/recordings".to_string(),
+            node_name: "recording".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 9090,
+            check_nodes: true,
+            shared_memory: None,
+            retries: 0,
+            retry_interval: Duration::from_secs(1),
+            connect_timeout: None,
+            wait_for_houdini: false,
+            lazy_connect: false,
+            playback: false,
+        }
+    }
+}
+
+impl LiveSessionOptions {
+    /// Use an existing session instead of connecting to one. Useful if you're already attached to
+    /// a session for a different purpose (for example live-reloading).
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// The path to the subnet in which the recording node is created. Defaults to
+    /// `/obj/recordings`.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// The name of the recording node. Defaults to `recording`. Use different names when logging
+    /// from multiple processes (for example client and server) into the same Houdini session.
+    pub fn with_node_name(mut self, node_name: impl Into<String>) -> Self {
+        self.node_name = node_name.into();
+        self
+    }
+
+    /// Connect to the session of a Houdini instance on another machine, for example to push
+    /// recordings from a console dev kit to a workstation. Defaults to `127.0.0.1` and port
+    /// `9090`. Consider also setting a longer [connect timeout](Self::with_connect_timeout).
+    pub fn with_host(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.host = host.into();
+        self.port = port;
+        self
+    }
+
+    /// Whether to check that the recording nodes still exist before each save, recreating them if
+    /// they have been deleted. Defaults to true. Turning this off saves a round trip per node and
+    /// save, which adds up on remote connections.
+    pub fn with_check_nodes(mut self, check_nodes: bool) -> Self {
+        self.check_nodes = check_nodes;
+        self
+    }
+
+    /// Connect to a shared memory session with the given name instead of the socket on port 9090.
+    /// This is much faster for large amounts of data, such as big per-frame meshes. The session
+    /// must be started in the SessionSync pane with the shared memory type and the same name.
+    pub fn with_shared_memory(mut self, name: impl Into<String>) -> Self {
+        self.shared_memory = Some(name.into());
+        self
+    }
+
+    /// Retry connecting to the session up to `retries` times if it fails. Defaults to 0.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// The time to wait between connection attempts. Defaults to one second.
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Keep retrying to connect to the session until the timeout has passed.
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Keep retrying to connect to the session until it appears, for example when the application
+    /// is launched before Houdini. Note that this blocks [`init_houlog_live`] until then.
+    pub fn with_wait_for_houdini(mut self, wait_for_houdini: bool) -> Self {
+        self.wait_for_houdini = wait_for_houdini;
+        self
+    }
+
+    /// Don't wait for the connection in [`init_houlog_live`], but keep trying to connect in the
+    /// background every [retry interval](Self::with_retry_interval) until Houdini is available.
+    /// Until then, saves are skipped and everything stays in memory, and once the session has been
+    /// established, the whole recording is sent. Defaults to false.
+    pub fn with_lazy_connect(mut self, lazy_connect: bool) -> Self {
+        self.lazy_connect = lazy_connect;
+        self
+    }
+
+    /// Instead of sending the whole recording with all frames on each save, only send the frame
+    /// that is currently being logged into, replacing the geometry of the recording node, and move
+    /// Houdini's playbar to that frame. This turns the session into a real-time view of what the
+    /// application sees right now, at the cost of not keeping the history in Houdini. Channels and
+    /// channel plots aren't split into separate nodes in this mode. Defaults to false.
+    pub fn with_playback(mut self, playback: bool) -> Self {
+        self.playback = playback;
+        self
+    }
+}
+
+/// Only keep the frames of the last `max_frames` frames up to the current one in memory, dropping
+/// older ones as the current frame advances. This allows running an always-on "flight recorder"
+/// for hours and still saving the last few seconds when something goes wrong. Dropped frames keep
+/// their numbering, so the saved recording starts at the first frame that's still in memory.
+/// Frames after the current one, for example ones logged via [`houlog_at_frame`], are kept until
+/// the current frame has moved past them. Pass `None` to keep all frames, which is the default.
+/// Errors are printed instead of being returned, use [`try_houlog_set_max_frames`] to handle them.
+pub fn houlog_set_max_frames(max_frames: Option<usize>) {
+    try_houlog_set_max_frames(max_frames).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_set_max_frames`], but returns errors instead of printing them.
+pub fn try_houlog_set_max_frames(max_frames: Option<usize>) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.set_max_frames(max_frames)
+}
+
+/// Limit the memory used by the in-memory recording to roughly `budget` bytes, applying the given
+/// policy whenever it's exceeded, instead of growing until the application runs out of memory
+/// during a long session. The memory usage is estimated via [`DebugLoggable::memory_size`]. Pass
+/// `None` to remove the limit, which is the default.
+/// Errors are printed instead of being returned, use [`try_houlog_set_memory_budget`] to handle them.
+pub fn houlog_set_memory_budget(budget: Option<usize>, policy: MemoryPolicy) {
+    try_houlog_set_memory_budget(budget, policy).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_set_memory_budget`], but returns errors instead of printing them.
+pub fn try_houlog_set_memory_budget(budget: Option<usize>, policy: MemoryPolicy) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.set_memory_budget(budget, policy)
+}
+
+/// Move the entries of completed frames to a temporary file on disk once the in-memory recording
+/// uses more than roughly `threshold` bytes, and stitch them back together when saving. This
+/// allows hour-long captures without holding everything in memory. Spilled entries only keep their
+/// exported form, so this works with all export methods. Pass `None` to keep everything in memory,
+/// which is the default.
+/// Errors are printed instead of being returned, use [`try_houlog_set_spill_threshold`] to handle them.
+pub fn houlog_set_spill_threshold(threshold: Option<usize>) {
+    try_houlog_set_spill_threshold(threshold).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_set_spill_threshold`], but returns errors instead of printing them.
+pub fn try_houlog_set_spill_threshold(threshold: Option<usize>) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.set_spill_threshold(threshold)
+}
+
+/// Statistics about the recording that is currently in memory, such as the number of entries per
+/// kind and name and the entries that use the most memory. Useful for finding out which call site
+/// bloats the recording before opening it in Houdini.
+pub fn houlog_stats() -> HoulogStats {
+    let Some(logger) = logger() else {
+        return HoulogStats::default();
+    };
+    logger.stats()
+}
+
+/// Returns the entries of the recording that is currently in memory that match the filter, in the
+/// form in which they would be saved. This lets unit tests assert on what has been logged without
+/// a Houdini session, for example via [`RecordedPoint::decode`](crate::RecordedPoint::decode).
+/// Entries that have been spilled to disk and the channel plots aren't included.
+///
+/// ```no_run
+/// # use glam::Vec3;
+/// # use houdini_debug_logger::*;
+/// let targets = houlog_query(&HoulogFilter::new().with_name("target").with_frame(3));
+/// assert_eq!(targets[0].decode::<Vec3>()?, Vec3::new(1.0, 0.0, 1.0));
+/// # anyhow::Ok(())
+/// ```
+pub fn houlog_query(filter: &HoulogFilter) -> Vec<RecordedPoint> {
+    let Some(logger) = logger() else {
+        return Vec::new();
+    };
+    logger.query(filter)
+}
+
+/// Export everything that has been recorded so far under the given name and start over, like
+/// [`houlog_clear`]. This allows capturing multiple variants of a run side by side, for example
+/// `houlog_snapshot("before_erosion")` and `houlog_snapshot("after_erosion")`. In a live session,
+/// the snapshot goes into its own nodes (`<node>__snapshot_<name>`), and with [`init_houlog`] into
+/// its own file (`recording_<name>.bgeo` for `recording.bgeo`), with the name sanitized the same
+/// way as node names. With [`init_houlog_memory`], snapshots are
+/// returned by [`houlog_exported_snapshots`], and with [`init_houlog_remote`] they are sent as a
+/// source of their own (`<source>_<name>`). Snapshots aren't supported with [`init_houlog_stream`].
+/// Errors are printed instead of being returned, use [`try_houlog_snapshot`] to handle them.
+pub fn houlog_snapshot(name: &str) {
+    try_houlog_snapshot(name).unwrap_or_else(warn);
+}
+
+/// Like [`houlog_snapshot`], but returns errors instead of printing them.
+pub fn try_houlog_snapshot(name: &str) -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.snapshot(name)
+}
+
+/// Drop everything that has been recorded so far and start over at frame 0, while keeping the
+/// session alive. Useful for iterative workflows such as retrying a generation with a new seed.
+/// The next save replaces the recording in Houdini with the (empty) new one.
+/// Errors are printed instead of being returned, use [`try_houlog_clear`] to handle them.
+pub fn houlog_clear() {
+    try_houlog_clear().unwrap_or_else(warn);
+}
+
+/// Like [`houlog_clear`], but returns errors instead of printing them.
+pub fn try_houlog_clear() -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.clear()
+}
+
+/// Choose what [`houlog_shutdown`] does with the recording. Defaults to [`DropBehavior::Save`].
+pub fn houlog_set_drop_behavior(behavior: DropBehavior) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    *lock(&logger.drop_behavior) = behavior;
+}
+
+/// Callbacks into the host application, see [`houlog_set_hooks`]. Hooks are called on the thread
+/// that triggered them, after the logger has released its locks, so they can log themselves.
+#[derive(Default)]
+pub struct Hooks {
+    /// Called after advancing to a new frame, with the (0-based) index of the frame that has just
+    /// been completed. Useful for capturing screenshots alongside the recording.
+    pub on_frame: Option<Box<dyn Fn(u32) + Send + Sync>>,
+    /// Called after the recording has been sent to Houdini.
+    pub on_save: Option<Box<dyn Fn() + Send + Sync>>,
+    /// Called for errors that the logging functions would otherwise only print, for example when
+    /// [`save_houlog`] fails. Errors returned by the `try_` functions aren't passed here.
+    pub on_error: Option<ErrorHook>,
+}
+
+/// The type of [`Hooks::on_error`].
+pub type ErrorHook = Box<dyn Fn(&anyhow::Error) + Send + Sync>;
+
+/// Install callbacks that get notified about frame advancement, saves and errors, replacing the
+/// previously installed ones.
+pub fn houlog_set_hooks(hooks: Hooks) {
+    let Some(logger) = logger() else {
+        return;
+    };
+    *lock(&logger.hooks) = Arc::new(hooks);
+}
+
+/// Finish the recording according to the behavior set via [`houlog_set_drop_behavior`]. Call this
+/// at the end of the program: the logger lives in a static, and statics are never dropped, so
+/// nothing is saved automatically when the program exits. This also deletes the temporary file of
+/// [`houlog_set_spill_threshold`], so the spilled frames are gone afterwards.
+/// Errors are printed instead of being returned, use [`try_houlog_shutdown`] to handle them.
+pub fn houlog_shutdown() {
+    try_houlog_shutdown().unwrap_or_else(warn);
+}
+
+/// Like [`houlog_shutdown`], but returns errors instead of printing them.
+pub fn try_houlog_shutdown() -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.shutdown()
+}
+
+/// Save the session and send it to Houdini. Errors are printed instead of being returned, use
+/// [`try_save_houlog`] to handle them.
+pub fn save_houlog() {
+    try_save_houlog().unwrap_or_else(warn);
+}
+
+/// Like [`save_houlog`], but returns errors instead of printing them.
+pub fn try_save_houlog() -> Result<()> {
+    let Some(logger) = logger() else {
+        return Ok(());
+    };
+    logger.save()
+}
+
+/// Replaces every character that isn't valid in a Houdini identifier (such as a group name) with an
+/// underscore.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn sanitize_identifier(name: &str) -> String {
+    let mut sanitized = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Like [`sanitize_identifier`], but also collapses repeated underscores, so that node labels never
+/// contain `__`, which separates the parts that are appended to the labels of generated nodes such
+/// as snapshots, see [`HoudiniDebugLogger::save_live`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn sanitize_node_label(name: &str) -> String {
+    let mut label = String::new();
+    for c in sanitize_identifier(name).chars() {
+        if c != '_' || !label.ends_with('_') {
+            label.push(c);
+        }
+    }
+    label
+}
+
+/// Assigns unique, valid Houdini identifiers to arbitrary names, see [`sanitize_identifier`].
+/// Names that sanitize to an identifier that is already taken, for example `a b` and `a/b`, or the
+/// name of a built-in attribute, get a numbered suffix so that they don't silently collide.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+struct Identifiers {
+    /// The identifier of each name that has been seen so far.
+    identifiers: HashMap<String, String>,
+    /// The identifiers that are in use, including the reserved ones.
+    taken: HashSet<String>,
+    /// Turns a name into a valid identifier, before making it unique.
+    sanitize: fn(&str) -> String,
+}
+
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+impl Identifiers {
+    fn with_reserved(reserved: &[&str]) -> Self {
+        Identifiers {
+            identifiers: HashMap::new(),
+            taken: reserved.iter().map(|name| name.to_string()).collect(),
+            sanitize: sanitize_identifier,
+        }
+    }
+
+    /// Identifiers for the labels of live nodes, see [`sanitize_node_label`].
+    fn node_labels() -> Self {
+        Identifiers {
+            sanitize: sanitize_node_label,
+            ..Identifiers::with_reserved(&[])
+        }
+    }
+
+    /// Returns the identifier for the name, which stays the same for repeated calls.
+    fn get(&mut self, name: &str) -> String {
+        if let Some(identifier) = self.identifiers.get(name) {
+            return identifier.clone();
+        }
+        let sanitized = (self.sanitize)(name);
+        let separator = if sanitized.ends_with('_') { "" } else { "_" };
+        let mut identifier = sanitized.clone();
+        let mut suffix = 1;
+        while self.taken.contains(&identifier) {
+            identifier = format!("{}{}{}", sanitized, separator, suffix);
+            suffix += 1;
+        }
+        self.taken.insert(identifier.clone());
+        self.identifiers
+            .insert(name.to_string(), identifier.clone());
+        identifier
+    }
+}
+
+/// The point attributes that are always exported. Custom attributes with these names are renamed.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+const BUILTIN_ATTRIBUTES: &[&str] = &[
+    "P",
+    "pscale",
+    "orient",
+    "v",
+    "N",
+    "name",
+    "kind",
+    "channel",
+    "level",
+    "time",
+    "frame_label",
+    "sim_time",
+    "realtime",
+    "id",
+    "src_file",
+    "src_line",
+    "metadata",
+    "path_root",
+    "path_leaf",
+    "orig_name",
+];
+
+/// The detail attribute that maps the built-in attributes which have been renamed via
+/// [`houlog_set_attribute_names`] to their exported names, as a JSON object. Its own name is fixed,
+/// so that readers can always find it.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+const ATTRIBUTE_NAMES_ATTRIBUTE: &str = "houlog_attribute_names";
+
+/// The point attribute holding the geometry of the entries for the Python SOP running
+/// [`PACK_CODE`], which removes it again. Its name is fixed, like [`ATTRIBUTE_NAMES_ATTRIBUTE`].
+#[cfg(feature = "enabled")]
+const ENTRY_GEOMETRY_ATTRIBUTE: &str = "houlog_geometry";
+
+/// The code of the Python SOP that packs the exported points, see
+/// [`HoudiniDebugLogger::packs_geometry`].
+#[cfg(feature = "enabled")]
+const PACK_CODE: &str = include_str!("../houdini/houlog_pack.py");
+
+/// The code of the Python SOP that turns recordings back into loose points when reading them, see
+/// [`read_bgeo`].
+#[cfg(feature = "enabled")]
+const UNPACK_CODE: &str = include_str!("../houdini/houlog_unpack.py");
+
+/// Checks that the exported names of the built-in attributes are valid identifiers and unique.
+fn validate_attribute_names(names: &AttributeNames) -> Result<()> {
+    if let Some(name) = names
+        .renamed
+        .keys()
+        .find(|name| !BUILTIN_ATTRIBUTES.contains(&name.as_str()))
+    {
+        return Err(anyhow!("{} isn't a built-in attribute", name));
+    }
+
+    let mut builtins = HashMap::new();
+    for builtin in BUILTIN_ATTRIBUTES {
+        let name = names.get(builtin);
+        if name.is_empty() || sanitize_identifier(&name) != name {
+            return Err(anyhow!("{} isn't a valid attribute name", name));
+        }
+        if name == ATTRIBUTE_NAMES_ATTRIBUTE {
+            return Err(anyhow!("{} is reserved", name));
+        }
+        if let Some(other) = builtins.insert(name.clone(), builtin) {
+            return Err(anyhow!(
+                "{} and {} would both be exported as {}",
+                other,
+                builtin,
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Inserts the name of a snapshot into the file name, before all extensions so that `.bgeo.sc`
+/// stays intact.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn snapshot_path(path: &Path, snapshot: &str) -> PathBuf {
+    let snapshot = sanitize_identifier(snapshot);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let file_name = match file_name.split_once('.') {
+        Some((stem, extensions)) => format!("{}_{}.{}", stem, snapshot, extensions),
+        None => format!("{}_{}", file_name, snapshot),
+    };
+    path.with_file_name(file_name)
+}
+
+/// The name of the calling thread, falling back to its id for unnamed threads.
+fn thread_name() -> String {
+    let thread = std::thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+/// Returns the logger of the [`with_houlog`] scope the calling thread is in, or the global logger.
+fn current_logger() -> Option<Arc<HoudiniDebugLogger>> {
+    SCOPED_LOGGER
+        .with(|scoped| scoped.borrow().clone())
+        .or_else(|| HOUDINI_DEBUG_LOGGER.get().cloned())
+}
+
+/// Returns the current logger, or prints a message if it hasn't been initialized yet.
+fn logger() -> Option<Arc<HoudiniDebugLogger>> {
+    let logger = current_logger();
+    if logger.is_none() {
+        eprintln!("HoudiniDebugLogger not initialized");
+    }
+    logger
+}
+
+/// Like [`logger`], but also returns `None` while logging is turned off via [`houlog_set_enabled`].
+fn enabled_logger() -> Option<Arc<HoudiniDebugLogger>> {
+    logger().filter(|logger| logger.enabled.load(Ordering::Relaxed))
+}
+
+/// Prints an error of the logger to stderr instead of panicking and falls back to the default value, so
+/// that the program being debugged keeps running. The error is also passed to the `on_error` hook,
+/// see [`houlog_set_hooks`].
+fn warn<T: Default>(error: anyhow::Error) -> T {
+    eprintln!("Houdini Debug Logger error: {:#}", error);
+    if let Some(logger) = current_logger() {
+        if let Some(on_error) = &logger.hooks().on_error {
+            on_error(&error);
+        }
+    }
+    T::default()
+}
+
+/// Locks the mutex, recovering from poisoning. A panic in one thread while logging shouldn't stop
+/// all other threads from logging for the rest of the run, and the recorded data stays consistent
+/// enough for debugging purposes.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+static HOUDINI_DEBUG_LOGGER: OnceLock<Arc<HoudiniDebugLogger>> = OnceLock::new();
+
+thread_local! {
+    /// The frame set via [`houlog_set_thread_frame`], overriding the global current frame.
+    static THREAD_FRAME: Cell<Option<usize>> = const { Cell::new(None) };
+
+    /// The ids and names of the currently active spans of this thread, innermost last.
+    static SPAN_STACK: RefCell<Vec<(u64, String)>> = const { RefCell::new(Vec::new()) };
+
+    /// The logger of the innermost [`with_houlog`] scope of this thread, which takes precedence
+    /// over the global logger.
+    static SCOPED_LOGGER: RefCell<Option<Arc<HoudiniDebugLogger>>> = const { RefCell::new(None) };
+}
+
+/// The method of exporting the data. This can either be a live session or a file.
+pub enum ExportMethod {
+    LiveSession {
+        /// The hapi-rs session to use. Only unset while connecting in the background, see
+        /// [`LiveSessionOptions::with_lazy_connect`].
+        session: OnceLock<Session>,
+
+        /// The path to the subnet in which the node will be stored
+        path: String,
+
+        /// The name of the node
+        node_name: String,
+
+        /// Whether to check that the cached recording nodes still exist before each save.
+        check_nodes: bool,
+
+        /// Whether to only send the current frame, see [`LiveSessionOptions::with_playback`].
+        playback: bool,
+    },
+    File {
+        /// The full filepath to the file to be created. Typically, this should end with `.bgeo`.
+        path: PathBuf,
+    },
+    Stream {
+        /// The address on which the Python receiver is listening.
+        address: SocketAddr,
+    },
+    /// Sends the recording to a [`HoulogServer`](crate::HoulogServer), see [`init_houlog_remote`].
+    Remote {
+        /// The address on which the server is listening.
+        address: SocketAddr,
+        /// The name of this process, exported as the `source` attribute by the server.
+        source: String,
+    },
+    /// Keeps what would be exported in memory instead, see [`init_houlog_memory`].
+    Memory {
+        /// The recording as of the last save.
+        recording: Mutex<Recording>,
+        /// The snapshots taken via [`houlog_snapshot`] by name.
+        snapshots: Mutex<BTreeMap<String, Recording>>,
+    },
+}
+
+/// The value of a custom per-entry attribute.
+#[derive(Serialize, Deserialize)]
+enum AttributeValue {
+    Float(f32),
+    Int(i32),
+    String(String),
+}
+
+struct LogEntry {
+    name: Name,
+    /// The kind and the position are captured when logging, since they are cheap. The metadata is
+    /// only serialized when saving.
+    kind: Name,
+    position: Vec3,
+    /// The size of the entry, see [`DebugLoggable::pscale`].
+    pscale: f32,
+    /// The rotation of the entry, see [`DebugLoggable::orient`].
+    orient: Quat,
+    /// The velocity of the entry, see [`DebugLoggable::velocity`].
+    velocity: Vec3,
+    /// The normal of the entry, see [`DebugLoggable::normal`].
+    normal: Vec3,
+    value: Box<dyn DebugLoggable>,
+    channel: Option<String>,
+    level: Level,
+    attributes: Vec<(String, AttributeValue)>,
+    /// The thread that logged the entry, if it used its own frame cursor.
+    thread: Option<ThreadKey>,
+    /// The wall-clock time at which the entry was logged.
+    timestamp: SystemTime,
+    /// A stable id of the logged entity, see [`houlog_id`].
+    id: Option<u64>,
+    /// The source location of the logging call.
+    location: SourceLocation,
+    /// Whether this is a counter or gauge of a frame, which live sessions export into a node of
+    /// their own, see [`FrameData::metric_entries`].
+    #[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+    metric: bool,
+}
+
+/// The source location of a logging call. Unlike [`Location`], this can also be restored from a
+/// spill file.
+#[derive(Clone, Copy)]
+struct SourceLocation {
+    file: &'static str,
+    line: u32,
+}
+
+impl From<&'static Location<'static>> for SourceLocation {
+    fn from(location: &'static Location<'static>) -> Self {
+        SourceLocation {
+            file: location.file(),
+            line: location.line(),
+        }
+    }
+}
+
+impl LogEntry {
+    #[track_caller]
+    fn new(names: &Interner, name: &str, value: impl DebugLoggable + 'static) -> Self {
+        LogEntry {
+            name: names.intern(name),
+            kind: names.intern(&value.kind()),
+            position: value.position(),
+            pscale: value.pscale().unwrap_or(DEFAULT_PSCALE),
+            orient: value.orient().unwrap_or(Quat::IDENTITY),
+            velocity: value.velocity().unwrap_or(Vec3::ZERO),
+            normal: value.normal().unwrap_or(Vec3::ZERO),
+            value: Box::new(value),
+            channel: None,
+            level: Level::Info,
+            attributes: Vec::new(),
+            thread: None,
+            timestamp: SystemTime::now(),
+            id: None,
+            location: Location::caller().into(),
+            metric: false,
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        std::mem::size_of::<LogEntry>() + self.value.memory_size()
+    }
+
+    #[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+    fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(attr_name, _)| attr_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// The id of an interned entry name, see [`Interner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Name(u32);
+
+/// Interns the names and kinds of entries, so that each entry only stores an id instead of its own
+/// copy of them. The names are resolved once per save.
+#[derive(Default)]
+struct Interner {
+    data: RwLock<InternerData>,
+}
+
+#[derive(Default)]
+struct InternerData {
+    ids: HashMap<Arc<str>, Name>,
+    names: Vec<Arc<str>>,
+}
+
+impl Interner {
+    fn intern(&self, name: &str) -> Name {
+        if let Some(id) = self.read().ids.get(name) {
+            return *id;
+        }
+        let mut data = self.data.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(id) = data.ids.get(name) {
+            return *id;
+        }
+        let id = Name(data.names.len() as u32);
+        let name = Arc::<str>::from(name);
+        data.names.push(name.clone());
+        data.ids.insert(name, id);
+        id
+    }
+
+    fn name(&self, id: Name) -> Arc<str> {
+        self.read().names[id.0 as usize].clone()
+    }
+
+    /// All names, indexed by their id.
+    fn names(&self) -> Vec<Arc<str>> {
+        self.read().names.clone()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, InternerData> {
+        self.data.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct FrameData {
+    entries: Vec<LogEntry>,
+    /// When the frame was created, which is when the previous frame ended unless frames were
+    /// skipped. [`ProfileBar`]s start relative to this.
+    started: Instant,
+    label: Option<String>,
+    /// The simulation time of the frame in seconds, see [`houlog_set_frame_time`].
+    sim_time: Option<f32>,
+    /// The counters and gauges of the frame, see [`houlog_counter`] and [`houlog_gauge`].
+    metrics: BTreeMap<String, Metric>,
+    /// The ranges of the spill file that hold entries of this frame, see [`SpillFile`].
+    spilled: Vec<(u64, u64)>,
+}
+
+impl FrameData {
+    fn new() -> Self {
+        FrameData {
+            entries: Vec::new(),
+            started: Instant::now(),
+            label: None,
+            sim_time: None,
+            metrics: BTreeMap::new(),
+            spilled: Vec::new(),
+        }
+    }
+
+    /// Builds one entry per counter and gauge of the frame.
+    fn metric_entries(&self, names: &Interner) -> Vec<LogEntry> {
+        self.metrics
+            .iter()
+            .map(|(name, metric)| LogEntry {
+                metric: true,
+                ..LogEntry::new(names, name, *metric)
+            })
+            .collect()
+    }
+}
+
+/// A temporary file that the entries of completed frames are moved into once the recording uses
+/// more memory than the spill threshold, see [`houlog_set_spill_threshold`]. They are read back on
+/// each save. The file is deleted when the recording is cleared, on [`houlog_shutdown`] or when a
+/// [`with_houlog`] scope ends.
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+}
+
+/// Identifies the thread of an entry that was logged with its own frame cursor, see
+/// [`houlog_set_thread_frame`]. Entries of the same frame are ordered by this when saving.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+enum ThreadKey {
+    Named(String),
+    /// Unnamed threads are numbered in the order they first logged, since their ids don't have a
+    /// meaningful order.
+    Unnamed(usize),
+}
+
+/// An entry as it is stored in the spill file. The value is stored in its exported form, since
+/// the original type can't be restored.
+#[derive(Serialize, Deserialize)]
+struct SpilledEntry {
+    name: u32,
+    kind: u32,
+    position: [f32; 3],
+    pscale: f32,
+    orient: [f32; 4],
+    velocity: [f32; 3],
+    normal: [f32; 3],
+    metadata: String,
+    channel: Option<String>,
+    level: Level,
+    attributes: Vec<(String, AttributeValue)>,
+    thread: Option<ThreadKey>,
+    timestamp: f64,
+    id: Option<u64>,
+    file: String,
+    line: u32,
+}
+
+/// The value of an entry that has been restored from its exported form, from the spill file or a
+/// saved recording. Only the metadata is kept here, the kind and the position are stored in the
+/// entry itself.
+struct SpilledValue {
+    metadata: String,
+}
+
+impl DebugLoggable for SpilledValue {
+    fn kind(&self) -> String {
+        "spilled".to_string()
+    }
+
+    fn as_json(&self) -> String {
+        self.metadata.clone()
+    }
+}
+
+impl SpillFile {
+    fn create() -> Result<Self> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "houlog-{}-{}.spill",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        Ok(SpillFile { path, file })
+    }
+
+    /// Moves the entries of the frame into the file.
+    fn write(&mut self, frame: &mut FrameData) -> Result<()> {
+        let entries = std::mem::take(&mut frame.entries)
+            .into_iter()
+            .map(|entry| {
+                let position = entry.position;
+                SpilledEntry {
+                    name: entry.name.0,
+                    kind: entry.kind.0,
+                    position: [position.x, position.y, position.z],
+                    pscale: entry.pscale,
+                    orient: entry.orient.to_array(),
+                    velocity: entry.velocity.to_array(),
+                    normal: entry.normal.to_array(),
+                    metadata: entry.value.as_json(),
+                    channel: entry.channel,
+                    level: entry.level,
+                    attributes: entry.attributes,
+                    thread: entry.thread,
+                    timestamp: entry
+                        .timestamp
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .map(|d| d.as_secs_f64())
+                        .unwrap_or_default(),
+                    id: entry.id,
+                    file: entry.location.file.to_string(),
+                    line: entry.location.line,
+                }
+            })
+            .collect::<Vec<_>>();
+        let bytes = serde_json::to_vec(&entries)?;
+        let offset = self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(&bytes)?;
+        frame.spilled.push((offset, bytes.len() as u64));
+        Ok(())
+    }
+
+    /// Reads back the entries of the frame.
+    fn read(&mut self, frame: &FrameData) -> Result<Vec<LogEntry>> {
+        let mut entries = Vec::new();
+        for &(offset, len) in &frame.spilled {
+            let mut bytes = vec![0; len as usize];
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.file.read_exact(&mut bytes)?;
+            let spilled = serde_json::from_slice::<Vec<SpilledEntry>>(&bytes)?;
+            entries.extend(spilled.into_iter().map(|entry| LogEntry {
+                name: Name(entry.name),
+                kind: Name(entry.kind),
+                position: Vec3::from(entry.position),
+                pscale: entry.pscale,
+                orient: Quat::from_array(entry.orient),
+                velocity: Vec3::from(entry.velocity),
+                normal: Vec3::from(entry.normal),
+                value: Box::new(SpilledValue {
+                    metadata: entry.metadata,
+                }),
+                channel: entry.channel,
+                level: entry.level,
+                attributes: entry.attributes,
+                thread: entry.thread,
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs_f64(entry.timestamp),
+                id: entry.id,
+                location: SourceLocation {
+                    file: static_file_name(entry.file),
+                    line: entry.line,
+                },
+                // Metrics are kept in their frame and never spilled.
+                metric: false,
+            }));
+        }
+        Ok(entries)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Returns a `'static` version of a source file name restored from the spill file. Each name is
+/// only leaked once, so this is bounded by the number of source files.
+fn static_file_name(file: String) -> &'static str {
+    static FILES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut files = lock(&FILES);
+    if let Some(file) = files.get(file.as_str()) {
+        return file;
+    }
+    let file: &'static str = Box::leak(file.into_boxed_str());
+    files.insert(file);
+    file
+}
+
+/// Maps each entry to an attribute value. With the `rayon` feature, this runs in parallel, which
+/// speeds up saving big recordings considerably.
+#[cfg(feature = "enabled")]
+fn map_entries<T: Send>(
+    entries: &[ExportEntry],
+    f: impl Fn(&ExportEntry) -> T + Sync + Send,
+) -> Vec<T> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        entries.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        entries.iter().map(f).collect()
+    }
+}
+
+/// The number of frames per node in live sessions, see [`HoudiniDebugLogger::save_live`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+const FRAMES_PER_CHUNK: usize = 100;
+
+/// A log entry together with the frame it is exported on.
+#[derive(Clone, Copy)]
+struct ExportEntry<'a> {
+    /// The 1-based frame of the entry, or 0 for entries that span the whole recording.
+    time: f32,
+    /// The frame of the entry, or `None` for entries that span the whole recording.
+    frame: Option<&'a FrameData>,
+    /// The resolved name of the entry.
+    name: &'a str,
+    /// The resolved kind of the entry.
+    kind: &'a str,
+    entry: &'a LogEntry,
+}
+
+impl ExportEntry<'_> {
+    /// Converts the entry into the form in which it is sent to the Python receiver (see
+    /// [`HoudiniDebugLogger::send_stream`]) and stored in NDJSON recordings. The fields match the
+    /// point attributes of the other export methods.
+    fn recorded_point(&self) -> RecordedPoint {
+        let position = self.entry.position;
+        let attributes = self
+            .entry
+            .attributes
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    AttributeValue::Float(v) => json!(v),
+                    AttributeValue::Int(v) => json!(v),
+                    AttributeValue::String(v) => json!(v),
+                };
+                (name.clone(), value)
+            })
+            .collect();
+
+        RecordedPoint {
+            position: [position.x, position.y, position.z],
+            pscale: self.entry.pscale,
+            orient: self.entry.orient.to_array(),
+            velocity: self.entry.velocity.to_array(),
+            normal: self.entry.normal.to_array(),
+            name: self.name.to_string(),
+            kind: self.kind.to_string(),
+            time: self.time,
+            frame_label: self
+                .frame
+                .and_then(|frame| frame.label.clone())
+                .unwrap_or_default(),
+            sim_time: self.frame.and_then(|frame| frame.sim_time).unwrap_or(0.0),
+            channel: self.entry.channel.clone().unwrap_or_default(),
+            level: self.entry.level as i32,
+            metadata: self.entry.value.as_json(),
+            id: self.entry.id.map(|id| id as i64).unwrap_or(-1),
+            src_file: self.entry.location.file.to_string(),
+            src_line: self.entry.location.line,
+            realtime: self
+                .entry
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or_default(),
+            attributes,
+            geometry: None,
+        }
+    }
+}
+
+/// The estimated memory used by a set of entries. Data shared between entries, such as the
+/// contents of an `Arc`, is counted once per allocation, no matter how many entries refer to it.
+#[derive(Default)]
+struct MemoryUsage {
+    bytes: usize,
+    /// The size of each shared allocation by its address, and the number of entries referring to
+    /// it. The entries keep the allocations alive, so their addresses can't be reused meanwhile.
+    shared: HashMap<usize, (usize, usize)>,
+}
+
+impl MemoryUsage {
+    fn add(&mut self, entry: &LogEntry) {
+        self.bytes += entry.memory_size();
+        if let Some((address, size)) = entry.value.shared_memory() {
+            let (size, count) = self.shared.entry(address).or_insert((size, 0));
+            if *count == 0 {
+                self.bytes += *size;
+            }
+            *count += 1;
+        }
+    }
+
+    fn remove(&mut self, entry: &LogEntry) {
+        self.bytes = self.bytes.saturating_sub(entry.memory_size());
+        if let Some((address, _)) = entry.value.shared_memory() {
+            if let Some((size, count)) = self.shared.get_mut(&address) {
+                *count -= 1;
+                if *count == 0 {
+                    self.bytes = self.bytes.saturating_sub(*size);
+                    self.shared.remove(&address);
+                }
+            }
+        }
+    }
+}
+
+struct LoggerData {
+    modified: bool,
+    /// The frames that are still in memory by their index. Only frames that have been logged into
+    /// exist, so that setting a far-away frame doesn't allocate all the frames in between.
+    frames: BTreeMap<usize, FrameData>,
+    /// The frames before this one have been dropped because of `max_frames` or the memory budget.
+    first_frame: usize,
+    /// The maximum number of frames to keep in memory, see [`houlog_set_max_frames`].
+    max_frames: Option<usize>,
+    /// The memory budget in bytes and the policy to apply, see [`houlog_set_memory_budget`].
+    memory_budget: Option<(usize, MemoryPolicy)>,
+    /// The estimated memory used by the entries of all frames.
+    memory_usage: MemoryUsage,
+    /// The memory usage above which completed frames are moved to disk, see
+    /// [`houlog_set_spill_threshold`].
+    spill_threshold: Option<usize>,
+    spill: Option<SpillFile>,
+    /// The first frame that hasn't been considered for spilling yet, see
+    /// [`LoggerData::spill_if_needed`].
+    spill_cursor: usize,
+    /// The index of the frame that entries are currently logged into.
+    current_frame: usize,
+    /// See [`houlog_set_frame_mode`].
+    frame_mode: FrameMode,
+    /// The time from which [`FrameMode::Realtime`] counts the frames.
+    clock_origin: Instant,
+    /// The labels of the currently active [`FrameScope`]s, innermost last.
+    frame_scopes: Vec<String>,
+    /// The frames that have changed since the last save.
+    dirty_frames: BTreeSet<usize>,
+    /// The recorded values of each scalar channel, as (frame, value, 0) points.
+    scalar_channels: BTreeMap<String, Vec<Vec3>>,
+    /// The number of calls to [`houlog_every_n`] per name.
+    call_counts: HashMap<Name, u64>,
+    /// The time at which an entry was last logged via [`houlog_sampled`] per name.
+    last_sampled: HashMap<Name, f32>,
+    /// The call sites of [`houlog_once`] that have already been logged.
+    logged_once: HashSet<&'static Location<'static>>,
+    /// The frame in which each call site of [`houlog_once_per_frame`] was last logged.
+    logged_once_per_frame: HashMap<&'static Location<'static>, usize>,
+    /// The index of each thread that has logged a [`ProfileBar`], see [`houlog_set_profiling`].
+    profile_tracks: HashMap<String, u32>,
+}
+
+impl LoggerData {
+    fn new() -> Self {
+        LoggerData {
+            modified: true,
+            frames: BTreeMap::from([(0, FrameData::new())]),
+            first_frame: 0,
+            max_frames: None,
+            memory_budget: None,
+            memory_usage: MemoryUsage::default(),
+            spill_threshold: None,
+            spill: None,
+            spill_cursor: 0,
+            current_frame: 0,
+            frame_mode: FrameMode::Manual,
+            clock_origin: Instant::now(),
+            frame_scopes: Vec::new(),
+            // Marked as dirty so that the first save overwrites whatever was exported before.
+            dirty_frames: BTreeSet::from([0]),
+            scalar_channels: BTreeMap::new(),
+            call_counts: HashMap::new(),
+            last_sampled: HashMap::new(),
+            logged_once: HashSet::new(),
+            logged_once_per_frame: HashMap::new(),
+            profile_tracks: HashMap::new(),
+        }
+    }
+
+    /// Drops all frames and starts over, keeping the settings.
+    fn clear(&mut self) {
+        // Scope guards that are still alive pop their label later on.
+        let frame_scopes = std::mem::take(&mut self.frame_scopes);
+        *self = LoggerData {
+            frame_scopes,
+            max_frames: self.max_frames,
+            frame_mode: self.frame_mode,
+            clock_origin: self.clock_origin,
+            memory_budget: self.memory_budget,
+            spill_threshold: self.spill_threshold,
+            ..LoggerData::new()
+        };
+    }
+
+    fn next_frame(&mut self, label: Option<String>) -> Result<()> {
+        self.modified = true;
+        self.current_frame = self.current_frame() + 1;
+        if let Some(frame) = self.frame_mut(self.current_frame) {
+            if label.is_some() {
+                frame.label = label;
+            }
+        }
+        self.spill_if_needed()
+    }
+
+    /// The frame the calling thread logs into.
+    fn cursor(&self) -> usize {
+        THREAD_FRAME
+            .with(|f| f.get())
+            .unwrap_or_else(|| self.current_frame())
+    }
+
+    /// The global current frame, which moves along with the wall clock in
+    /// [`FrameMode::Realtime`].
+    fn current_frame(&self) -> usize {
+        match self.frame_mode {
+            FrameMode::Manual => self.current_frame,
+            FrameMode::Realtime { fps } => {
+                let elapsed = self.clock_origin.elapsed().as_secs_f64() * fps as f64;
+                self.current_frame.max(elapsed as usize)
+            }
+        }
+    }
+
+    /// Returns the frame with the given index, creating it if needed. Returns `None` if the frame
+    /// has already been dropped because of `max_frames` or the memory budget.
+    fn frame_mut(&mut self, index: usize) -> Option<&mut FrameData> {
+        if index < self.first_frame {
+            return None;
+        }
+        self.dirty_frames.insert(index);
+        if let btree_map::Entry::Vacant(frame) = self.frames.entry(index) {
+            frame.insert(FrameData::new());
+            self.drop_old_frames();
+        }
+        self.frames.get_mut(&index)
+    }
+
+    /// The index after the last frame in memory, or `first_frame` if there are none.
+    fn end_frame(&self) -> usize {
+        self.frames
+            .last_key_value()
+            .map_or(self.first_frame, |(&index, _)| index + 1)
+    }
+
+    /// Drops the frames that are more than `max_frames` frames before the current frame.
+    fn drop_old_frames(&mut self) {
+        let Some(max_frames) = self.max_frames else {
+            return;
+        };
+        let first_kept = (self.current_frame() + 1).saturating_sub(max_frames.max(1));
+        if first_kept > self.first_frame {
+            self.drop_frames_before(first_kept);
+        }
+    }
+
+    fn drop_frames_before(&mut self, first_kept: usize) {
+        let kept = self.frames.split_off(&first_kept);
+        let dropped = std::mem::replace(&mut self.frames, kept);
+        for entry in dropped.values().flat_map(|frame| &frame.entries) {
+            self.memory_usage.remove(entry);
+        }
+        self.first_frame = first_kept;
+        self.dirty_frames.insert(self.first_frame);
+
+        let first_time = (self.first_frame + 1) as f32;
+        for points in self.scalar_channels.values_mut() {
+            points.retain(|pt| pt.x >= first_time);
+        }
+    }
+
+    fn push_entry(&mut self, frame: usize, entry: LogEntry) {
+        self.memory_usage.add(&entry);
+        match self.frame_mut(frame) {
+            Some(frame) => frame.entries.push(entry),
+            None => self.memory_usage.remove(&entry),
+        }
+    }
+
+    /// Replaces the last entry with the same name in the frame, or adds the entry if there is
+    /// none.
+    fn replace_entry(&mut self, frame: usize, entry: LogEntry) {
+        self.memory_usage.add(&entry);
+        let Some(frame) = self.frame_mut(frame) else {
+            self.memory_usage.remove(&entry);
+            return;
+        };
+        let replaced = match frame.entries.iter().rposition(|e| e.name == entry.name) {
+            Some(i) => std::mem::replace(&mut frame.entries[i], entry),
+            None => return frame.entries.push(entry),
+        };
+        self.memory_usage.remove(&replaced);
+    }
+
+    fn stats(&self, names: &Interner) -> HoulogStats {
+        /// The number of entries in [`HoulogStats::largest_entries`].
+        const LARGEST_ENTRIES: usize = 10;
+
+        let names = names.names();
+        let mut stats = HoulogStats {
+            frames: self.frames.len(),
+            memory: self.memory_usage.bytes,
+            ..HoulogStats::default()
+        };
+        let mut sizes = Vec::new();
+        for (&frame_index, frame) in &self.frames {
+            let mut frame_bytes = 0;
+            for entry in &frame.entries {
+                let bytes = entry.memory_size();
+                frame_bytes += bytes;
+                let name = &names[entry.name.0 as usize];
+                let kind = &names[entry.kind.0 as usize];
+                *stats.entries_per_name.entry(name.to_string()).or_default() += 1;
+                *stats.entries_per_kind.entry(kind.to_string()).or_default() += 1;
+                sizes.push((bytes, frame_index, entry));
+            }
+            stats.entries += frame.entries.len();
+            stats.bytes_per_frame.insert(frame_index, frame_bytes);
+        }
+
+        sizes.sort_by_key(|(bytes, _, _)| std::cmp::Reverse(*bytes));
+        stats.largest_entries = sizes
+            .into_iter()
+            .take(LARGEST_ENTRIES)
+            .map(|(bytes, frame, entry)| EntryStats {
+                name: names[entry.name.0 as usize].to_string(),
+                kind: names[entry.kind.0 as usize].to_string(),
+                frame,
+                bytes,
+                file: entry.location.file,
+                line: entry.location.line,
+            })
+            .collect();
+        stats
+    }
+
+    fn update_memory_usage(&mut self) {
+        self.memory_usage = MemoryUsage::default();
+        for entry in self.frames.values().flat_map(|frame| &frame.entries) {
+            self.memory_usage.add(entry);
+        }
+    }
+
+    /// Applies the memory budget policy while the budget is exceeded. Returns whether the
+    /// recording needs to be flushed, which has to happen after the lock is released.
+    fn enforce_memory_budget(&mut self) -> bool {
+        let Some((budget, policy)) = self.memory_budget else {
+            return false;
+        };
+        while self.memory_usage.bytes > budget {
+            match policy {
+                MemoryPolicy::DropOldest => {
+                    if !self.drop_oldest_frame() {
+                        break;
+                    }
+                }
+                MemoryPolicy::Downsample => {
+                    if !self.downsample() && !self.drop_oldest_frame() {
+                        break;
+                    }
+                }
+                MemoryPolicy::Flush => return true,
+            }
+        }
+        false
+    }
+
+    /// Moves the entries of the frames that have been completed since the last call into the
+    /// spill file if the memory usage exceeds the spill threshold. Each frame is only considered
+    /// once, so this doesn't do any work until the next frame has been reached.
+    fn spill_if_needed(&mut self) -> Result<()> {
+        let Some(threshold) = self.spill_threshold else {
+            return Ok(());
+        };
+        let start = self.spill_cursor.max(self.first_frame);
+        let end = self.current_frame();
+        if self.memory_usage.bytes <= threshold || start >= end {
+            return Ok(());
+        }
+
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => self.spill.insert(SpillFile::create()?),
+        };
+        for (_, frame) in self.frames.range_mut(start..end) {
+            for entry in &frame.entries {
+                self.memory_usage.remove(entry);
+            }
+            if !frame.entries.is_empty() {
+                spill.write(frame)?;
+            }
+        }
+        self.spill_cursor = end;
+        Ok(())
+    }
+
+    /// Deletes the spill file, dropping the entries that have been spilled.
+    fn discard_spill(&mut self) {
+        self.spill = None;
+        for frame in self.frames.values_mut() {
+            frame.spilled.clear();
+        }
+    }
+
+    /// Reads back the spilled entries of each frame.
+    fn restore_spilled(&mut self) -> Result<Vec<Vec<LogEntry>>> {
+        let Some(spill) = &mut self.spill else {
+            return Ok(self.frames.values().map(|_| Vec::new()).collect());
+        };
+        self.frames
+            .values()
+            .map(|frame| spill.read(frame))
+            .collect()
+    }
+
+    /// Drops the oldest frame, unless it's the only one left.
+    fn drop_oldest_frame(&mut self) -> bool {
+        match self.frames.first_key_value() {
+            Some((&oldest, _)) if self.frames.len() > 1 => {
+                self.drop_frames_before(oldest + 1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drops every other entry of the kind that uses the most memory, if there are enough entries
+    /// of it. Returns whether anything was dropped.
+    fn downsample(&mut self) -> bool {
+        let mut usage = HashMap::<Name, (usize, usize)>::new();
+        for entry in self.frames.values().flat_map(|frame| &frame.entries) {
+            let (size, count) = usage.entry(entry.kind).or_default();
+            *size += entry.memory_size();
+            *count += 1;
+        }
+        let Some((kind, _)) = usage
+            .into_iter()
+            .filter(|(_, (_, count))| *count > 1)
+            .max_by_key(|(_, (size, _))| *size)
+        else {
+            return false;
+        };
+
+        let mut keep = true;
+        for (&index, frame) in self.frames.iter_mut() {
+            let len = frame.entries.len();
+            frame.entries.retain(|entry| {
+                if entry.kind != kind {
+                    return true;
+                }
+                keep = !keep;
+                !keep
+            });
+            if frame.entries.len() != len {
+                self.dirty_frames.insert(index);
+            }
+        }
+        self.update_memory_usage();
+        true
+    }
+
+    /// Builds one polyline entry per scalar channel.
+    fn channel_plots(&self, names: &Interner) -> Vec<LogEntry> {
+        self.scalar_channels
+            .iter()
+            .map(|(name, points)| {
+                // Values can be recorded out of order when the frame is set explicitly.
+                let mut points = points.clone();
+                points.sort_by(|a, b| a.x.total_cmp(&b.x));
+                LogEntry::new(names, name, Polyline { points })
+            })
+            .collect()
+    }
+}
+
+struct HoudiniDebugLogger {
+    data: Mutex<LoggerData>,
+    export_method: ExportMethod,
+    /// Kept separate from `data` so that checking whether a channel is enabled is cheap.
+    disabled_channels: RwLock<HashSet<String>>,
+    min_level: AtomicU8,
+    metadata_format: AtomicU8,
+    export_layout: AtomicU8,
+    enabled: AtomicBool,
+    assert_panics: AtomicBool,
+    /// See [`houlog_set_profiling`].
+    profiling: AtomicBool,
+    /// The bits of the `f32` set via [`houlog_set_default_pscale`].
+    default_pscale: AtomicU32,
+    /// See [`houlog_set_stream_geometry`].
+    stream_geometry: AtomicBool,
+    /// See [`houlog_set_keep_replaced`].
+    keep_replaced: AtomicBool,
+    /// The bits of the `f32` frame rate set via [`houlog_set_timeline`], 0 if it hasn't been set.
+    fps: AtomicU32,
+    /// The start frame set via [`houlog_set_timeline`], 1 if it hasn't been set.
+    start_frame: AtomicI64,
+    /// The number of times the recording has been flushed by [`MemoryPolicy::Flush`].
+    flushes: AtomicUsize,
+    /// See [`houlog_set_attribute_names`].
+    attribute_names: Mutex<AttributeNames>,
+    /// See [`houlog_set_global`].
+    globals: Mutex<BTreeMap<String, Value>>,
+    drop_behavior: Mutex<DropBehavior>,
+    /// See [`houlog_set_hooks`]. Cloned out of the mutex before calling them, so that hooks can
+    /// replace the hooks.
+    hooks: Mutex<Arc<Hooks>>,
+    names: Interner,
+    /// The numbers of unnamed threads, see [`ThreadKey::Unnamed`].
+    unnamed_threads: Mutex<HashMap<ThreadId, usize>>,
+    /// When the logger was initialized. Span start times are relative to this.
+    created: Instant,
+    /// The recording nodes in the live session by name, see [`HoudiniDebugLogger::live_node`].
+    #[cfg(feature = "enabled")]
+    live_nodes: Mutex<HashMap<String, HoudiniNode>>,
+    /// The chunks that have been written per node, by the name of the recording (or snapshot) the
+    /// nodes belong to, see [`HoudiniDebugLogger::save_live`].
+    #[cfg(feature = "enabled")]
+    live_chunks: Mutex<BTreeMap<String, BTreeMap<String, BTreeSet<usize>>>>,
+    /// The labels of the recording nodes in the live session by channel, see
+    /// [`HoudiniDebugLogger::save_live`].
+    #[cfg(feature = "enabled")]
+    node_labels: Mutex<Identifiers>,
+    /// The nodes used for writing files, see [`HoudiniDebugLogger::file_nodes`].
+    #[cfg(feature = "enabled")]
+    file_nodes: Mutex<Option<(HoudiniNode, HoudiniNode)>>,
+    /// The connection to the Python receiver or the server, see
+    /// [`HoudiniDebugLogger::send_stream`].
+    stream: Mutex<Option<TcpStream>>,
+    /// Whether the next message to the Python receiver has to contain the whole recording instead
+    /// of only the changed frames, because something affecting all frames has changed.
+    stream_resend: AtomicBool,
+    /// See [`houlog_serve_websocket`].
+    #[cfg(feature = "websocket")]
+    websocket: OnceLock<WebSocketServer>,
+}
+
+impl HoudiniDebugLogger {
+    fn new(export_method: ExportMethod) -> Self {
+        HoudiniDebugLogger {
+            export_method,
+            data: Mutex::new(LoggerData::new()),
+            disabled_channels: RwLock::new(HashSet::new()),
+            min_level: AtomicU8::new(Level::Debug as u8),
+            metadata_format: AtomicU8::new(MetadataFormat::Json as u8),
+            export_layout: AtomicU8::new(ExportLayout::Merged as u8),
+            enabled: AtomicBool::new(true),
+            assert_panics: AtomicBool::new(true),
+            profiling: AtomicBool::new(false),
+            default_pscale: AtomicU32::new(DEFAULT_PSCALE.to_bits()),
+            stream_geometry: AtomicBool::new(false),
+            keep_replaced: AtomicBool::new(false),
+            fps: AtomicU32::new(0),
+            start_frame: AtomicI64::new(1),
+            flushes: AtomicUsize::new(0),
+            attribute_names: Mutex::new(AttributeNames::default()),
+            globals: Mutex::new(BTreeMap::new()),
+            drop_behavior: Mutex::new(DropBehavior::default()),
+            hooks: Mutex::new(Arc::default()),
+            names: Interner::default(),
+            unnamed_threads: Mutex::new(HashMap::new()),
+            created: Instant::now(),
+            #[cfg(feature = "enabled")]
+            live_nodes: Mutex::new(HashMap::new()),
+            #[cfg(feature = "enabled")]
+            live_chunks: Mutex::new(BTreeMap::new()),
+            #[cfg(feature = "enabled")]
+            node_labels: Mutex::new(Identifiers::node_labels()),
+            #[cfg(feature = "enabled")]
+            file_nodes: Mutex::new(None),
+            stream: Mutex::new(None),
+            stream_resend: AtomicBool::new(false),
+            #[cfg(feature = "websocket")]
+            websocket: OnceLock::new(),
+        }
+    }
+
+    fn new_with_file(p: PathBuf) -> Result<Self> {
+        if cfg!(not(feature = "enabled")) {
+            return Err(anyhow!(
+                "Writing {} requires the `enabled` feature",
+                p.display()
+            ));
+        }
+        Ok(Self::new(ExportMethod::File { path: p }))
+    }
+
+    fn new_with_stream(address: SocketAddr) -> Self {
+        Self::new(ExportMethod::Stream { address })
+    }
+
+    fn new_remote(address: SocketAddr, source: String) -> Self {
+        Self::new(ExportMethod::Remote { address, source })
+    }
+
+    fn new_in_memory() -> Self {
+        Self::new(ExportMethod::Memory {
+            recording: Mutex::new(Recording::default()),
+            snapshots: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    fn new_with_live_session(options: &mut LiveSessionOptions) -> Result<Self> {
+        let session = OnceLock::new();
+        match options.session.take() {
+            Some(existing) => {
+                let _ = session.set(existing);
+            }
+            None if !options.lazy_connect => {
+                let _ = session.set(Self::connect(options)?);
+            }
+            None => {}
+        }
+
+        Ok(Self::new(ExportMethod::LiveSession {
+            session,
+            path: options.path.clone(),
+            node_name: options.node_name.clone(),
+            check_nodes: options.check_nodes,
+            playback: options.playback,
+        }))
+    }
+
+    /// Keeps trying to connect to the live session in a background thread, and saves everything
+    /// that has been recorded so far once it's connected. Returns the thread, if one was started.
+    fn connect_in_background(
+        self: &Arc<Self>,
+        options: LiveSessionOptions,
+    ) -> Option<JoinHandle<()>> {
+        let ExportMethod::LiveSession { session, .. } = &self.export_method else {
+            return None;
+        };
+        if session.get().is_some() {
+            return None;
+        }
+        let options = LiveSessionOptions {
+            wait_for_houdini: true,
+            ..options
+        };
+        let logger = Arc::clone(self);
+        Some(std::thread::spawn(move || match Self::connect(&options) {
+            Ok(connected) => {
+                if let ExportMethod::LiveSession { session, .. } = &logger.export_method {
+                    let _ = session.set(connected);
+                }
+                logger.save().unwrap_or_else(warn);
+            }
+            Err(e) => warn(e.context("Failed to connect to Houdini")),
+        }))
+    }
+
+    /// Connects to the live session, retrying as configured in the options.
+    #[cfg(feature = "enabled")]
+    fn connect(options: &LiveSessionOptions) -> Result<Session> {
+        let socket = (options.host.as_str(), options.port)
+            .to_socket_addrs()?
+            .find_map(|address| match address {
+                SocketAddr::V4(address) => Some(address),
+                SocketAddr::V6(_) => None,
+            })
+            .ok_or_else(|| anyhow!("Could not resolve {} to an IPv4 address", options.host))?;
+        let start = Instant::now();
+        let mut attempts = 0;
+        loop {
+            let result = match &options.shared_memory {
+                Some(name) => connect_to_memory_server(name, None),
+                None => connect_to_socket(socket, None),
+            };
+            let err = match result {
+                Ok(session) => return Ok(session),
+                Err(err) => err,
+            };
+            attempts += 1;
+            let within_timeout = match options.connect_timeout {
+                Some(timeout) => start.elapsed() + options.retry_interval < timeout,
+                None => false,
+            };
+            if !options.wait_for_houdini && attempts > options.retries && !within_timeout {
+                return Err(err.into());
+            }
+            std::thread::sleep(options.retry_interval);
+        }
+    }
+
+    #[cfg(not(feature = "enabled"))]
+    fn connect(_options: &LiveSessionOptions) -> Result<Session> {
+        Err(anyhow!(
+            "Connecting to Houdini requires the `enabled` feature"
+        ))
+    }
+
+    fn next_frame(&self, label: Option<String>) -> Result<()> {
+        let mut data = lock(&self.data);
+        let completed = data.current_frame();
+        let spilled = data.next_frame(label);
+        drop(data);
+        self.frame_completed(completed);
+        spilled
+    }
+
+    fn push_frame_scope(&self, label: &str) -> Result<()> {
+        let mut data = lock(&self.data);
+        let completed = data.current_frame();
+        data.frame_scopes.push(label.to_string());
+        let spilled = data.next_frame(Some(label.to_string()));
+        drop(data);
+        self.frame_completed(completed);
+        spilled
+    }
+
+    fn pop_frame_scope(&self) -> Result<()> {
+        let mut data = lock(&self.data);
+        let completed = data.current_frame();
+        data.frame_scopes.pop();
+        let label = data.frame_scopes.last().cloned();
+        let spilled = data.next_frame(label);
+        drop(data);
+        self.frame_completed(completed);
+        spilled
+    }
+
+    fn query(&self, filter: &HoulogFilter) -> Vec<RecordedPoint> {
+        let data = lock(&self.data);
+        let names = self.names.names();
+        let start_frame = self.start_frame.load(Ordering::Relaxed);
+        let mut points = Vec::new();
+        for (&frame_index, frame) in &data.frames {
+            if !filter.matches_frame(frame_index) {
+                continue;
+            }
+            for entry in &frame.entries {
+                let name = &names[entry.name.0 as usize];
+                let kind = &names[entry.kind.0 as usize];
+                if filter.matches(name, kind) {
+                    let export = ExportEntry {
+                        time: (start_frame + frame_index as i64) as f32,
+                        frame: Some(frame),
+                        name,
+                        kind,
+                        entry,
+                    };
+                    points.push(export.recorded_point());
+                }
+            }
+        }
+        points
+    }
+
+    fn stats(&self) -> HoulogStats {
+        let data = lock(&self.data);
+        data.stats(&self.names)
+    }
+
+    fn hooks(&self) -> Arc<Hooks> {
+        lock(&self.hooks).clone()
+    }
+
+    /// Calls the `on_frame` hook.
+    fn frame_completed(&self, frame: usize) {
+        if let Some(on_frame) = &self.hooks().on_frame {
+            on_frame(frame as u32);
+        }
+    }
+
+    fn clear(&self) -> Result<()> {
+        lock(&self.data).clear();
+        // The Python receiver still has the frames of the old recording that aren't logged into
+        // again, so it needs the whole recording.
+        self.stream_resend.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn set_max_frames(&self, max_frames: Option<usize>) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.max_frames = max_frames;
+        data.drop_old_frames();
+        Ok(())
+    }
+
+    /// The frame the calling thread currently logs into.
+    fn cursor(&self) -> Result<usize> {
+        let data = lock(&self.data);
+        Ok(data.cursor())
+    }
+
+    /// Returns when the given frame started (`None` if it has been dropped), and the index of the
+    /// given profiling track, assigning a new index if the track hasn't been seen before.
+    fn profile_track(&self, frame: usize, track: &str) -> (Option<Instant>, u32) {
+        let mut data = lock(&self.data);
+        let started = data.frames.get(&frame).map(|frame| frame.started);
+        let next_index = data.profile_tracks.len() as u32;
+        let index = *data
+            .profile_tracks
+            .entry(track.to_string())
+            .or_insert(next_index);
+        (started, index)
+    }
+
+    fn set_frame_time(&self, seconds: f32) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.modified = true;
+        let frame = data.cursor();
+        if let Some(frame) = data.frame_mut(frame) {
+            frame.sim_time = Some(seconds);
+        }
+        Ok(())
+    }
+
+    fn set_frame(&self, frame: usize) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.modified = true;
+        data.current_frame = frame;
+        data.frame_mut(frame);
+        Ok(())
+    }
+
+    #[track_caller]
+    fn entry(&self, name: &str, value: impl DebugLoggable + 'static) -> LogEntry {
+        let pscale = value
+            .pscale()
+            .unwrap_or_else(|| f32::from_bits(self.default_pscale.load(Ordering::Relaxed)));
+        LogEntry {
+            pscale,
+            ..LogEntry::new(&self.names, name, value)
+        }
+    }
+
+    fn log(&self, entry: LogEntry) -> Result<()> {
+        self.push_entry(None, self.tag_thread(entry), false)
+    }
+
+    /// Like [`HoudiniDebugLogger::log`], but replaces the last entry with the same name in the
+    /// frame, see [`houlog_replace`].
+    fn replace(&self, entry: LogEntry) -> Result<()> {
+        let replace = !self.keep_replaced.load(Ordering::Relaxed);
+        self.push_entry(None, self.tag_thread(entry), replace)
+    }
+
+    fn log_to_frame(&self, frame: usize, entry: LogEntry) -> Result<()> {
+        self.push_entry(Some(frame), entry, false)
+    }
+
+    /// Records the thread of entries logged with their own frame cursor.
+    fn tag_thread(&self, entry: LogEntry) -> LogEntry {
+        if THREAD_FRAME.with(|f| f.get()).is_none() {
+            return entry;
+        }
+        let thread = std::thread::current();
+        let key = match thread.name() {
+            Some(name) => ThreadKey::Named(name.to_string()),
+            None => {
+                let mut unnamed_threads = lock(&self.unnamed_threads);
+                let next = unnamed_threads.len();
+                ThreadKey::Unnamed(*unnamed_threads.entry(thread.id()).or_insert(next))
+            }
+        };
+        LogEntry {
+            thread: Some(key),
+            ..entry
+        }
+    }
+
+    /// Adds the entry to the given frame, or to the current frame of the calling thread. With
+    /// `replace`, the entry replaces the last one with the same name in the frame instead.
+    fn push_entry(&self, frame: Option<usize>, entry: LogEntry, replace: bool) -> Result<()> {
+        let plot_value = entry.value.plot_value();
+        let flush = {
+            let mut data = lock(&self.data);
+            data.modified = true;
+            let frame = frame.unwrap_or_else(|| data.cursor());
+            if let Some(value) = plot_value {
+                let point = Vec3::new((frame + 1) as f32, value, 0.0);
+                let points = data
+                    .scalar_channels
+                    .entry(self.names.name(entry.name).to_string())
+                    .or_default();
+                match points.last_mut() {
+                    Some(last) if replace && last.x == point.x => *last = point,
+                    _ => points.push(point),
+                }
+            }
+            if replace {
+                data.replace_entry(frame, entry);
+            } else {
+                data.push_entry(frame, entry);
+            }
+            let flush = data.enforce_memory_budget();
+            data.spill_if_needed()?;
+            flush
+        };
+        if flush {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn set_spill_threshold(&self, threshold: Option<usize>) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.spill_threshold = threshold;
+        data.spill_if_needed()
+    }
+
+    fn set_memory_budget(&self, budget: Option<usize>, policy: MemoryPolicy) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.memory_budget = budget.map(|budget| (budget, policy));
+        if data.enforce_memory_budget() {
+            drop(data);
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Exports the recording into a target of its own and starts over, see [`MemoryPolicy::Flush`].
+    fn flush(&self) -> Result<()> {
+        if let ExportMethod::Stream { .. } = &self.export_method {
+            // The receiver only ever shows the latest recording.
+            self.save()?;
+            return self.clear();
+        }
+        let flush = self.flushes.fetch_add(1, Ordering::Relaxed) + 1;
+        self.snapshot(&format!("flush{}", flush))
+    }
+
+    fn is_channel_enabled(&self, channel: &str) -> bool {
+        !self
+            .disabled_channels
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(channel)
+    }
+
+    #[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+    fn metadata_format(&self) -> MetadataFormat {
+        match self.metadata_format.load(Ordering::Relaxed) {
+            1 => MetadataFormat::Dictionary,
+            _ => MetadataFormat::Json,
+        }
+    }
+
+    #[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+    fn export_layout(&self) -> ExportLayout {
+        match self.export_layout.load(Ordering::Relaxed) {
+            1 => ExportLayout::PerFrame,
+            _ => ExportLayout::Merged,
+        }
+    }
+
+    fn is_level_enabled(&self, level: Level) -> bool {
+        level as u8 >= self.min_level.load(Ordering::Relaxed)
+    }
+
+    fn set_channel_enabled(&self, channel: &str, enabled: bool) -> Result<()> {
+        let mut disabled = self
+            .disabled_channels
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if enabled {
+            disabled.remove(channel);
+        } else {
+            disabled.insert(channel.to_string());
+        }
+        Ok(())
+    }
+
+    fn channels(&self) -> BTreeMap<String, bool> {
+        let mut channels = {
+            let data = lock(&self.data);
+            data.frames
+                .values()
+                .flat_map(|frame| &frame.entries)
+                .filter_map(|entry| entry.channel.clone())
+                .map(|channel| (channel, true))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let disabled = self
+            .disabled_channels
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        for channel in disabled.iter() {
+            channels.insert(channel.clone(), false);
+        }
+        channels
+    }
+
+    /// Returns whether the entry with the given name should be logged by [`houlog_every_n`].
+    fn sample_every_n(&self, name: &str, n: u64) -> Result<bool> {
+        let name = self.names.intern(name);
+        let mut data = lock(&self.data);
+        let count = data.call_counts.entry(name).or_default();
+        let sample = count.is_multiple_of(n.max(1));
+        *count += 1;
+        Ok(sample)
+    }
+
+    /// Returns whether the entry with the given name should be logged by [`houlog_sampled`].
+    fn sample_interval(&self, name: &str, time_secs: f32, interval_secs: f32) -> Result<bool> {
+        let name = self.names.intern(name);
+        let mut data = lock(&self.data);
+        // Intervals that aren't a valid duration, such as NaN or negative ones, log every call.
+        let interval = Duration::try_from_secs_f32(interval_secs)
+            .unwrap_or_default()
+            .as_secs_f32();
+        let last = data.last_sampled.get(&name).copied();
+        if last.is_some_and(|last| (last..last + interval).contains(&time_secs)) {
+            return Ok(false);
+        }
+        data.last_sampled.insert(name, time_secs);
+        Ok(true)
+    }
+
+    /// Returns whether this is the first call from the given call site, either in the whole
+    /// recording or in the current frame.
+    fn first_call(&self, location: &'static Location<'static>, per_frame: bool) -> Result<bool> {
+        let mut data = lock(&self.data);
+        if per_frame {
+            let frame = data.cursor();
+            Ok(data.logged_once_per_frame.insert(location, frame) != Some(frame))
+        } else {
+            Ok(data.logged_once.insert(location))
+        }
+    }
+
+    fn log_metric(&self, name: &str, metric: Metric) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.modified = true;
+        let frame = data.cursor();
+        let Some(frame) = data.frame_mut(frame) else {
+            return Ok(());
+        };
+        let metric = match (frame.metrics.get(name), metric) {
+            (Some(Metric::Counter(total)), Metric::Counter(delta)) => {
+                Metric::Counter(total.saturating_add(delta))
+            }
+            // Gauges keep the last value, and switching the type of a metric starts over.
+            _ => metric,
+        };
+        frame.metrics.insert(name.to_string(), metric);
+        Ok(())
+    }
+
+    fn log_channel_value(&self, name: &str, value: f32) -> Result<()> {
+        let mut data = lock(&self.data);
+        data.modified = true;
+        let frame = (data.cursor() + 1) as f32;
+        data.scalar_channels
+            .entry(name.to_string())
+            .or_default()
+            .push(Vec3::new(frame, value, 0.0));
+        Ok(())
+    }
+
+    fn shutdown(self: &Arc<Self>) -> Result<()> {
+        let behavior = *lock(&self.drop_behavior);
+        let mut still_saving = false;
+        let result = match behavior {
+            DropBehavior::Save => self.save(),
+            DropBehavior::SaveWithTimeout(timeout) => {
+                // The thread keeps running if saving takes too long, it ends with the process.
+                let (sender, receiver) = mpsc::channel();
+                let logger = Arc::clone(self);
+                std::thread::spawn(move || {
+                    let _ = sender.send(logger.save());
+                });
+                receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+                    still_saving = true;
+                    Err(anyhow!("saving took longer than {:?}", timeout))
+                })
+            }
+            DropBehavior::Nothing => Ok(()),
+        };
+        // The global logger is never dropped, so the spill file has to be deleted here. If saving timed
+        // out, the save thread may still be holding the data, and waiting for it would defeat the
+        // timeout, so the spill file is left behind with a warning in that case.
+        if !still_saving {
+            lock(&self.data).discard_spill();
+        } else if let Ok(mut data) = self.data.try_lock() {
+            data.discard_spill();
+        } else {
+            warn::<()>(anyhow!(
+                "The spill file couldn't be deleted because saving is still in progress"
+            ));
+        }
+        result
+    }
+
+    fn save(&self) -> Result<()> {
+        if self.save_modified()? {
+            if let Some(on_save) = &self.hooks().on_save {
+                on_save();
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the recording if it has been modified since the last save, returns whether it has.
+    fn save_modified(&self) -> Result<bool> {
+        if let ExportMethod::LiveSession { session, .. } = &self.export_method {
+            if session.get().is_none() {
+                // Still connecting in the background, which saves once it's done.
+                return Ok(false);
+            }
+        }
+
+        let mut data = lock(&self.data);
+        if !data.modified {
+            // Avoid saving overly often
+            return Ok(false);
+        }
+        data.modified = false;
+        let dirty_frames = std::mem::take(&mut data.dirty_frames);
+        self.export(&mut data, &dirty_frames, None)?;
+        Ok(true)
+    }
+
+    /// Exports the recording under the given name and starts a fresh one, see [`houlog_snapshot`].
+    fn snapshot(&self, name: &str) -> Result<()> {
+        if let ExportMethod::LiveSession { session, .. } = &self.export_method {
+            if session.get().is_none() {
+                return Err(anyhow!(
+                    "Can't take snapshot {} before connecting to Houdini",
+                    name
+                ));
+            }
+        }
+
+        let mut data = lock(&self.data);
+        let frames = data.frames.keys().copied().collect();
+        self.export(&mut data, &frames, Some(name))?;
+        data.clear();
+        Ok(())
+    }
+
+    /// Exports all frames, with `dirty_frames` being the frames that changed since the last
+    /// export. Snapshots go into their own nodes or files, see [`houlog_snapshot`].
+    #[cfg_attr(not(feature = "enabled"), allow(unused_variables))]
+    fn export(
+        &self,
+        data: &mut LoggerData,
+        dirty_frames: &BTreeSet<usize>,
+        snapshot: Option<&str>,
+    ) -> Result<()> {
+        // Merge the entries of threads with their own frame cursor deterministically. The sort is
+        // stable, so entries of the same thread keep their order and entries logged via the global
+        // frame come first.
+        for frame in data.frames.values_mut() {
+            frame.entries.sort_by(|a, b| a.thread.cmp(&b.thread));
+        }
+
+        let spilled_entries = data.restore_spilled()?;
+        let channel_plots = data.channel_plots(&self.names);
+        let metric_entries = data
+            .frames
+            .values()
+            .map(|frame| frame.metric_entries(&self.names))
+            .collect::<Vec<_>>();
+        // Resolved after building the entries above, which can intern new names.
+        let names = self.names.names();
+        let names = names.as_slice();
+        let first_frame = data.first_frame;
+        let end_frame = data.end_frame();
+        let start_frame = self.start_frame.load(Ordering::Relaxed);
+        let entries = data
+            .frames
+            .iter()
+            .zip(spilled_entries.iter().zip(&metric_entries))
+            .flat_map(|((&frame, d), (spilled, metrics))| {
+                spilled
+                    .iter()
+                    .chain(&d.entries)
+                    .chain(metrics)
+                    .map(move |entry| ExportEntry {
+                        time: (start_frame + frame as i64) as f32,
+                        frame: Some(d),
+                        name: &names[entry.name.0 as usize],
+                        kind: &names[entry.kind.0 as usize],
+                        entry,
+                    })
+            })
+            .chain(channel_plots.iter().map(|entry| ExportEntry {
+                time: 0.0,
+                frame: None,
+                name: &names[entry.name.0 as usize],
+                kind: &names[entry.kind.0 as usize],
+                entry,
+            }))
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "websocket")]
+        if let Some(server) = self.websocket.get().filter(|_| snapshot.is_none()) {
+            server.broadcast(self.stream_payload(&entries, None, None)?);
+        }
+
+        #[cfg(feature = "enabled")]
+        if let ExportMethod::LiveSession { session, .. } = &self.export_method {
+            let fps = f32::from_bits(self.fps.load(Ordering::Relaxed));
+            if let Some(session) = session.get().filter(|_| fps > 0.0 && snapshot.is_none()) {
+                // Houdini's frame 1 is at time 0, and the first frame is at the start frame.
+                let last_frame = start_frame + end_frame.max(first_frame + 1) as i64 - 1;
+                let options = TimelineOptions::default()
+                    .with_fps(fps)
+                    .with_start_time((start_frame - 1) as f32 / fps)
+                    .with_end_time((last_frame - 1) as f32 / fps);
+                session.set_timeline_options(options)?;
+            }
+        }
+
+        match &self.export_method {
+            #[cfg(feature = "enabled")]
+            ExportMethod::LiveSession { playback: true, .. } if snapshot.is_none() => {
+                let current_frame = data.current_frame();
+                let current_time = (start_frame + current_frame as i64) as f32;
+                let entries = entries
+                    .into_iter()
+                    .filter(|e| e.frame.is_some() && e.time == current_time)
+                    .collect::<Vec<_>>();
+                self.save_playback(&entries, current_frame)?;
+            }
+            #[cfg(feature = "enabled")]
+            ExportMethod::LiveSession { node_name, .. } => {
+                let dirty_chunks = dirty_frames
+                    .iter()
+                    .map(|frame| frame / FRAMES_PER_CHUNK)
+                    .collect::<BTreeSet<_>>();
+                let present_chunks = data
+                    .frames
+                    .keys()
+                    .map(|frame| frame / FRAMES_PER_CHUNK)
+                    .collect::<BTreeSet<_>>();
+                self.save_live(node_name, snapshot, entries, &dirty_chunks, &present_chunks)?;
+            }
+            #[cfg(feature = "enabled")]
+            ExportMethod::File { path } => {
+                let path = match snapshot {
+                    Some(snapshot) => snapshot_path(path, snapshot),
+                    None => path.clone(),
+                };
+                let path = path
+                    .to_str()
+                    .ok_or_else(|| anyhow!("Could not convert path to string"))?;
+                if self.save_file(path, &entries).is_err() {
+                    // The session might have died, so try again with a new one.
+                    *lock(&self.file_nodes) = None;
+                    self.save_file(path, &entries)?;
+                }
+            }
+            #[cfg(not(feature = "enabled"))]
+            ExportMethod::LiveSession { .. } | ExportMethod::File { .. } => {
+                return Err(anyhow!(
+                    "Exporting to Houdini requires the `enabled` feature"
+                ));
+            }
+            ExportMethod::Stream { .. } if snapshot.is_some() => {
+                return Err(anyhow!("Snapshots aren't supported when streaming"));
+            }
+            ExportMethod::Stream { address } => {
+                // A new connection might be to a restarted receiver, which needs everything.
+                let resend = self.stream_resend.swap(false, Ordering::Relaxed);
+                let payload = if resend || lock(&self.stream).is_none() || data.frames.is_empty() {
+                    self.stream_payload(&entries, None, None)?
+                } else {
+                    // Like the live session, only send the frames that changed since the last
+                    // save, together with the plots spanning the whole recording.
+                    let frame_time = |frame: usize| (start_frame + frame as i64) as f32;
+                    let changed = entries
+                        .iter()
+                        .filter(|e| {
+                            e.frame.is_none()
+                                || dirty_frames.contains(&((e.time as i64 - start_frame) as usize))
+                        })
+                        .copied()
+                        .collect::<Vec<_>>();
+                    let frames = first_frame..end_frame;
+                    let frames = json!({
+                        "first": frame_time(frames.start),
+                        "last": frame_time(frames.end - 1),
+                        "updated": dirty_frames
+                            .range(frames)
+                            .map(|&frame| frame_time(frame))
+                            .collect::<Vec<_>>(),
+                    });
+                    self.stream_payload(&changed, None, Some(frames))?
+                };
+                if let Err(e) = self.send_stream(address, payload) {
+                    // The changed frames are lost, so the next message has to contain everything.
+                    self.stream_resend.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+            }
+            ExportMethod::Remote { address, source } => {
+                // Snapshots show up as clients of their own on the server.
+                let source = match snapshot {
+                    Some(snapshot) => format!("{}_{}", source, snapshot),
+                    None => source.clone(),
+                };
+                self.send_stream(address, self.stream_payload(&entries, Some(&source), None)?)?;
+            }
+            ExportMethod::Memory {
+                recording,
+                snapshots,
+            } => {
+                let exported = Recording {
+                    points: self.recorded_points(&entries),
+                };
+                match snapshot {
+                    Some(snapshot) => {
+                        lock(snapshots).insert(snapshot.to_string(), exported);
+                    }
+                    None => *lock(recording) = exported,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends the recording to the Python receiver or the server as a single message: The length of
+    /// the payload as a big-endian u32, followed by the payload as JSON. The connection is kept open
+    /// between saves and reestablished on the next save if sending fails.
+    fn send_stream(&self, address: &SocketAddr, payload: String) -> Result<()> {
+        let payload = payload.into_bytes();
+        let length = u32::try_from(payload.len()).map_err(|_| {
+            anyhow!(
+                "Can't send {} bytes at once, the maximum is {} bytes",
+                payload.len(),
+                u32::MAX
+            )
+        })?;
+        let mut message = length.to_be_bytes().to_vec();
+        message.extend_from_slice(&payload);
+
+        let mut stream = lock(&self.stream);
+        let mut connection = match stream.take() {
+            Some(connection) => connection,
+            None => TcpStream::connect(address)?,
+        };
+        connection.write_all(&message)?;
+        *stream = Some(connection);
+        Ok(())
+    }
+
+    /// The entries in the form in which they are streamed, with their geometry if
+    /// [`houlog_set_stream_geometry`] is turned on.
+    fn recorded_points(&self, entries: &[ExportEntry]) -> Vec<RecordedPoint> {
+        let stream_geometry = self.stream_geometry.load(Ordering::Relaxed);
+        entries
+            .iter()
+            .map(|e| RecordedPoint {
+                geometry: e.entry.value.geometry().filter(|_| stream_geometry),
+                ..e.recorded_point()
+            })
+            .collect()
+    }
+
+    /// Encodes the entries as JSON for [`HoudiniDebugLogger::send_stream`] and the WebSocket server.
+    /// The source is only set when sending to a [`HoulogServer`](crate::HoulogServer), and `frames`
+    /// only when sending the changed frames to the Python receiver.
+    fn stream_payload(
+        &self,
+        entries: &[ExportEntry],
+        source: Option<&str>,
+        frames: Option<serde_json::Value>,
+    ) -> Result<String> {
+        let mut payload = json!({ "points": self.recorded_points(entries) });
+        if let Some(source) = source {
+            payload["source"] = json!(source);
+        }
+        if let Some(frames) = frames {
+            payload["frames"] = frames;
+        }
+        Ok(serde_json::to_string(&payload)?)
+    }
+}
+
+#[cfg(feature = "enabled")]
+impl HoudiniDebugLogger {
+    /// Saves to the live session. Each channel gets its own node, so that heavy channels can be
+    /// bypassed in Houdini without recooking everything else. Entries without a channel always go
+    /// into the main node.
+    ///
+    /// To keep saves fast as the recording grows, the frames of each channel are split into chunks
+    /// of [`FRAMES_PER_CHUNK`] frames, each stored in its own node (`<node>__chunk<n>`). Only the
+    /// chunks with frames that changed since the last save are uploaded again. The channel plots
+    /// go into `<node>__plots`, and the node with the actual name merges all of them. Counters and
+    /// gauges are kept apart from the geometric entries in `<node>__metrics`, which is split the
+    /// same way.
+    /// With [`ExportLayout::PerFrame`], the merge node takes the chunks and the plots from a Python
+    /// SOP behind each of them (`<chunk>__pack`), which packs the points of each frame.
+    ///
+    /// The nodes of a snapshot get `__snapshot_<name>` appended to their labels. Labels never
+    /// contain `__` otherwise, so none of these generated nodes can collide with the node of a
+    /// channel, for example one named `plots`.
+    fn save_live(
+        &self,
+        node_name: &str,
+        snapshot: Option<&str>,
+        entries: Vec<ExportEntry>,
+        dirty_chunks: &BTreeSet<usize>,
+        present_chunks: &BTreeSet<usize>,
+    ) -> Result<()> {
+        let ExportMethod::LiveSession {
+            session,
+            path,
+            check_nodes,
+            ..
+        } = &self.export_method
+        else {
+            return Ok(());
+        };
+        let Some(session) = session.get() else {
+            return Ok(());
+        };
+
+        let start_frame = self.start_frame.load(Ordering::Relaxed);
+        let LiveLayout {
+            mut nodes,
+            orig_names,
+        } = live_layout(
+            node_name,
+            snapshot,
+            entries,
+            start_frame,
+            &mut lock(&self.node_labels),
+        );
+
+        let mut live_chunks = lock(&self.live_chunks);
+        let live_chunks = live_chunks
+            .entry(format!("{}{}", node_name, snapshot_suffix(snapshot)))
+            .or_default();
+        // Channels that have been exported before must be updated even if they are empty now.
+        for name in live_chunks.keys() {
+            nodes.entry(name.clone()).or_default();
+        }
+
+        for (name, mut chunks) in nodes {
+            let orig_names = orig_names
+                .get(&name)
+                .map(|orig_name| BTreeMap::from([(name.clone(), orig_name.clone())]))
+                .unwrap_or_default();
+            let written = live_chunks.entry(name.clone()).or_default();
+            let previous_inputs = written.len() + 1;
+            let mut changed = false;
+
+            // Chunks of dropped or cleared frames
+            let stale = written
+                .difference(present_chunks)
+                .copied()
+                .collect::<Vec<_>>();
+            for chunk in stale {
+                self.delete_live_node(&pack_label(&chunk_label(&name, chunk)))?;
+                self.delete_live_node(&chunk_label(&name, chunk))?;
+                written.remove(&chunk);
+                changed = true;
+            }
+
+            for &chunk in dirty_chunks.intersection(present_chunks) {
+                let entries = chunks.remove(&Some(chunk));
+                if entries.is_none() && !written.contains(&chunk) {
+                    continue;
+                }
+                let chunk_name = chunk_label(&name, chunk);
+                let node = self.live_node(session, path, &chunk_name, "null", *check_nodes)?;
+                self.write_geometry(&node, &entries.unwrap_or_default(), orig_names.clone())?;
+                changed |= written.insert(chunk);
+            }
+
+            let plots_name = plots_label(&name);
+            let plots = self.live_node(session, path, &plots_name, "null", *check_nodes)?;
+            let plot_entries = chunks.remove(&None).unwrap_or_default();
+            self.write_geometry(&plots, &plot_entries, orig_names)?;
+
+            let cached = lock(&self.live_nodes).contains_key(&name);
+            let merge = self.live_node(session, path, &name, "merge", *check_nodes)?;
+            if changed || !cached {
+                let mut inputs =
+                    vec![self.live_output(session, path, plots, &plots_name, *check_nodes)?];
+                for &chunk in written.iter() {
+                    let chunk_name = chunk_label(&name, chunk);
+                    let node = self.live_node(session, path, &chunk_name, "null", *check_nodes)?;
+                    inputs.push(self.live_output(
+                        session,
+                        path,
+                        node,
+                        &chunk_name,
+                        *check_nodes,
+                    )?);
+                }
+                for (i, input) in inputs.iter().enumerate() {
+                    merge.connect_input(i as i32, input.handle, 0)?;
+                }
+                for i in inputs.len()..previous_inputs {
+                    merge.disconnect_input(i as i32)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the geometry of the recording node with the entries of the given frame and moves the
+    /// playbar there, see [`LiveSessionOptions::with_playback`].
+    fn save_playback(&self, entries: &[ExportEntry], frame: usize) -> Result<()> {
+        let ExportMethod::LiveSession {
+            session,
+            path,
+            node_name,
+            check_nodes,
+            ..
+        } = &self.export_method
+        else {
+            return Ok(());
+        };
+        let Some(session) = session.get() else {
+            return Ok(());
+        };
+
+        let label = lock(&self.node_labels).get(node_name);
+        let mut orig_names = BTreeMap::new();
+        if &label != node_name {
+            orig_names.insert(label.clone(), node_name.clone());
+        }
+        let node = self.live_node(session, path, &label, "null", *check_nodes)?;
+        self.write_geometry(&node, entries, orig_names)?;
+
+        // Houdini's frame 1 is at time 0, and the first frame is at the start frame.
+        let fps = session.get_timeline_options()?.fps();
+        let start_frame = self.start_frame.load(Ordering::Relaxed);
+        session.set_time((start_frame - 1 + frame as i64) as f64 / fps as f64)?;
+        Ok(())
+    }
+
+    /// Deletes a node created via [`HoudiniDebugLogger::live_node`].
+    fn delete_live_node(&self, node_name: &str) -> Result<()> {
+        let node = lock(&self.live_nodes).remove(node_name);
+        if let Some(node) = node {
+            node.delete()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the entries as points into the geometry of the given node. `orig_names` maps
+    /// sanitized identifiers, such as the label of the node, to the names they were created from.
+    /// Together with the renamed custom attributes, it ends up in the `orig_name` detail attribute.
+    fn write_geometry(
+        &self,
+        node: &HoudiniNode,
+        entries: &[ExportEntry],
+        mut orig_names: BTreeMap<String, String>,
+    ) -> Result<Geometry> {
+        node.cook()?;
+        let geom = node
+            .geometry()?
+            .ok_or_else(|| anyhow!("No geometry on node"))?;
+
+        let part_info = PartInfo::default()
+            .with_part_type(PartType::Mesh)
+            .with_point_count(entries.len() as i32)
+            .with_face_count(0)
+            .with_vertex_count(0);
+
+        geom.set_part_info(&part_info)?;
+
+        let names = lock(&self.attribute_names).clone();
+        Self::add_positions(&geom, entries, &names)?;
+        Self::add_pscales(&geom, entries, &names)?;
+        Self::add_orients(&geom, entries, &names)?;
+        Self::add_velocities(&geom, entries, &names)?;
+        Self::add_normals(&geom, entries, &names)?;
+        Self::add_names(&geom, entries, &names)?;
+        Self::add_name_paths(&geom, entries, &names)?;
+        Self::add_frame_times(&geom, entries, &names)?;
+        Self::add_frame_labels(&geom, entries, &names)?;
+        Self::add_sim_times(&geom, entries, &names)?;
+        Self::add_metadata(&geom, entries, &names, self.metadata_format())?;
+        Self::add_kinds(&geom, entries, &names)?;
+        Self::add_channels(&geom, entries, &names)?;
+        Self::add_levels(&geom, entries, &names)?;
+        Self::add_timestamps(&geom, entries, &names)?;
+        Self::add_ids(&geom, entries, &names)?;
+        Self::add_source_locations(&geom, entries, &names)?;
+        if self.stream_geometry.load(Ordering::Relaxed) {
+            Self::add_entry_geometry(&geom, entries)?;
+        }
+        orig_names.extend(Self::add_custom_attributes(&geom, entries, &names)?);
+        orig_names.extend(self.add_globals(&geom, &names)?);
+        orig_names.extend(Self::add_groups(&geom, entries)?);
+        Self::add_orig_names(&geom, &orig_names, &names)?;
+        Self::add_attribute_names(&geom, &names)?;
+        if self.export_layout() == ExportLayout::PerFrame {
+            Self::add_frame_groups(&geom, entries)?;
+        }
+
+        geom.commit()?;
+
+        Ok(geom)
+    }
+
+    fn add_positions(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_positions = map_entries(entries, |e| {
+            let v = e.entry.position;
+            [v.x, v.y, v.z]
+        })
+        .into_iter()
+        .flatten()
+        .collect::<Vec<f32>>();
+
+        let p_attr_info = AttributeInfo::default()
+            .with_count(point_positions.len() as i32 / 3)
+            .with_tuple_size(3)
+            .with_storage(StorageType::Float)
+            .with_type_info(AttributeTypeInfo::Point)
+            .with_owner(AttributeOwner::Point);
+
+        let p_attrib = geom.add_numeric_attribute::<f32>(&names.get("P"), 0, p_attr_info)?;
+
+        if !point_positions.is_empty() {
+            p_attrib.set(0, &point_positions)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_pscales(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_pscales = entries.iter().map(|e| e.entry.pscale).collect::<Vec<f32>>();
+
+        Self::add_float_attribute(geom, &names.get("pscale"), &point_pscales)
+    }
+
+    fn add_orients(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_orients = entries
+            .iter()
+            .flat_map(|e| e.entry.orient.to_array())
+            .collect::<Vec<f32>>();
+
+        let attr_info = AttributeInfo::default()
+            .with_count(entries.len() as i32)
+            .with_tuple_size(4)
+            .with_storage(StorageType::Float)
+            .with_type_info(AttributeTypeInfo::Quaternion)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<f32>(&names.get("orient"), 0, attr_info)?;
+
+        if !point_orients.is_empty() {
+            attrib.set(0, &point_orients)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_velocities(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_velocities = entries
+            .iter()
+            .flat_map(|e| e.entry.velocity.to_array())
+            .collect::<Vec<f32>>();
+
+        let attr_info = AttributeInfo::default()
+            .with_count(entries.len() as i32)
+            .with_tuple_size(3)
+            .with_storage(StorageType::Float)
+            .with_type_info(AttributeTypeInfo::Vector)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<f32>(&names.get("v"), 0, attr_info)?;
+
+        if !point_velocities.is_empty() {
+            attrib.set(0, &point_velocities)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_normals(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_normals = entries
+            .iter()
+            .flat_map(|e| e.entry.normal.to_array())
+            .collect::<Vec<f32>>();
+
+        let attr_info = AttributeInfo::default()
+            .with_count(entries.len() as i32)
+            .with_tuple_size(3)
+            .with_storage(StorageType::Float)
+            .with_type_info(AttributeTypeInfo::Normal)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<f32>(&names.get("N"), 0, attr_info)?;
+
+        if !point_normals.is_empty() {
+            attrib.set(0, &point_normals)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_names(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_names = map_entries(entries, |e| e.name.to_string());
+
+        Self::add_string_attribute(geom, &names.get("name"), &point_names)
+    }
+
+    fn add_kinds(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_kinds = map_entries(entries, |e| e.kind.to_string());
+
+        Self::add_string_attribute(geom, &names.get("kind"), &point_kinds)
+    }
+
+    fn add_channels(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_channels = entries
+            .iter()
+            .map(|e| e.entry.channel.clone().unwrap_or_default())
+            .collect::<Vec<String>>();
+
+        Self::add_string_attribute(geom, &names.get("channel"), &point_channels)
+    }
+
+    fn add_levels(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_levels = entries
+            .iter()
+            .map(|e| e.entry.level as i32)
+            .collect::<Vec<i32>>();
+
+        Self::add_int_attribute(geom, &names.get("level"), &point_levels)
+    }
+
+    fn add_frame_times(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_times = entries.iter().map(|e| e.time).collect::<Vec<f32>>();
+
+        Self::add_float_attribute(geom, &names.get("time"), &point_times)
+    }
+
+    fn add_frame_labels(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_labels = entries
+            .iter()
+            .map(|e| {
+                e.frame
+                    .and_then(|frame| frame.label.clone())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<String>>();
+
+        Self::add_string_attribute(geom, &names.get("frame_label"), &point_labels)
+    }
+
+    fn add_sim_times(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_sim_times = entries
+            .iter()
+            .map(|e| e.frame.and_then(|frame| frame.sim_time).unwrap_or(0.0))
+            .collect::<Vec<f32>>();
+
+        Self::add_float_attribute(geom, &names.get("sim_time"), &point_sim_times)
+    }
+
+    /// Adds the wall-clock time of each entry as seconds since the unix epoch. This needs double
+    /// precision, a 32 bit float would only be accurate to about two minutes.
+    fn add_timestamps(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_timestamps = entries
+            .iter()
+            .map(|e| {
+                e.entry
+                    .timestamp
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map(|d| d.as_secs_f64())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<f64>>();
+
+        let attr_info = AttributeInfo::default()
+            .with_count(point_timestamps.len() as i32)
+            .with_tuple_size(1)
+            .with_storage(StorageType::Float64)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<f64>(&names.get("realtime"), 0, attr_info)?;
+
+        if !point_timestamps.is_empty() {
+            attrib.set(0, &point_timestamps)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_ids(geom: &Geometry, entries: &[ExportEntry], names: &AttributeNames) -> Result<()> {
+        let point_ids = entries
+            .iter()
+            .map(|e| e.entry.id.map(|id| id as i64).unwrap_or(-1))
+            .collect::<Vec<i64>>();
+
+        let attr_info = AttributeInfo::default()
+            .with_count(point_ids.len() as i32)
+            .with_tuple_size(1)
+            .with_storage(StorageType::Int64)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<i64>(&names.get("id"), 0, attr_info)?;
+
+        if !point_ids.is_empty() {
+            attrib.set(0, &point_ids)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds the source location of the logging call as `src_file` and `src_line`, so that the code
+    /// which produced an entry can be found from within Houdini.
+    fn add_source_locations(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let point_files = entries
+            .iter()
+            .map(|e| e.entry.location.file.to_string())
+            .collect::<Vec<String>>();
+        let point_lines = entries
+            .iter()
+            .map(|e| e.entry.location.line as i32)
+            .collect::<Vec<i32>>();
+
+        Self::add_string_attribute(geom, &names.get("src_file"), &point_files)?;
+        Self::add_int_attribute(geom, &names.get("src_line"), &point_lines)
+    }
+
+    /// Adds the geometry of the entries as JSON in [`ENTRY_GEOMETRY_ATTRIBUTE`], see
+    /// [`houlog_set_stream_geometry`]. Entries without geometry get an empty string.
+    fn add_entry_geometry(geom: &Geometry, entries: &[ExportEntry]) -> Result<()> {
+        let geometry = entries
+            .iter()
+            .map(|e| match e.entry.value.geometry() {
+                Some(geometry) => serde_json::to_string(&geometry),
+                None => Ok(String::new()),
+            })
+            .collect::<serde_json::Result<Vec<String>>>()?;
+        Self::add_string_attribute(geom, ENTRY_GEOMETRY_ATTRIBUTE, &geometry)
+    }
+
+    /// Adds the attributes set via [`EntryBuilder`]. The type of each attribute is determined by
+    /// the first entry that sets it. Attribute names are sanitized via [`Identifiers`], the ones
+    /// that had to be changed are returned, mapped to their original names.
+    fn add_custom_attributes(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<BTreeMap<String, String>> {
+        let mut attributes = BTreeMap::<&str, &AttributeValue>::new();
+        for e in entries {
+            for (name, value) in &e.entry.attributes {
+                attributes.entry(name.as_str()).or_insert(value);
+            }
+        }
+
+        let reserved = BUILTIN_ATTRIBUTES
+            .iter()
+            .map(|name| names.get(name))
+            .collect::<Vec<_>>();
+        let reserved = reserved.iter().map(String::as_str).collect::<Vec<_>>();
+        let mut identifiers = Identifiers::with_reserved(&reserved);
+        let mut orig_names = BTreeMap::new();
+        for (name, first_value) in attributes {
+            let identifier = identifiers.get(name);
+            if identifier != name {
+                orig_names.insert(identifier.clone(), name.to_string());
+            }
+            match first_value {
+                AttributeValue::Float(_) => {
+                    let values = entries
+                        .iter()
+                        .map(|e| match e.entry.attribute(name) {
+                            Some(AttributeValue::Float(v)) => *v,
+                            _ => 0.0,
+                        })
+                        .collect::<Vec<f32>>();
+                    Self::add_float_attribute(geom, &identifier, &values)?;
+                }
+                AttributeValue::Int(_) => {
+                    let values = entries
+                        .iter()
+                        .map(|e| match e.entry.attribute(name) {
+                            Some(AttributeValue::Int(v)) => *v,
+                            _ => 0,
+                        })
+                        .collect::<Vec<i32>>();
+                    Self::add_int_attribute(geom, &identifier, &values)?;
+                }
+                AttributeValue::String(_) => {
+                    let values = entries
+                        .iter()
+                        .map(|e| match e.entry.attribute(name) {
+                            Some(AttributeValue::String(v)) => v.clone(),
+                            _ => String::new(),
+                        })
+                        .collect::<Vec<String>>();
+                    Self::add_string_attribute(geom, &identifier, &values)?;
+                }
+            }
+        }
+
+        Ok(orig_names)
+    }
+
+    /// Adds the `orig_name` detail attribute, a JSON object that maps sanitized identifiers back to
+    /// the names they were created from. It's only added if something had to be renamed.
+    fn add_orig_names(
+        geom: &Geometry,
+        orig_names: &BTreeMap<String, String>,
+        names: &AttributeNames,
+    ) -> Result<()> {
+        if orig_names.is_empty() {
+            return Ok(());
+        }
+
+        let attr_info = AttributeInfo::default()
+            .with_count(1)
+            .with_tuple_size(1)
+            .with_storage(StorageType::String)
+            .with_owner(AttributeOwner::Detail);
+
+        let attrib = geom.add_string_attribute(&names.get("orig_name"), 0, attr_info)?;
+        attrib.set(0, &[serde_json::to_string(orig_names)?.as_str()])?;
+
+        Ok(())
+    }
+
+    /// Records the built-in attributes that have been renamed, see [`ATTRIBUTE_NAMES_ATTRIBUTE`].
+    fn add_attribute_names(geom: &Geometry, names: &AttributeNames) -> Result<()> {
+        let renamed = BUILTIN_ATTRIBUTES
+            .iter()
+            .map(|builtin| (builtin.to_string(), names.get(builtin)))
+            .filter(|(builtin, name)| builtin != name)
+            .collect::<BTreeMap<_, _>>();
+        if renamed.is_empty() {
+            return Ok(());
+        }
+
+        let attr_info = AttributeInfo::default()
+            .with_count(1)
+            .with_tuple_size(1)
+            .with_storage(StorageType::String)
+            .with_owner(AttributeOwner::Detail);
+
+        let attrib = geom.add_string_attribute(ATTRIBUTE_NAMES_ATTRIBUTE, 0, attr_info)?;
+        attrib.set(0, &[serde_json::to_string(&renamed)?.as_str()])?;
+
+        Ok(())
+    }
+
+    /// Adds the values set via [`houlog_set_global`] as detail attributes. Returns the original
+    /// names of the keys that had to be sanitized.
+    fn add_globals(
+        &self,
+        geom: &Geometry,
+        names: &AttributeNames,
+    ) -> Result<BTreeMap<String, String>> {
+        let globals = lock(&self.globals);
+        let orig_name = names.get("orig_name");
+        let mut reserved = vec![orig_name.as_str(), ATTRIBUTE_NAMES_ATTRIBUTE];
+        let mut values = Vec::new();
+        let fps = f32::from_bits(self.fps.load(Ordering::Relaxed));
+        if fps > 0.0 {
+            // The timeline takes precedence, globals with the same names are renamed.
+            reserved.extend(["fps", "start_frame"]);
+            let start_frame = self.start_frame.load(Ordering::Relaxed);
+            values.push(("fps".to_string(), json!(fps)));
+            values.push(("start_frame".to_string(), json!(start_frame)));
+        }
+        let mut identifiers = Identifiers::with_reserved(&reserved);
+        let mut orig_names = BTreeMap::new();
+        for (key, value) in globals.iter() {
+            let identifier = identifiers.get(key);
+            if &identifier != key {
+                orig_names.insert(identifier.clone(), key.clone());
+            }
+            values.push((identifier, value.clone()));
+        }
+
+        for (identifier, value) in &values {
+            let attr_info = AttributeInfo::default()
+                .with_count(1)
+                .with_tuple_size(1)
+                .with_owner(AttributeOwner::Detail);
+            match value {
+                Value::Bool(v) => {
+                    let attr_info = attr_info.with_storage(StorageType::Int);
+                    let attrib = geom.add_numeric_attribute::<i32>(identifier, 0, attr_info)?;
+                    attrib.set(0, &[*v as i32])?;
+                }
+                Value::Number(v) if v.is_f64() => {
+                    let attr_info = attr_info.with_storage(StorageType::Float64);
+                    let attrib = geom.add_numeric_attribute::<f64>(identifier, 0, attr_info)?;
+                    attrib.set(0, &[v.as_f64().unwrap_or_default()])?;
+                }
+                Value::Number(v) => {
+                    // Large unsigned values such as seeds wrap around, but keep their bits.
+                    let v = v
+                        .as_i64()
+                        .or_else(|| v.as_u64().map(|v| v as i64))
+                        .unwrap_or_default();
+                    let attr_info = attr_info.with_storage(StorageType::Int64);
+                    let attrib = geom.add_numeric_attribute::<i64>(identifier, 0, attr_info)?;
+                    attrib.set(0, &[v])?;
+                }
+                value => {
+                    let value = match value {
+                        Value::String(v) => v.clone(),
+                        value => value.to_string(),
+                    };
+                    let attr_info = attr_info.with_storage(StorageType::String);
+                    let attrib = geom.add_string_attribute(identifier, 0, attr_info)?;
+                    attrib.set(0, &[value.as_str()])?;
+                }
+            }
+        }
+        Ok(orig_names)
+    }
+
+    fn add_metadata(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+        format: MetadataFormat,
+    ) -> Result<()> {
+        let pt_metadata = map_entries(entries, |e| e.entry.value.as_json());
+
+        match format {
+            MetadataFormat::Json => {
+                Self::add_string_attribute(geom, &names.get("metadata"), &pt_metadata)
+            }
+            MetadataFormat::Dictionary => {
+                let attr_info = AttributeInfo::default()
+                    .with_count(pt_metadata.len() as i32)
+                    .with_tuple_size(1)
+                    .with_storage(StorageType::Dictionary)
+                    .with_owner(AttributeOwner::Point);
+
+                let attrib = geom.add_dictionary_attribute(&names.get("metadata"), 0, attr_info)?;
+
+                if !pt_metadata.is_empty() {
+                    attrib.set(
+                        0,
+                        pt_metadata
+                            .iter()
+                            .map(|value| value.as_str())
+                            .collect::<Vec<_>>()
+                            .as_slice(),
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Names can be `/`-separated paths such as `ai/agent_3/path`. This adds the first and the last
+    /// segment of the path as `path_root` and `path_leaf`, see [`name_path`].
+    fn add_name_paths(
+        geom: &Geometry,
+        entries: &[ExportEntry],
+        names: &AttributeNames,
+    ) -> Result<()> {
+        let (point_roots, point_leaves): (Vec<String>, Vec<String>) = entries
+            .iter()
+            .map(|e| {
+                let (root, leaf) = name_path(e.name);
+                (root.to_string(), leaf.to_string())
+            })
+            .unzip();
+
+        Self::add_string_attribute(geom, &names.get("path_root"), &point_roots)?;
+        Self::add_string_attribute(geom, &names.get("path_leaf"), &point_leaves)
+    }
+
+    /// Adds the point groups of [`point_groups`]. Returns the original names of the groups that had
+    /// to be sanitized.
+    fn add_groups(geom: &Geometry, entries: &[ExportEntry]) -> Result<BTreeMap<String, String>> {
+        let (groups, orig_names) = point_groups(entries);
+        for (group_name, membership) in groups {
+            geom.add_group(0, GroupType::Point, &group_name, Some(&membership))?;
+        }
+        Ok(orig_names)
+    }
+
+    /// Adds a point group for each frame, see [`ExportLayout::PerFrame`].
+    fn add_frame_groups(geom: &Geometry, entries: &[ExportEntry]) -> Result<()> {
+        let mut membership = vec![0; entries.len()];
+        for (group_name, ranges) in frame_groups(entries) {
+            for range in &ranges {
+                membership[range.clone()].fill(1);
+            }
+            geom.add_group(0, GroupType::Point, &group_name, Some(&membership))?;
+            for range in ranges {
+                membership[range].fill(0);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds a float point attribute with one value per point.
+    fn add_float_attribute(geom: &Geometry, name: &str, values: &[f32]) -> Result<()> {
+        let attr_info = AttributeInfo::default()
+            .with_count(values.len() as i32)
+            .with_tuple_size(1)
+            .with_storage(StorageType::Float)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<f32>(name, 0, attr_info)?;
+
+        if !values.is_empty() {
+            attrib.set(0, values)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds an integer point attribute with one value per point.
+    fn add_int_attribute(geom: &Geometry, name: &str, values: &[i32]) -> Result<()> {
+        let attr_info = AttributeInfo::default()
+            .with_count(values.len() as i32)
+            .with_tuple_size(1)
+            .with_storage(StorageType::Int)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_numeric_attribute::<i32>(name, 0, attr_info)?;
+
+        if !values.is_empty() {
+            attrib.set(0, values)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a string point attribute with one value per point.
+    fn add_string_attribute(geom: &Geometry, name: &str, values: &[String]) -> Result<()> {
+        let attr_info = AttributeInfo::default()
+            .with_count(values.len() as i32)
+            .with_tuple_size(1)
+            .with_storage(StorageType::String)
+            .with_owner(AttributeOwner::Point);
+
+        let attrib = geom.add_string_attribute(name, 0, attr_info)?;
+
+        if !values.is_empty() {
+            attrib.set(
+                0,
+                values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Vec<_>>()
+                    .as_slice(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the node with the given name and type in the live session. The node is only created
+    /// if it doesn't exist yet and is rewritten in place afterwards, so that wiring and display
+    /// flags in the Houdini scene are kept. Unless `check_nodes` is false, cached nodes are checked
+    /// for whether they still exist, which costs a round trip to Houdini.
+    fn live_node(
+        &self,
+        session: &Session,
+        path: &str,
+        node_name: &str,
+        node_type: &str,
+        check_nodes: bool,
+    ) -> Result<HoudiniNode> {
+        let mut live_nodes = lock(&self.live_nodes);
+        if let Some(node) = live_nodes.get(node_name) {
+            if !check_nodes || node.is_valid()? {
+                return Ok(node.clone());
+            }
+        }
+
+        let parent = session
+            .get_node_from_path(path, None)?
+            .ok_or_else(|| anyhow!("Node {} doesn't exist", path))?;
+        let node = match session.get_node_from_path(node_name, Some(parent.handle))? {
+            Some(node) => node,
+            None => session
+                .node_builder(node_type)
+                .with_parent(parent)
+                .with_label(node_name)
+                .create()?,
+        };
+        live_nodes.insert(node_name.to_string(), node.clone());
+        Ok(node)
+    }
+
+    fn save_file(&self, path: &str, entries: &[ExportEntry]) -> Result<()> {
+        let (node, pack) = self.file_nodes()?;
+        let geom = self.write_geometry(&node, entries, BTreeMap::new())?;
+        if self.packs_geometry() {
+            pack.cook()?;
+            let packed = pack
+                .geometry()?
+                .ok_or_else(|| anyhow!("No geometry on node"))?;
+            packed.save_to_file(path)?;
+        } else {
+            geom.save_to_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the node used for writing files, together with the Python SOP that packs its
+    /// points, see [`HoudiniDebugLogger::packs_geometry`]. Starting a session takes a few seconds,
+    /// so the session and the nodes are kept around and reused for subsequent saves.
+    fn file_nodes(&self) -> Result<(HoudiniNode, HoudiniNode)> {
+        let mut file_nodes = lock(&self.file_nodes);
+        if let Some(nodes) = &*file_nodes {
+            return Ok(nodes.clone());
+        }
+
+        let session = quick_session(None)?;
+        let parent = session.create_node("Object/geo")?;
+        let node = session
+            .node_builder("null")
+            .with_parent(parent.clone())
+            .create()?;
+        let pack = session
+            .node_builder("python")
+            .with_parent(parent)
+            .create()?;
+        set_python_code(&pack, PACK_CODE)?;
+        pack.connect_input(0, node.handle, 0)?;
+        *file_nodes = Some((node.clone(), pack.clone()));
+        Ok((node, pack))
+    }
+
+    /// Whether the exported points have to go through a Python SOP running [`PACK_CODE`], because
+    /// the input geometry of Houdini Engine can't hold the parts of [`ExportLayout::PerFrame`] or
+    /// the packed primitives of [`houlog_set_stream_geometry`].
+    fn packs_geometry(&self) -> bool {
+        self.export_layout() == ExportLayout::PerFrame
+            || self.stream_geometry.load(Ordering::Relaxed)
+    }
+
+    /// Returns the node that the merge node of a live recording takes the points of the given
+    /// node from. That's the node itself, unless the points have to be packed, see
+    /// [`HoudiniDebugLogger::packs_geometry`], in which case it's a Python SOP
+    /// (`<node>__pack`) behind it.
+    fn live_output(
+        &self,
+        session: &Session,
+        path: &str,
+        node: HoudiniNode,
+        node_name: &str,
+        check_nodes: bool,
+    ) -> Result<HoudiniNode> {
+        if !self.packs_geometry() {
+            return Ok(node);
+        }
+        let pack_name = pack_label(node_name);
+        let pack = self.live_node(session, path, &pack_name, "python", check_nodes)?;
+        set_python_code(&pack, PACK_CODE)?;
+        pack.connect_input(0, node.handle, 0)?;
+        Ok(pack)
+    }
+}
+
+/// The entries of a live recording by the label of the node they go into, see
+/// [`HoudiniDebugLogger::save_live`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+struct LiveLayout<'a> {
+    /// The entries of each node by chunk, with the channel plots in the `None` chunk. The main node
+    /// is always there, even without any entries.
+    nodes: BTreeMap<String, BTreeMap<Option<usize>, Vec<ExportEntry<'a>>>>,
+    /// The original names of the nodes whose label had to be sanitized.
+    orig_names: BTreeMap<String, String>,
+}
+
+/// Splits the entries of a live recording into the nodes of their channels and into chunks of
+/// [`FRAMES_PER_CHUNK`] frames, see [`HoudiniDebugLogger::save_live`]. The labels of the nodes
+/// stay the same across saves as long as the same `labels` are passed.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn live_layout<'a>(
+    node_name: &str,
+    snapshot: Option<&str>,
+    entries: Vec<ExportEntry<'a>>,
+    start_frame: i64,
+    labels: &mut Identifiers,
+) -> LiveLayout<'a> {
+    let mut nodes = BTreeMap::<String, BTreeMap<Option<usize>, Vec<ExportEntry>>>::new();
+    let mut orig_names = BTreeMap::<String, String>::new();
+    let snapshot_suffix = snapshot_suffix(snapshot);
+    let mut label = |name: String| {
+        let label = labels.get(&name) + &snapshot_suffix;
+        if label != name {
+            orig_names.insert(label.clone(), name);
+        }
+        label
+    };
+    nodes.insert(label(node_name.to_string()), BTreeMap::new());
+    for e in entries {
+        let name = match &e.entry.channel {
+            _ if e.entry.metric => metrics_label(&label(node_name.to_string())),
+            Some(channel) => label(format!("{}_{}", node_name, channel)),
+            None => label(node_name.to_string()),
+        };
+        let chunk = e
+            .frame
+            .map(|_| (e.time as i64 - start_frame) as usize / FRAMES_PER_CHUNK);
+        nodes
+            .entry(name)
+            .or_default()
+            .entry(chunk)
+            .or_default()
+            .push(e);
+    }
+    LiveLayout { nodes, orig_names }
+}
+
+/// What's appended to the labels of the live nodes of a snapshot, or nothing for the recording
+/// itself.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn snapshot_suffix(snapshot: Option<&str>) -> String {
+    snapshot
+        .map(|snapshot| format!("__snapshot_{}", sanitize_node_label(snapshot)))
+        .unwrap_or_default()
+}
+
+/// The label of the live node holding a chunk of the frames of a node.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn chunk_label(node: &str, chunk: usize) -> String {
+    format!("{}__chunk{}", node, chunk)
+}
+
+/// The label of the live node holding the channel plots of a node.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn plots_label(node: &str) -> String {
+    format!("{}__plots", node)
+}
+
+/// The label of the live node holding the counters and gauges of a node.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn metrics_label(node: &str) -> String {
+    format!("{}__metrics", node)
+}
+
+/// The label of the Python SOP that packs the points of a live node, see
+/// [`HoudiniDebugLogger::live_output`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn pack_label(node: &str) -> String {
+    format!("{}__pack", node)
+}
+
+/// A point group per kind (`kind_vec3`, ...) and per channel (`chan_physics`, ...), so that data
+/// can be isolated using the group field of any SOP. Returns the membership of each group by its
+/// identifier, and the original names of the groups that had to be sanitized. Like the custom
+/// attributes, group names that sanitize to the same identifier, such as `chan_a-b` and
+/// `chan_a_b`, get a numbered suffix instead of being merged into one group.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn point_groups(entries: &[ExportEntry]) -> (BTreeMap<String, Vec<i32>>, BTreeMap<String, String>) {
+    let mut members = BTreeMap::<String, Vec<usize>>::new();
+    for (i, e) in entries.iter().enumerate() {
+        members
+            .entry(format!("kind_{}", e.kind))
+            .or_default()
+            .push(i);
+        if let Some(channel) = &e.entry.channel {
+            members
+                .entry(format!("chan_{}", channel))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut identifiers = Identifiers::with_reserved(&[]);
+    let mut groups = BTreeMap::new();
+    let mut orig_names = BTreeMap::new();
+    for (name, indices) in members {
+        let identifier = identifiers.get(&name);
+        if identifier != name {
+            orig_names.insert(identifier.clone(), name);
+        }
+        let mut membership = vec![0; entries.len()];
+        for i in indices {
+            membership[i] = 1;
+        }
+        groups.insert(identifier, membership);
+    }
+    (groups, orig_names)
+}
+
+/// Groups the entries into the frames they belong to, as the `time` of the frame together with the
+/// ranges of their indices. Entries that don't belong to a frame, such as the channel plots, are in
+/// frame 0. The entries of a frame are usually next to each other, but a frame can come up more
+/// than once otherwise.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn frame_ranges(entries: &[ExportEntry]) -> Vec<(i32, Range<usize>)> {
+    let mut frames = Vec::<(i32, Range<usize>)>::new();
+    for (i, e) in entries.iter().enumerate() {
+        let frame = e.time as i32;
+        match frames.last_mut() {
+            Some((last, range)) if *last == frame && range.end == i => range.end = i + 1,
+            _ => frames.push((frame, i..i + 1)),
+        }
+    }
+    frames
+}
+
+/// The frame data of a point read back from a recording, which only holds the label and the
+/// simulation time of its frame, see [`write_bgeo`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn recorded_frame(point: &RecordedPoint) -> FrameData {
+    FrameData {
+        label: Some(point.frame_label.clone()).filter(|label| !label.is_empty()),
+        sim_time: Some(point.sim_time).filter(|&sim_time| sim_time != 0.0),
+        ..FrameData::new()
+    }
+}
+
+/// Turns a point read back from a recording into an entry again, see [`write_bgeo`]. The metadata
+/// is kept as it is, like for the entries restored from a spill file.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn recorded_entry(names: &Interner, point: &RecordedPoint) -> LogEntry {
+    LogEntry {
+        name: names.intern(&point.name),
+        kind: names.intern(&point.kind),
+        position: Vec3::from(point.position),
+        pscale: point.pscale,
+        orient: Quat::from_array(point.orient),
+        velocity: Vec3::from(point.velocity),
+        normal: Vec3::from(point.normal),
+        value: Box::new(SpilledValue {
+            metadata: point.metadata.clone(),
+        }),
+        channel: Some(point.channel.clone()).filter(|channel| !channel.is_empty()),
+        level: match point.level {
+            0 => Level::Trace,
+            1 => Level::Debug,
+            _ => Level::Info,
+        },
+        attributes: point
+            .attributes
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = match value {
+                    Value::Number(v) if v.is_i64() => AttributeValue::Int(v.as_i64()? as i32),
+                    Value::Number(v) => AttributeValue::Float(v.as_f64()? as f32),
+                    Value::String(v) => AttributeValue::String(v.clone()),
+                    _ => return None,
+                };
+                Some((name.clone(), value))
+            })
+            .collect(),
+        thread: None,
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs_f64(point.realtime.max(0.0)),
+        id: u64::try_from(point.id).ok(),
+        location: SourceLocation {
+            file: static_file_name(point.src_file.clone()),
+            line: point.src_line,
+        },
+        // Counters and gauges go back to the `__metrics` node they were exported to.
+        metric: matches!(point.kind.as_str(), "counter" | "gauge"),
+    }
+}
+
+/// The point group of each frame (`frame_1`, ...) with the ranges of the entries in it, ordered by
+/// frame, see [`ExportLayout::PerFrame`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn frame_groups(entries: &[ExportEntry]) -> Vec<(String, Vec<Range<usize>>)> {
+    let mut frames = BTreeMap::<i32, Vec<Range<usize>>>::new();
+    for (frame, range) in frame_ranges(entries) {
+        frames.entry(frame).or_default().push(range);
+    }
+    frames
+        .into_iter()
+        .map(|(frame, ranges)| (format!("frame_{}", frame), ranges))
+        .collect()
+}
+
+/// The first and the last segment of a `/`-separated name, which are exported as `path_root` and
+/// `path_leaf`. Names without a `/` are both.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn name_path(name: &str) -> (&str, &str) {
+    let root = name.split('/').next().unwrap_or_default();
+    let leaf = name.rsplit('/').next().unwrap_or_default();
+    (root, leaf)
+}
+
+/// Sets the code of a Python SOP.
+#[cfg(feature = "enabled")]
+fn set_python_code(node: &HoudiniNode, code: &str) -> Result<()> {
+    match node.parameter("python")? {
+        Parameter::String(parameter) => Ok(parameter.set(0, code)?),
+        _ => Err(anyhow!("The Python SOP has no code parameter")),
+    }
+}
+
+/// Reads the points of a recording saved via [`init_houlog`], see
+/// [`Recording::read_bgeo`](crate::Recording::read_bgeo). Recordings with packed frames (see
+/// [`ExportLayout::PerFrame`]) are unpacked first.
+#[cfg(feature = "enabled")]
+pub(crate) fn read_bgeo(path: &Path) -> Result<Vec<RecordedPoint>> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Could not convert path to string"))?;
+    let session = quick_session(None)?;
+    let parent = session.create_node("Object/geo")?;
+    let input = session.create_input_node("houlog_recording", Some(parent.handle))?;
+    input.load_from_file(path)?;
+    let unpack = session
+        .node_builder("python")
+        .with_parent(parent)
+        .create()?;
+    set_python_code(&unpack, UNPACK_CODE)?;
+    unpack.connect_input(0, input.node.handle, 0)?;
+    unpack.cook()?;
+    let geom = unpack
+        .geometry()?
+        .ok_or_else(|| anyhow!("No geometry on node"))?;
+    let count = geom.part_info(0)?.point_count() as usize;
+    let attribute_names = read_attribute_names(&geom)?;
+    let attribute = |name: &str| attribute_names.get(name);
+
+    // Attributes missing in recordings from older versions get the defaults of a recorded point.
+    let defaults = RecordedPoint::default();
+    let numeric = |name: &str, default: &[f32]| {
+        read_numeric_attribute::<f32>(&geom, &attribute(name), count, default)
+    };
+    let positions = numeric("P", &defaults.position)?;
+    let pscales = numeric("pscale", &[defaults.pscale])?;
+    let orients = numeric("orient", &defaults.orient)?;
+    let velocities = numeric("v", &defaults.velocity)?;
+    let normals = numeric("N", &defaults.normal)?;
+    let names = read_string_attribute(&geom, &attribute("name"), count)?;
+    let kinds = read_string_attribute(&geom, &attribute("kind"), count)?;
+    let times = numeric("time", &[defaults.time])?;
+    let frame_labels = read_string_attribute(&geom, &attribute("frame_label"), count)?;
+    let sim_times = numeric("sim_time", &[defaults.sim_time])?;
+    let channels = read_string_attribute(&geom, &attribute("channel"), count)?;
+    let levels = read_numeric_attribute(&geom, &attribute("level"), count, &[defaults.level])?;
+    let metadata = read_string_attribute(&geom, &attribute("metadata"), count)?;
+    let ids = read_numeric_attribute(&geom, &attribute("id"), count, &[defaults.id])?;
+    let src_files = read_string_attribute(&geom, &attribute("src_file"), count)?;
+    let src_lines = read_numeric_attribute::<i32>(&geom, &attribute("src_line"), count, &[0])?;
+    let realtimes =
+        read_numeric_attribute(&geom, &attribute("realtime"), count, &[defaults.realtime])?;
+    let attributes = read_custom_attributes(&geom, &attribute_names, count)?;
+
+    Ok((0..count)
+        .map(|i| RecordedPoint {
+            position: [positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]],
+            pscale: pscales[i],
+            orient: [
+                orients[i * 4],
+                orients[i * 4 + 1],
+                orients[i * 4 + 2],
+                orients[i * 4 + 3],
+            ],
+            velocity: [
+                velocities[i * 3],
+                velocities[i * 3 + 1],
+                velocities[i * 3 + 2],
+            ],
+            normal: [normals[i * 3], normals[i * 3 + 1], normals[i * 3 + 2]],
+            name: names[i].clone(),
+            kind: kinds[i].clone(),
+            time: times[i],
+            frame_label: frame_labels[i].clone(),
+            sim_time: sim_times[i],
+            channel: channels[i].clone(),
+            level: levels[i],
+            metadata: metadata[i].clone(),
+            id: ids[i],
+            src_file: src_files[i].clone(),
+            src_line: src_lines[i] as u32,
+            realtime: realtimes[i],
+            attributes: attributes
+                .iter()
+                .map(|(name, values)| (name.clone(), values[i].clone()))
+                .collect(),
+            geometry: None,
+        })
+        .collect())
+}
+
+/// Reads a numeric point attribute of `count` points, filling in `default` (the value of a single
+/// point) if it doesn't exist, for example in recordings from older versions.
+#[cfg(feature = "enabled")]
+fn read_numeric_attribute<T: AttribAccess + Clone>(
+    geom: &Geometry,
+    name: &str,
+    count: usize,
+    default: &[T],
+) -> Result<Vec<T>> {
+    let values = match geom.get_attribute(0, AttributeOwner::Point, name)? {
+        Some(attrib) => match attrib.downcast::<NumericAttr<T>>() {
+            Some(attrib) => attrib.get(0)?,
+            None => return Err(anyhow!("Attribute {} has an unexpected type", name)),
+        },
+        None => Vec::new(),
+    };
+    Ok(pad(values, count, default))
+}
+
+/// Like [`read_numeric_attribute`], but for string attributes. Dictionary attributes, such as the
+/// `metadata` of [`MetadataFormat::Dictionary`], are read as JSON.
+#[cfg(feature = "enabled")]
+fn read_string_attribute(geom: &Geometry, name: &str, count: usize) -> Result<Vec<String>> {
+    let values = match geom.get_attribute(0, AttributeOwner::Point, name)? {
+        Some(attrib) => match attrib.downcast::<StringAttr>() {
+            Some(attrib) => attrib.get(0)?.iter_str().map(str::to_string).collect(),
+            None => match attrib.downcast::<DictionaryAttr>() {
+                Some(attrib) => attrib.get(0)?.iter_str().map(str::to_string).collect(),
+                None => return Err(anyhow!("Attribute {} has an unexpected type", name)),
+            },
+        },
+        None => Vec::new(),
+    };
+    Ok(pad(values, count, &[String::new()]))
+}
+
+/// Truncates or extends the values to `count` points, extending with the value of a single point.
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn pad<T: Clone>(mut values: Vec<T>, count: usize, default: &[T]) -> Vec<T> {
+    let len = count * default.len();
+    values.truncate(len);
+    while values.len() < len {
+        values.push(default[values.len() % default.len()].clone());
+    }
+    values
+}
+
+/// Reads a detail attribute that holds a JSON object of strings, such as `orig_name`. Missing
+/// attributes are read as an empty object.
+#[cfg(feature = "enabled")]
+fn read_detail_map(geom: &Geometry, name: &str) -> Result<BTreeMap<String, String>> {
+    Ok(match geom.get_attribute(0, AttributeOwner::Detail, name)? {
+        Some(attrib) => match attrib.downcast::<StringAttr>() {
+            Some(attrib) => attrib
+                .get(0)?
+                .iter_str()
+                .next()
+                .map(serde_json::from_str::<BTreeMap<String, String>>)
+                .transpose()?
+                .unwrap_or_default(),
+            None => BTreeMap::new(),
+        },
+        None => BTreeMap::new(),
+    })
+}
+
+/// Reads the names under which the built-in attributes have been exported, see
+/// [`ATTRIBUTE_NAMES_ATTRIBUTE`].
+#[cfg(feature = "enabled")]
+fn read_attribute_names(geom: &Geometry) -> Result<AttributeNames> {
+    Ok(AttributeNames {
+        prefix: String::new(),
+        renamed: read_detail_map(geom, ATTRIBUTE_NAMES_ATTRIBUTE)?,
+    })
+}
+
+/// Reads the attributes set via [`EntryBuilder`], which are all point attributes that aren't
+/// built in, restoring the names that had to be sanitized via the `orig_name` detail attribute.
+#[cfg(feature = "enabled")]
+fn read_custom_attributes(
+    geom: &Geometry,
+    attribute_names: &AttributeNames,
+    count: usize,
+) -> Result<BTreeMap<String, Vec<Value>>> {
+    let orig_names = read_detail_map(geom, &attribute_names.get("orig_name"))?;
+    let part = geom.part_info(0)?;
+    let point_attributes = geom.get_attribute_names(AttributeOwner::Point, &part)?;
+    let mut attributes = BTreeMap::new();
+    for (name, orig_name) in
+        custom_attribute_names(point_attributes.iter_str(), attribute_names, &orig_names)
+    {
+        let values = if let Ok(values) = read_numeric_attribute::<f32>(geom, name, count, &[0.0]) {
+            values.into_iter().map(|v| json!(v)).collect()
+        } else if let Ok(values) = read_numeric_attribute::<i32>(geom, name, count, &[0]) {
+            values.into_iter().map(|v| json!(v)).collect()
+        } else {
+            read_string_attribute(geom, name, count)?
+                .into_iter()
+                .map(|v| json!(v))
+                .collect()
+        };
+        attributes.insert(orig_name, values);
+    }
+    Ok(attributes)
+}
+
+/// Picks the attributes set via [`EntryBuilder`] out of the names of the point attributes of a
+/// recording, together with their original names, see [`read_custom_attributes`].
+#[cfg_attr(not(feature = "enabled"), allow(dead_code))]
+fn custom_attribute_names<'a>(
+    point_attributes: impl IntoIterator<Item = &'a str>,
+    attribute_names: &AttributeNames,
+    orig_names: &BTreeMap<String, String>,
+) -> Vec<(&'a str, String)> {
+    let builtins = BUILTIN_ATTRIBUTES
+        .iter()
+        .map(|builtin| attribute_names.get(builtin))
+        .collect::<HashSet<_>>();
+    point_attributes
+        .into_iter()
+        .filter(|name| !builtins.contains(*name))
+        .map(|name| {
+            (
+                name,
+                orig_names
+                    .get(name)
+                    .map_or(name, String::as_str)
+                    .to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Writes the points in the same format as [`init_houlog`], see
+/// [`Recording::write_bgeo`](crate::Recording::write_bgeo).
+#[cfg(feature = "enabled")]
+pub(crate) fn write_bgeo(path: &Path, points: &[RecordedPoint]) -> Result<()> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Could not convert path to string"))?;
+    let logger = HoudiniDebugLogger::new_with_file(PathBuf::from(path))?;
+
+    // Every point gets its own frame data, which only holds the label and the simulation time.
+    let frames = points.iter().map(recorded_frame).collect::<Vec<_>>();
+    let entries = points
+        .iter()
+        .map(|point| recorded_entry(&logger.names, point))
+        .collect::<Vec<_>>();
+
+    let names = logger.names.names();
+    let entries = points
+        .iter()
+        .zip(entries.iter().zip(&frames))
+        .map(|(point, (entry, frame))| ExportEntry {
+            time: point.time,
+            frame: (point.time > 0.0).then_some(frame),
+            name: &names[entry.name.0 as usize],
+            kind: &names[entry.kind.0 as usize],
+            entry,
+        })
+        .collect::<Vec<_>>();
+    logger.save_file(path, &entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "enabled")]
+    use crate::{Line, Polygon};
+    #[cfg(feature = "enabled")]
+    use glam::Mat4;
+
+    #[test]
+    #[cfg(feature = "enabled")]
+    fn test() -> Result<()> {
         // init_houlog("./houlog.bgeo")?;
-        init_houlog_live(None)?;
+        init_houlog_live(LiveSessionOptions::default())?;
         houlog("test", Vec3::new(1.0, 2.0, 3.0));
         houlog(
             "test",
@@ -445,8 +4832,1062 @@ mod tests {
             },
         );
 
-        save_houlog()?;
+        try_save_houlog()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn memory_export_records_entries() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog("target", Vec3::new(1.0, 0.0, 1.0));
+            houlog_next_frame();
+            houlog("target", Vec3::new(2.0, 0.0, 1.0));
+            save_houlog();
+            houlog_exported()
+        })?;
+        exported.assert_count("target", 2);
+        exported.assert_not_logged("other");
+        let first = exported.assert_logged("target");
+        assert_eq!(first.decode::<Vec3>()?, Vec3::new(1.0, 0.0, 1.0));
+        Ok(())
+    }
+
+    #[test]
+    fn entries_below_the_min_level_are_dropped() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_min_level(Level::Debug);
+            houlog_at(Level::Trace, "trace", Vec3::ZERO);
+            houlog_at(Level::Debug, "debug", Vec3::ZERO);
+            houlog("info", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+        exported.assert_not_logged("trace");
+        assert_eq!(exported.assert_logged("debug").level, Level::Debug as i32);
+        assert_eq!(exported.assert_logged("info").level, Level::Info as i32);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_channels_are_dropped() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_in("physics", "before", Vec3::ZERO);
+            houlog_disable_channel("physics");
+            houlog_in("physics", "disabled", Vec3::ZERO);
+            houlog_in("ai", "other", Vec3::ZERO);
+            houlog_enable_channel("physics");
+            houlog_in("physics", "after", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+        exported.assert_not_logged("disabled");
+        assert_eq!(exported.assert_logged("before").channel, "physics");
+        assert_eq!(exported.assert_logged("other").channel, "ai");
+        assert_eq!(exported.assert_logged("after").channel, "physics");
+        Ok(())
+    }
+
+    #[test]
+    fn once_logs_the_first_call_per_call_site() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            for _ in 0..3 {
+                for _ in 0..2 {
+                    houlog_once("once", Vec3::ZERO);
+                    houlog_once_per_frame("per_frame", Vec3::ZERO);
+                }
+                houlog_next_frame();
+            }
+            save_houlog();
+            houlog_exported()
+        })?;
+        exported.assert_count("once", 1);
+        exported.assert_count("per_frame", 3);
+        let frames = exported
+            .entries()
+            .filter(|entry| entry.name == "per_frame")
+            .map(|entry| entry.frame())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [Some(0), Some(1), Some(2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn entries_carry_their_attributes_id_and_location() -> Result<()> {
+        let before = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs_f64();
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_entry("agent", Vec3::ZERO)
+                .attr_f32("speed", 4.5)
+                .attr_i32("team", 2)
+                .attr_str("state", "chase")
+                .commit();
+            houlog_id(7, "tracked", Vec3::X);
+            save_houlog();
+            houlog_exported()
+        })?;
+        let agent = exported.assert_logged("agent");
+        assert_eq!(agent.attributes["speed"], json!(4.5));
+        assert_eq!(agent.attributes["team"], json!(2));
+        assert_eq!(agent.attributes["state"], json!("chase"));
+        assert_eq!(agent.id, -1);
+        assert!(agent.src_file.ends_with("houdini_debug_logger.rs"));
+        assert!(agent.src_line > 0);
+        assert!(agent.realtime >= before.floor());
+        let tracked = exported.assert_logged("tracked");
+        assert_eq!(tracked.id, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn frames_carry_their_label_and_sim_time() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_frame_time(0.5);
+            houlog("start", Vec3::ZERO);
+            houlog_next_frame_named("relax");
+            houlog("relaxed", Vec3::ZERO);
+            {
+                let _outer = houlog_frame_scope("outer");
+                houlog("outer", Vec3::ZERO);
+                {
+                    let _inner = houlog_frame_scope("inner");
+                    houlog("inner", Vec3::ZERO);
+                }
+                houlog("outer_again", Vec3::ZERO);
+            }
+            houlog("after", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+        let start = exported.assert_logged("start");
+        assert_eq!((start.frame_label.as_str(), start.sim_time), ("", 0.5));
+        let frames = ["relaxed", "outer", "inner", "outer_again", "after"].map(|name| {
+            let entry = exported.assert_logged(name);
+            (entry.frame(), entry.frame_label.clone(), entry.sim_time)
+        });
+        assert_eq!(
+            frames,
+            [
+                (Some(1), "relax".to_string(), 0.0),
+                (Some(2), "outer".to_string(), 0.0),
+                (Some(3), "inner".to_string(), 0.0),
+                (Some(4), "outer".to_string(), 0.0),
+                (Some(5), String::new(), 0.0),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn clear_starts_over_at_the_first_frame() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog("stale", Vec3::ZERO);
+            houlog_next_frame();
+            houlog("stale", Vec3::ZERO);
+            houlog_clear();
+            houlog("fresh", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+        exported.assert_not_logged("stale");
+        assert_eq!(exported.assert_logged("fresh").frame(), Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_assertions_are_logged_and_saved() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_assert_panics(false);
+            let normal = Vec3::new(0.0, 2.0, 0.0);
+            crate::houassert!(normal.is_normalized(), "normal", normal);
+            crate::houassert!(Vec3::Y.is_normalized(), "fine", Vec3::Y);
+            // The failed assertion saved the recording already.
+            houlog_exported()
+        })?;
+        exported.assert_not_logged("fine");
+        let failed = exported.assert_logged("normal");
+        assert_eq!(failed.kind, "assert_failed");
+        let metadata: Value = serde_json::from_str(&failed.metadata)?;
+        assert_eq!(metadata["condition"], "normal.is_normalized()");
+        assert_eq!(metadata["kind"], "vec3");
+        Ok(())
+    }
+
+    #[test]
+    fn max_frames_drops_the_oldest_frames() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_max_frames(Some(2));
+            for i in 0..4 {
+                houlog("frame", i as f32);
+                houlog_next_frame();
+            }
+            houlog("frame", 4.0);
+            save_houlog();
+            houlog_exported()
+        })?;
+        let frames = exported
+            .entries()
+            .filter(|entry| entry.name == "frame")
+            .map(|entry| entry.frame())
+            .collect::<Vec<_>>();
+        assert_eq!(frames, [Some(3), Some(4)]);
+        Ok(())
+    }
+
+    #[test]
+    fn far_away_frames_dont_create_the_frames_in_between() -> Result<()> {
+        with_houlog(HoulogConfig::Memory, || {
+            houlog("first", Vec3::ZERO);
+            houlog_set_frame(u32::MAX);
+            houlog("last", Vec3::ZERO);
+            houlog_at_frame(1_000_000, "worker", Vec3::ZERO);
+
+            let logger = current_logger().unwrap();
+            let data = lock(&logger.data);
+            let frames = data.frames.keys().copied().collect::<Vec<_>>();
+            assert_eq!(frames, [0, 1_000_000, u32::MAX as usize]);
+        })
+    }
+
+    #[test]
+    fn future_frames_dont_drop_the_current_frame() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_max_frames(Some(2));
+            houlog("a", Vec3::ZERO);
+            houlog_next_frame();
+            houlog_at_frame(1000, "future", Vec3::ZERO);
+            houlog("b", Vec3::ZERO);
+            houlog_next_frame();
+            houlog("c", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+        let frames = exported
+            .entries()
+            .map(|entry| (entry.name.as_str(), entry.frame()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            frames,
+            [("b", Some(1)), ("c", Some(2)), ("future", Some(1000))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn spans_record_their_nesting() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            {
+                let _outer = houlog_span("outer");
+                let _inner = houlog_span("inner");
+            }
+            let after = houlog_span("after");
+            drop(after);
+            save_houlog();
+            houlog_exported()
+        })?;
+        let inner = exported.assert_logged("inner").decode::<Span>()?;
+        assert_eq!((inner.depth, inner.parent.as_deref()), (1, Some("outer")));
+        let outer = exported.assert_logged("outer").decode::<Span>()?;
+        assert_eq!((outer.depth, outer.parent), (0, None));
+        let after = exported.assert_logged("after").decode::<Span>()?;
+        assert_eq!((after.depth, after.parent), (0, None));
+        Ok(())
+    }
+
+    #[test]
+    fn spans_dropped_out_of_order_keep_the_nesting_intact() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            let a = houlog_span("a");
+            let b = houlog_span("b");
+            drop(a);
+            let c = houlog_span("c");
+            drop(b);
+            let d = houlog_span("d");
+            drop(d);
+            drop(c);
+            save_houlog();
+            houlog_exported()
+        })?;
+        for name in ["a", "c"] {
+            let span = exported.assert_logged(name).decode::<Span>()?;
+            assert_eq!((span.depth, span.parent), (0, None));
+        }
+        let b = exported.assert_logged("b").decode::<Span>()?;
+        assert_eq!((b.depth, b.parent.as_deref()), (1, Some("a")));
+        let d = exported.assert_logged("d").decode::<Span>()?;
+        assert_eq!((d.depth, d.parent.as_deref()), (1, Some("c")));
+        Ok(())
+    }
+
+    #[test]
+    fn sampled_logs_every_call_for_invalid_intervals() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            for interval in [f32::NAN, f32::INFINITY, -1.0, f32::MAX] {
+                houlog_sampled("v", Vec3::ZERO, 0.0, interval);
+            }
+            save_houlog();
+            houlog_exported()
+        })?;
+        exported.assert_count("v", 4);
+        Ok(())
+    }
+
+    #[test]
+    fn sampled_follows_the_given_time() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            for step in 0..10 {
+                houlog_sampled("v", step as f32, step as f32 * 0.25, 1.0);
+            }
+            // Rewinding starts over.
+            houlog_sampled("v", 10.0, 0.0, 1.0);
+            save_houlog();
+            houlog_exported()
+        })?;
+        let values = exported
+            .entries_named("v")
+            .map(|entry| entry.decode::<f32>())
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(values, [0.0, 4.0, 8.0, 10.0]);
+        Ok(())
+    }
+
+    #[test]
+    fn spilled_frames_are_restored_and_deleted_on_shutdown() -> Result<()> {
+        let mut spill_path = None;
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_spill_threshold(Some(0));
+            for i in 0..3 {
+                houlog("p", Vec3::splat(i as f32));
+                houlog_next_frame();
+            }
+            let logger = current_logger().unwrap();
+            let data = lock(&logger.data);
+            assert!(data.frames.values().all(|frame| frame.entries.is_empty()));
+            assert_eq!(data.memory_usage.bytes, 0);
+            spill_path = data.spill.as_ref().map(|spill| spill.path.clone());
+            drop(data);
+
+            save_houlog();
+            let exported = houlog_exported();
+            try_houlog_shutdown().unwrap();
+            exported
+        })?;
+
+        let positions = exported
+            .entries_named("p")
+            .map(|point| point.position)
+            .collect::<Vec<_>>();
+        assert_eq!(positions, [[0.0; 3], [1.0; 3], [2.0; 3]]);
+        assert!(!spill_path.expect("frames should be spilled").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn flushes_go_into_their_own_snapshots() -> Result<()> {
+        let snapshots = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_memory_budget(Some(0), MemoryPolicy::Flush);
+            houlog("first", Vec3::ZERO);
+            houlog("second", Vec3::ONE);
+            houlog_exported_snapshots()
+        })?;
+
+        assert_eq!(snapshots.keys().collect::<Vec<_>>(), ["flush1", "flush2"]);
+        snapshots["flush1"].assert_count("first", 1);
+        snapshots["flush2"].assert_count("second", 1);
+        snapshots["flush2"].assert_not_logged("first");
+        Ok(())
+    }
+
+    #[test]
+    fn frame_ranges_groups_entries_by_frame() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let entry = logger.entry("p", Vec3::ZERO);
+        let frame = FrameData::new();
+        let entries = [1.0, 1.0, 2.0, 0.0, 1.0].map(|time| ExportEntry {
+            time,
+            frame: (time != 0.0).then_some(&frame),
+            name: "p",
+            kind: "vec3",
+            entry: &entry,
+        });
+
+        assert_eq!(
+            frame_ranges(&entries),
+            [(1, 0..2), (2, 2..3), (0, 3..4), (1, 4..5)]
+        );
+    }
+
+    #[test]
+    fn stream_geometry_is_exported() -> Result<()> {
+        let polygon = crate::Polygon {
+            points: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+        };
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog("before", polygon.clone());
+            houlog_set_stream_geometry(true);
+            houlog("after", polygon.clone());
+            houlog("point", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+        assert_eq!(
+            exported.assert_logged("before").geometry,
+            polygon.geometry()
+        );
+        assert!(exported.assert_logged("after").geometry.is_some());
+        assert_eq!(exported.assert_logged("point").geometry, None);
+        Ok(())
+    }
+
+    #[test]
+    fn attribute_names_must_be_valid_and_unique() {
+        let names = AttributeNames::with_prefix("houlog_").rename("kind", "type");
+        assert!(validate_attribute_names(&names).is_ok());
+        let names = AttributeNames::default().rename("kind", "my kind");
+        assert!(validate_attribute_names(&names).is_err());
+        let names = AttributeNames::default().rename("kind", "name");
+        assert!(validate_attribute_names(&names).is_err());
+        let names = AttributeNames::default().rename("knd", "type");
+        assert!(validate_attribute_names(&names).is_err());
+    }
+
+    #[test]
+    fn timeline_offsets_the_exported_frames() -> Result<()> {
+        let exported = with_houlog(HoulogConfig::Memory, || {
+            houlog_set_timeline(f32::NAN, 5);
+            houlog_set_timeline(30.0, 0);
+            houlog("first", Vec3::ZERO);
+            houlog_set_timeline(30.0, 1001);
+            houlog_next_frame();
+            houlog("second", Vec3::ZERO);
+            save_houlog();
+            houlog_exported()
+        })?;
+
+        let times = exported
+            .points
+            .iter()
+            .map(|point| (point.name.as_str(), point.time))
+            .collect::<Vec<_>>();
+        assert_eq!(times, [("first", 1001.0), ("second", 1002.0)]);
+        Ok(())
+    }
+
+    #[test]
+    fn realtime_frame_mode_rejects_invalid_frame_rates() -> Result<()> {
+        with_houlog(HoulogConfig::Memory, || {
+            for fps in [0.0, -30.0, f32::NAN, f32::INFINITY] {
+                houlog_set_frame_mode(FrameMode::Realtime { fps });
+                let logger = current_logger().unwrap();
+                assert_eq!(lock(&logger.data).frame_mode, FrameMode::Manual);
+            }
+        })
+    }
+
+    #[test]
+    fn realtime_frames_are_only_created_when_logged_into() {
+        let mut data = LoggerData::new();
+        data.frame_mode = FrameMode::Realtime { fps: 1000.0 };
+        // Idle for an hour before logging again.
+        data.clock_origin = Instant::now() - Duration::from_secs(3600);
+        let frame = data.current_frame();
+        assert!(frame >= 3_600_000);
+        assert!(data.frame_mut(frame).is_some());
+        assert_eq!(data.frames.keys().copied().collect::<Vec<_>>(), [0, frame]);
+    }
+
+    #[test]
+    fn identifiers_dont_collide() {
+        let mut identifiers = Identifiers::with_reserved(&["name"]);
+        assert_eq!(identifiers.get("a b"), "a_b");
+        assert_eq!(identifiers.get("a/b"), "a_b_1");
+        assert_eq!(identifiers.get("a b"), "a_b");
+        assert_eq!(identifiers.get("name"), "name_1");
+    }
+
+    #[test]
+    fn node_labels_dont_contain_double_underscores() {
+        assert_eq!(
+            sanitize_node_label("recording_physics"),
+            "recording_physics"
+        );
+        assert_eq!(
+            sanitize_node_label("recording__snapshot_a"),
+            "recording_snapshot_a"
+        );
+        assert_eq!(sanitize_node_label("recording_a/ b"), "recording_a_b");
+
+        let mut labels = Identifiers::node_labels();
+        assert_eq!(labels.get("a_"), "a_");
+        assert_eq!(labels.get("a/"), "a_1");
+    }
+
+    #[test]
+    fn sanitize_identifier_replaces_invalid_characters() {
+        assert_eq!(sanitize_identifier("kind_vec3"), "kind_vec3");
+        assert_eq!(sanitize_identifier("chan_ai/agent 3"), "chan_ai_agent_3");
+        assert_eq!(sanitize_identifier("3d"), "_3d");
+    }
+
+    #[test]
+    fn point_groups_dont_merge_colliding_names() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let frame = FrameData::new();
+        let logged = [
+            ("vec3", Some("a-b")),
+            ("vec3", Some("a_b")),
+            ("mat4", None),
+            ("vec3", Some("a-b")),
+        ];
+        let log_entries = logged.map(|(_, channel)| LogEntry {
+            channel: channel.map(str::to_string),
+            ..logger.entry("p", Vec3::ZERO)
+        });
+        let entries = logged
+            .iter()
+            .zip(&log_entries)
+            .map(|(&(kind, _), entry)| ExportEntry {
+                time: 1.0,
+                frame: Some(&frame),
+                name: "p",
+                kind,
+                entry,
+            })
+            .collect::<Vec<_>>();
+
+        let (groups, orig_names) = point_groups(&entries);
+        assert_eq!(
+            groups,
+            BTreeMap::from([
+                ("chan_a_b".to_string(), vec![1, 0, 0, 1]),
+                ("chan_a_b_1".to_string(), vec![0, 1, 0, 0]),
+                ("kind_mat4".to_string(), vec![0, 0, 1, 0]),
+                ("kind_vec3".to_string(), vec![1, 1, 0, 1]),
+            ])
+        );
+        assert_eq!(
+            orig_names,
+            BTreeMap::from([
+                ("chan_a_b".to_string(), "chan_a-b".to_string()),
+                ("chan_a_b_1".to_string(), "chan_a_b".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn frame_groups_merge_the_ranges_of_a_frame() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let entry = logger.entry("p", Vec3::ZERO);
+        let frame = FrameData::new();
+        let entries = [2.0, 10.0, 0.0, 2.0].map(|time| ExportEntry {
+            time,
+            frame: (time != 0.0).then_some(&frame),
+            name: "p",
+            kind: "vec3",
+            entry: &entry,
+        });
+
+        let groups = frame_groups(&entries)
+            .into_iter()
+            .map(|(name, ranges)| {
+                let ranges = ranges.into_iter().map(|r| (r.start, r.end)).collect();
+                (name, ranges)
+            })
+            .collect::<Vec<(String, Vec<_>)>>();
+        assert_eq!(
+            groups,
+            [
+                ("frame_0".to_string(), vec![(2, 3)]),
+                ("frame_2".to_string(), vec![(0, 1), (3, 4)]),
+                ("frame_10".to_string(), vec![(1, 2)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn name_paths_are_the_first_and_the_last_segment() {
+        assert_eq!(name_path("ai/agent_3/path"), ("ai", "path"));
+        assert_eq!(name_path("target"), ("target", "target"));
+        assert_eq!(name_path("/leading"), ("", "leading"));
+    }
+
+    #[test]
+    fn live_layout_splits_channels_metrics_and_chunks() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let frame = FrameData::new();
+        let log_entries = [
+            LogEntry {
+                channel: Some("a b".to_string()),
+                ..logger.entry("p", Vec3::ZERO)
+            },
+            logger.entry("p", Vec3::ZERO),
+            LogEntry {
+                metric: true,
+                channel: Some("a b".to_string()),
+                ..logger.entry("p", Metric::Counter(1))
+            },
+            logger.entry("p", 1.0),
+        ];
+        // The last entry is a channel plot.
+        let entries = |times: [f32; 4]| {
+            times
+                .into_iter()
+                .zip(&log_entries)
+                .map(|(time, entry)| ExportEntry {
+                    time,
+                    frame: (time != 0.0).then_some(&frame),
+                    name: "p",
+                    kind: "vec3",
+                    entry,
+                })
+                .collect::<Vec<_>>()
+        };
+        let layout_of = |layout: LiveLayout| {
+            layout
+                .nodes
+                .into_iter()
+                .map(|(node, chunks)| {
+                    let chunks = chunks
+                        .into_iter()
+                        .map(|(chunk, entries)| (chunk, entries.len()))
+                        .collect::<Vec<_>>();
+                    (node, chunks)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut labels = Identifiers::node_labels();
+        let layout = live_layout("rec", None, entries([1.0, 150.0, 2.0, 0.0]), 1, &mut labels);
+        assert_eq!(
+            layout.orig_names,
+            BTreeMap::from([("rec_a_b".to_string(), "rec_a b".to_string())])
+        );
+        assert_eq!(
+            layout_of(layout),
+            [
+                ("rec".to_string(), vec![(None, 1), (Some(1), 1)]),
+                ("rec__metrics".to_string(), vec![(Some(0), 1)]),
+                ("rec_a_b".to_string(), vec![(Some(0), 1)]),
+            ]
+        );
+
+        // The chunks start at the start frame, and snapshots get their own labels.
+        let layout = live_layout(
+            "rec",
+            Some("a/b"),
+            entries([100.0, 249.0, 199.0, 0.0]),
+            100,
+            &mut labels,
+        );
+        assert_eq!(
+            layout.orig_names,
+            BTreeMap::from([
+                ("rec__snapshot_a_b".to_string(), "rec".to_string()),
+                ("rec_a_b__snapshot_a_b".to_string(), "rec_a b".to_string()),
+            ])
+        );
+        assert_eq!(
+            layout_of(layout),
+            [
+                (
+                    "rec__snapshot_a_b".to_string(),
+                    vec![(None, 1), (Some(1), 1)]
+                ),
+                ("rec__snapshot_a_b__metrics".to_string(), vec![(Some(0), 1)]),
+                ("rec_a_b__snapshot_a_b".to_string(), vec![(Some(0), 1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn live_node_labels_are_apart_from_channel_labels() {
+        assert_eq!(snapshot_suffix(None), "");
+        assert_eq!(
+            snapshot_suffix(Some("before relax")),
+            "__snapshot_before_relax"
+        );
+        assert_eq!(chunk_label("rec", 3), "rec__chunk3");
+        assert_eq!(pack_label(&chunk_label("rec", 3)), "rec__chunk3__pack");
+        assert_eq!(plots_label("rec"), "rec__plots");
+        assert_eq!(metrics_label("rec"), "rec__metrics");
+
+        // A channel named like a generated node doesn't get its label.
+        let mut labels = Identifiers::node_labels();
+        assert_eq!(labels.get("rec_plots"), "rec_plots");
+        assert_eq!(labels.get("rec__plots"), "rec_plots_1");
+    }
+
+    #[test]
+    fn recorded_points_survive_the_conversion_into_entries() {
+        let points = [
+            RecordedPoint {
+                position: [1.0, 2.0, 3.0],
+                pscale: 0.5,
+                orient: [0.0, 1.0, 0.0, 0.0],
+                velocity: [1.0, 0.0, 0.0],
+                normal: [0.0, 1.0, 0.0],
+                name: "agent".to_string(),
+                kind: "vec3".to_string(),
+                time: 3.0,
+                frame_label: "relax".to_string(),
+                sim_time: 0.25,
+                channel: "ai".to_string(),
+                level: Level::Debug as i32,
+                metadata: r#"{"pt":[1.0,2.0,3.0]}"#.to_string(),
+                id: 7,
+                src_file: "src/agent.rs".to_string(),
+                src_line: 12,
+                realtime: 1.5,
+                attributes: BTreeMap::from([
+                    ("speed".to_string(), json!(4.5)),
+                    ("state".to_string(), json!("chase")),
+                    ("team".to_string(), json!(2)),
+                ]),
+                geometry: None,
+            },
+            RecordedPoint {
+                name: "ticks".to_string(),
+                kind: "counter".to_string(),
+                level: Level::Info as i32,
+                metadata: r#"{"value":3}"#.to_string(),
+                ..RecordedPoint::default()
+            },
+        ];
+        let names = Interner::default();
+        let frames = points.iter().map(recorded_frame).collect::<Vec<_>>();
+        let entries = points
+            .iter()
+            .map(|point| recorded_entry(&names, point))
+            .collect::<Vec<_>>();
+        assert!(!entries[0].metric);
+        assert!(entries[1].metric);
+
+        let names = names.names();
+        for ((point, entry), frame) in points.iter().zip(&entries).zip(&frames) {
+            let exported = ExportEntry {
+                time: point.time,
+                frame: Some(frame),
+                name: &names[entry.name.0 as usize],
+                kind: &names[entry.kind.0 as usize],
+                entry,
+            };
+            assert_eq!(&exported.recorded_point(), point);
+        }
+    }
+
+    #[test]
+    fn custom_attributes_are_read_back_under_their_original_names() {
+        let orig_names = BTreeMap::from([("state_1".to_string(), "state".to_string())]);
+        let point_attributes = ["P", "name", "speed", "state_1", "houlog_kind"];
+        assert_eq!(
+            custom_attribute_names(point_attributes, &AttributeNames::default(), &orig_names),
+            [
+                ("speed", "speed".to_string()),
+                ("state_1", "state".to_string()),
+                ("houlog_kind", "houlog_kind".to_string()),
+            ]
+        );
+        let prefixed = AttributeNames::with_prefix("houlog_");
+        assert_eq!(
+            custom_attribute_names(point_attributes, &prefixed, &orig_names),
+            [
+                ("name", "name".to_string()),
+                ("speed", "speed".to_string()),
+                ("state_1", "state".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_attribute_values_are_padded_with_the_default() {
+        assert_eq!(
+            pad(vec![1.0, 2.0, 3.0], 2, &[0.0, 9.0]),
+            [1.0, 2.0, 3.0, 9.0]
+        );
+        assert_eq!(pad(vec![1, 2, 3], 2, &[0]), [1, 2]);
+        assert_eq!(pad(Vec::new(), 2, &[-1]), [-1, -1]);
+    }
+
+    #[test]
+    fn snapshot_path_keeps_extensions() {
+        assert_eq!(
+            snapshot_path(Path::new("out/recording.bgeo.sc"), "a"),
+            Path::new("out/recording_a.bgeo.sc")
+        );
+        assert_eq!(
+            snapshot_path(Path::new("recording"), "a"),
+            Path::new("recording_a")
+        );
+        assert_eq!(
+            snapshot_path(Path::new("recording.bgeo"), "../a"),
+            Path::new("recording____a.bgeo")
+        );
+    }
+
+    #[test]
+    fn streaming_only_sends_changed_frames() -> Result<()> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        with_houlog(HoulogConfig::Stream(address), || {
+            houlog("a", Vec3::ZERO);
+            houlog_next_frame();
+            houlog("b", Vec3::ONE);
+            save_houlog();
+            houlog("c", Vec3::ONE);
+            save_houlog();
+        })?;
+
+        let (mut connection, _) = listener.accept()?;
+        let mut receive = || -> Result<serde_json::Value> {
+            let mut length = [0; 4];
+            connection.read_exact(&mut length)?;
+            let mut payload = vec![0; u32::from_be_bytes(length) as usize];
+            connection.read_exact(&mut payload)?;
+            Ok(serde_json::from_slice(&payload)?)
+        };
+        let names = |message: &serde_json::Value| {
+            message["points"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|point| point["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let first = receive()?;
+        assert!(first.get("frames").is_none());
+        assert_eq!(names(&first), ["a", "b"]);
+        let second = receive()?;
+        assert_eq!(
+            second["frames"],
+            json!({ "first": 1.0, "last": 2.0, "updated": [2.0] })
+        );
+        assert_eq!(names(&second), ["b", "c"]);
+        Ok(())
+    }
+
+    #[test]
+    fn unnamed_threads_are_ordered_by_first_use() -> Result<()> {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let log_from_thread = |frame: u32, name: String| {
+            houlog_set_thread_frame(frame);
+            logger.log(logger.entry(&name, Vec3::ZERO)).unwrap();
+        };
+        // Enough threads that their ids don't sort the same as text.
+        std::thread::scope(|scope| {
+            let threads = (0..12)
+                .map(|i| {
+                    let (sender, receiver) = mpsc::channel::<()>();
+                    let log_from_thread = &log_from_thread;
+                    let thread = scope.spawn(move || {
+                        log_from_thread(0, format!("first{}", i));
+                        receiver.recv().unwrap();
+                        log_from_thread(1, format!("t{}", i));
+                    });
+                    // Wait until the thread has logged for the first time.
+                    while lock(&logger.data).frames[&0].entries.len() <= i {
+                        std::thread::yield_now();
+                    }
+                    (sender, thread)
+                })
+                .collect::<Vec<_>>();
+            for (sender, thread) in threads.into_iter().rev() {
+                sender.send(()).unwrap();
+                thread.join().unwrap();
+            }
+        });
+        logger.save()?;
 
+        let ExportMethod::Memory { recording, .. } = &logger.export_method else {
+            unreachable!();
+        };
+        let names = lock(recording)
+            .points
+            .iter()
+            .filter(|point| point.time == 2.0)
+            .map(|point| point.name.clone())
+            .collect::<Vec<_>>();
+        let expected = (0..12).map(|i| format!("t{}", i)).collect::<Vec<_>>();
+        assert_eq!(names, expected);
         Ok(())
     }
+
+    #[test]
+    fn counters_saturate_and_are_exported_as_metrics() -> Result<()> {
+        with_houlog(HoulogConfig::Memory, || {
+            houlog_counter("spawned", i64::MAX);
+            houlog_counter("spawned", 1);
+            houlog("p", Vec3::ZERO);
+
+            let logger = current_logger().unwrap();
+            let data = lock(&logger.data);
+            let frame = &data.frames[&0];
+            assert_eq!(frame.metrics["spawned"], Metric::Counter(i64::MAX));
+            let metrics = frame.metric_entries(&logger.names);
+            assert!(metrics.iter().all(|entry| entry.metric));
+            assert!(frame.entries.iter().all(|entry| !entry.metric));
+        })
+    }
+
+    /// Builds a recording with the given entries per frame, each logged as a polyline with the
+    /// given number of points.
+    fn recording_data(logger: &HoudiniDebugLogger, frames: &[&[(&str, usize)]]) -> LoggerData {
+        let mut data = LoggerData::new();
+        for (i, entries) in frames.iter().enumerate() {
+            let frame = data.frame_mut(i).unwrap();
+            for &(name, points) in entries.iter() {
+                let line = crate::Polyline {
+                    points: vec![Vec3::ZERO; points],
+                };
+                frame.entries.push(logger.entry(name, line));
+            }
+        }
+        data.update_memory_usage();
+        data
+    }
+
+    fn entry_names(logger: &HoudiniDebugLogger, data: &LoggerData) -> Vec<Vec<String>> {
+        let names = logger.names.names();
+        data.frames
+            .values()
+            .map(|frame| {
+                frame
+                    .entries
+                    .iter()
+                    .map(|entry| names[entry.name.0 as usize].to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn downsample_drops_every_other_entry_of_the_largest_kind() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let mut data = recording_data(&logger, &[&[("a", 100), ("b", 100)], &[("c", 100)]]);
+        data.frames
+            .get_mut(&0)
+            .unwrap()
+            .entries
+            .push(logger.entry("p", Vec3::ZERO));
+        data.update_memory_usage();
+        let usage = data.memory_usage.bytes;
+        data.dirty_frames.clear();
+
+        assert!(data.downsample());
+        assert_eq!(entry_names(&logger, &data), [vec!["a", "p"], vec!["c"]]);
+        assert!(data.memory_usage.bytes < usage);
+        assert_eq!(data.dirty_frames, BTreeSet::from([0]));
+
+        // A single entry of a kind isn't downsampled any further.
+        let mut data = recording_data(&logger, &[&[("a", 100)]]);
+        assert!(!data.downsample());
+    }
+
+    #[test]
+    fn memory_budget_drops_oldest_frames() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let mut data = recording_data(&logger, &[&[("a", 100)], &[("b", 100)], &[("c", 100)]]);
+        let budget = data.memory_usage.bytes / 2;
+        data.memory_budget = Some((budget, MemoryPolicy::DropOldest));
+        assert!(!data.enforce_memory_budget());
+        assert_eq!(entry_names(&logger, &data), [vec!["c"]]);
+        assert_eq!(data.first_frame, 2);
+
+        // The last frame is kept even if it alone exceeds the budget.
+        data.memory_budget = Some((0, MemoryPolicy::DropOldest));
+        assert!(!data.enforce_memory_budget());
+        assert_eq!(entry_names(&logger, &data), [vec!["c"]]);
+    }
+
+    #[test]
+    fn memory_budget_downsamples_before_dropping_frames() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let frames: &[&[(&str, usize)]] = &[&[("a", 100), ("b", 100)], &[("c", 100), ("d", 100)]];
+        let mut data = recording_data(&logger, frames);
+        let budget = data.memory_usage.bytes * 3 / 4;
+        data.memory_budget = Some((budget, MemoryPolicy::Downsample));
+        assert!(!data.enforce_memory_budget());
+        assert_eq!(entry_names(&logger, &data), [vec!["a"], vec!["c"]]);
+
+        // Once there is nothing left to downsample, the oldest frames are dropped.
+        data.memory_budget = Some((0, MemoryPolicy::Downsample));
+        assert!(!data.enforce_memory_budget());
+        assert_eq!(entry_names(&logger, &data), [Vec::<String>::new()]);
+        assert_eq!(data.first_frame, 1);
+    }
+
+    #[test]
+    fn memory_budget_counts_shared_data_once() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let line = |points: usize| {
+            Arc::new(crate::Polyline {
+                points: vec![Vec3::ZERO; points],
+            })
+        };
+        let shared = line(1000);
+        let mut data = LoggerData::new();
+        for i in 0..3 {
+            data.push_entry(i, logger.entry("line", Arc::clone(&shared)));
+        }
+        let entry_size = logger.entry("line", Arc::clone(&shared)).memory_size();
+
+        // Logging the same line every frame counts its points only once.
+        let usage = 3 * entry_size + (*shared).memory_size();
+        assert_eq!(data.memory_usage.bytes, usage);
+
+        // Distinct lines are counted once each.
+        let other = line(1000);
+        data.push_entry(2, logger.entry("other", Arc::clone(&other)));
+        let usage = usage + entry_size + (*other).memory_size();
+        assert_eq!(data.memory_usage.bytes, usage);
+        data.update_memory_usage();
+        assert_eq!(data.memory_usage.bytes, usage);
+
+        // The shared line counts as long as any entry refers to it.
+        data.drop_frames_before(2);
+        let usage = 2 * entry_size + (*shared).memory_size() + (*other).memory_size();
+        assert_eq!(data.memory_usage.bytes, usage);
+        data.drop_frames_before(3);
+        assert_eq!(data.memory_usage.bytes, 0);
+    }
+
+    #[test]
+    fn memory_budget_requests_a_flush() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let mut data = recording_data(&logger, &[&[("a", 100)]]);
+        data.memory_budget = Some((0, MemoryPolicy::Flush));
+        assert!(data.enforce_memory_budget());
+        assert_eq!(entry_names(&logger, &data), [vec!["a"]]);
+
+        data.memory_budget = Some((data.memory_usage.bytes, MemoryPolicy::Flush));
+        assert!(!data.enforce_memory_budget());
+    }
+
+    #[test]
+    fn replace_entry_replaces_the_last_entry_with_the_same_name() {
+        let logger = HoudiniDebugLogger::new_in_memory();
+        let mut data = recording_data(&logger, &[&[("a", 1), ("b", 1), ("a", 1)]]);
+        let line = |points: usize| crate::Polyline {
+            points: vec![Vec3::ONE; points],
+        };
+
+        data.replace_entry(0, logger.entry("a", line(100)));
+        assert_eq!(entry_names(&logger, &data), [vec!["a", "b", "a"]]);
+        let replaced = &data.frames[&0].entries[2];
+        assert_eq!(replaced.position, Vec3::ONE);
+        let usage = data.memory_usage.bytes;
+        data.update_memory_usage();
+        assert_eq!(data.memory_usage.bytes, usage);
+
+        data.replace_entry(0, logger.entry("c", line(1)));
+        data.replace_entry(1, logger.entry("a", line(1)));
+        assert_eq!(
+            entry_names(&logger, &data),
+            [vec!["a", "b", "a", "c"], vec!["a"]]
+        );
+        let usage = data.memory_usage.bytes;
+        data.update_memory_usage();
+        assert_eq!(data.memory_usage.bytes, usage);
+    }
 }
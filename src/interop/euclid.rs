@@ -0,0 +1,38 @@
+use crate::{IntoLoggable, Obb};
+use euclid::{Box3D, Point3D, Transform3D, Vector3D};
+use glam::{Mat4, Quat, Vec3};
+
+impl<U> IntoLoggable for Point3D<f32, U> {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl<U> IntoLoggable for Vector3D<f32, U> {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl<Src, Dst> IntoLoggable for Transform3D<f32, Src, Dst> {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        // euclid transforms row vectors, so its rows are glam's columns.
+        Mat4::from_cols_array(&self.to_array())
+    }
+}
+
+impl<U> IntoLoggable for Box3D<f32, U> {
+    type LoggableType = Obb;
+    fn into_loggable(self) -> Self::LoggableType {
+        let min = self.min.into_loggable();
+        let max = self.max.into_loggable();
+        Obb {
+            center: (min + max) * 0.5,
+            half_extents: (max - min) * 0.5,
+            rotation: Quat::IDENTITY,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{houlog_2d_plane, ColorGrid, Heightfield, IntoLoggable};
+use image::{GrayImage, RgbImage};
+
+/// The pixels become heights between 0 and 1, one sample per pixel.
+impl IntoLoggable for &GrayImage {
+    type LoggableType = Heightfield;
+    fn into_loggable(self) -> Self::LoggableType {
+        Heightfield {
+            width: self.width() as usize,
+            height: self.height() as usize,
+            cell_size: 1.0,
+            heights: self.pixels().map(|p| p.0[0] as f32 / 255.0).collect(),
+        }
+    }
+}
+
+impl IntoLoggable for GrayImage {
+    type LoggableType = Heightfield;
+    fn into_loggable(self) -> Self::LoggableType {
+        (&self).into_loggable()
+    }
+}
+
+/// The pixels become colored points on the plane configured via
+/// [`houlog_set_2d_plane`](crate::houlog_set_2d_plane()).
+impl IntoLoggable for &RgbImage {
+    type LoggableType = ColorGrid;
+    fn into_loggable(self) -> Self::LoggableType {
+        ColorGrid {
+            width: self.width() as usize,
+            height: self.height() as usize,
+            cell_size: 1.0,
+            plane: houlog_2d_plane(),
+            colors: self
+                .pixels()
+                .map(|p| p.0.map(|c| c as f32 / 255.0))
+                .collect(),
+        }
+    }
+}
+
+impl IntoLoggable for RgbImage {
+    type LoggableType = ColorGrid;
+    fn into_loggable(self) -> Self::LoggableType {
+        (&self).into_loggable()
+    }
+}
@@ -0,0 +1,40 @@
+use crate::IntoLoggable;
+use glam::{Mat4, Quat, Vec3, Vec4};
+use mint::{ColumnMatrix4, Point3, Quaternion, Vector3, Vector4};
+
+fn to_vec4(v: Vector4<f32>) -> Vec4 {
+    Vec4::new(v.x, v.y, v.z, v.w)
+}
+
+impl IntoLoggable for Point3<f32> {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl IntoLoggable for Vector3<f32> {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl IntoLoggable for Quaternion<f32> {
+    type LoggableType = Quat;
+    fn into_loggable(self) -> Self::LoggableType {
+        Quat::from_xyzw(self.v.x, self.v.y, self.v.z, self.s)
+    }
+}
+
+impl IntoLoggable for ColumnMatrix4<f32> {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        Mat4::from_cols(
+            to_vec4(self.x),
+            to_vec4(self.y),
+            to_vec4(self.z),
+            to_vec4(self.w),
+        )
+    }
+}
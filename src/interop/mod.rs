@@ -0,0 +1,21 @@
+//! [`IntoLoggable`](crate::IntoLoggable) implementations for the types of other math and geometry
+//! libraries. Each of them is behind a feature flag with the same name as the library.
+
+#[cfg(feature = "cgmath")]
+mod cgmath;
+#[cfg(feature = "euclid")]
+mod euclid;
+#[cfg(feature = "image")]
+mod image;
+#[cfg(feature = "mint")]
+mod mint;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+#[cfg(any(feature = "parry3d", feature = "rapier3d"))]
+mod parry;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+#[cfg(feature = "rapier3d")]
+mod rapier3d;
+#[cfg(feature = "ultraviolet")]
+mod ultraviolet;
@@ -0,0 +1,40 @@
+use crate::IntoLoggable;
+use glam::{Mat4, Quat, Vec3};
+use nalgebra::{Isometry3, Matrix4, Point3, UnitQuaternion, Vector3};
+
+impl IntoLoggable for Point3<f32> {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl IntoLoggable for Vector3<f32> {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl IntoLoggable for UnitQuaternion<f32> {
+    type LoggableType = Quat;
+    fn into_loggable(self) -> Self::LoggableType {
+        let coords = self.into_inner().coords;
+        Quat::from_xyzw(coords.x, coords.y, coords.z, coords.w)
+    }
+}
+
+impl IntoLoggable for Matrix4<f32> {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        // Both nalgebra and glam store their matrices in column-major order.
+        Mat4::from_cols_slice(self.as_slice())
+    }
+}
+
+impl IntoLoggable for Isometry3<f32> {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        self.to_homogeneous().into_loggable()
+    }
+}
@@ -0,0 +1,115 @@
+// When both the `parry3d` and `rapier3d` features are enabled, the parry version used by rapier
+// must match the `parry3d` dependency, otherwise the shape types are distinct.
+#[cfg(feature = "parry3d")]
+use parry3d as parry;
+#[cfg(not(feature = "parry3d"))]
+use rapier3d::parry;
+
+use crate::{Capsule, ConvexHull, IntoLoggable, Mesh, Obb, Shape, Sphere};
+use glam::{Quat, Vec3};
+use parry::math::{Isometry, Point, Real, Vector};
+use parry::shape::{Shape as ParryShape, SharedShape};
+
+pub(super) fn to_vec3(v: &Vector<Real>) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+fn to_point(iso: &Isometry<Real>, p: &Point<Real>) -> Vec3 {
+    let p = iso * p;
+    Vec3::new(p.x, p.y, p.z)
+}
+
+pub(super) fn to_quat(iso: &Isometry<Real>) -> Quat {
+    let coords = iso.rotation.into_inner().coords;
+    Quat::from_xyzw(coords.x, coords.y, coords.z, coords.w)
+}
+
+/// Converts a shape placed at `iso` into a loggable shape. Shapes without a dedicated loggable are
+/// logged as their bounding box.
+pub(super) fn shape_to_loggable(shape: &dyn ParryShape, iso: &Isometry<Real>) -> Shape {
+    if let Some(ball) = shape.as_ball() {
+        return Shape::Sphere(Sphere {
+            center: to_vec3(&iso.translation.vector),
+            radius: ball.radius,
+        });
+    }
+
+    if let Some(capsule) = shape.as_capsule() {
+        return Shape::Capsule(Capsule {
+            start: to_point(iso, &capsule.segment.a),
+            end: to_point(iso, &capsule.segment.b),
+            radius: capsule.radius,
+        });
+    }
+
+    if let Some(cuboid) = shape.as_cuboid() {
+        return Shape::Obb(Obb {
+            center: to_vec3(&iso.translation.vector),
+            half_extents: to_vec3(&cuboid.half_extents),
+            rotation: to_quat(iso),
+        });
+    }
+
+    if let Some(convex) = shape.as_convex_polyhedron() {
+        return Shape::ConvexHull(ConvexHull {
+            points: convex.points().iter().map(|p| to_point(iso, p)).collect(),
+        });
+    }
+
+    if let Some(trimesh) = shape.as_trimesh() {
+        return Shape::Mesh(Mesh {
+            vertices: trimesh
+                .vertices()
+                .iter()
+                .map(|p| to_point(iso, p))
+                .collect(),
+            indices: trimesh
+                .indices()
+                .iter()
+                .flatten()
+                .map(|i| *i as usize)
+                .collect(),
+            index_counts: vec![3; trimesh.indices().len()],
+        });
+    }
+
+    if let Some(compound) = shape.as_compound() {
+        return Shape::Compound(
+            compound
+                .shapes()
+                .iter()
+                .map(|(local_iso, shape)| shape_to_loggable(&**shape, &(iso * local_iso)))
+                .collect(),
+        );
+    }
+
+    let aabb = shape.compute_local_aabb();
+    Shape::Obb(Obb {
+        center: to_point(iso, &aabb.center()),
+        half_extents: to_vec3(&aabb.half_extents()),
+        rotation: to_quat(iso),
+    })
+}
+
+impl IntoLoggable for &dyn ParryShape {
+    type LoggableType = Shape;
+    fn into_loggable(self) -> Self::LoggableType {
+        shape_to_loggable(self, &Isometry::identity())
+    }
+}
+
+impl IntoLoggable for &SharedShape {
+    type LoggableType = Shape;
+    fn into_loggable(self) -> Self::LoggableType {
+        shape_to_loggable(&**self, &Isometry::identity())
+    }
+}
+
+/// A shape placed at the given position.
+impl IntoLoggable for (&Isometry<Real>, &dyn ParryShape) {
+    type LoggableType = Shape;
+    fn into_loggable(self) -> Self::LoggableType {
+        let (iso, shape) = self;
+        shape_to_loggable(shape, iso)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{Graph, IntoLoggable};
+use glam::Vec3;
+use petgraph::graph::IndexType;
+use petgraph::visit::EdgeRef;
+use petgraph::EdgeType;
+
+impl Graph {
+    /// Converts a petgraph graph, placing each node at the position returned by `position`.
+    pub fn from_petgraph<N, E, Ty: EdgeType, Ix: IndexType>(
+        graph: &petgraph::Graph<N, E, Ty, Ix>,
+        position: impl Fn(&N) -> Vec3,
+    ) -> Self {
+        Graph {
+            nodes: graph.node_weights().map(position).collect(),
+            edges: graph
+                .edge_references()
+                .map(|e| (e.source().index(), e.target().index()))
+                .collect(),
+            directed: graph.is_directed(),
+        }
+    }
+}
+
+/// A graph together with a function for the positions of its nodes.
+impl<N, E, Ty: EdgeType, Ix: IndexType, F: Fn(&N) -> Vec3> IntoLoggable
+    for (&petgraph::Graph<N, E, Ty, Ix>, F)
+{
+    type LoggableType = Graph;
+    fn into_loggable(self) -> Self::LoggableType {
+        let (graph, position) = self;
+        Graph::from_petgraph(graph, position)
+    }
+}
@@ -0,0 +1,27 @@
+use super::parry::{shape_to_loggable, to_quat, to_vec3};
+use crate::{IntoLoggable, Kinematics, Shape};
+use glam::Mat4;
+use rapier3d::dynamics::RigidBody;
+use rapier3d::geometry::Collider;
+
+impl IntoLoggable for &Collider {
+    type LoggableType = Shape;
+    fn into_loggable(self) -> Self::LoggableType {
+        shape_to_loggable(self.shape(), self.position())
+    }
+}
+
+impl IntoLoggable for &RigidBody {
+    type LoggableType = Kinematics;
+    fn into_loggable(self) -> Self::LoggableType {
+        let iso = self.position();
+        Kinematics {
+            transform: Mat4::from_rotation_translation(
+                to_quat(iso),
+                to_vec3(&iso.translation.vector),
+            ),
+            linear_velocity: to_vec3(self.linvel()),
+            angular_velocity: to_vec3(self.angvel()),
+        }
+    }
+}
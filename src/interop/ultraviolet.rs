@@ -0,0 +1,36 @@
+use crate::IntoLoggable;
+use glam::{Mat4, Quat, Vec3, Vec4};
+use ultraviolet::{Isometry3, Mat4 as UvMat4, Rotor3, Vec3 as UvVec3, Vec4 as UvVec4};
+
+fn to_vec4(v: UvVec4) -> Vec4 {
+    Vec4::new(v.x, v.y, v.z, v.w)
+}
+
+impl IntoLoggable for UvVec3 {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl IntoLoggable for UvMat4 {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        let [x, y, z, w] = self.cols;
+        Mat4::from_cols(to_vec4(x), to_vec4(y), to_vec4(z), to_vec4(w))
+    }
+}
+
+impl IntoLoggable for Rotor3 {
+    type LoggableType = Quat;
+    fn into_loggable(self) -> Self::LoggableType {
+        Quat::from_array(self.into_quaternion_array())
+    }
+}
+
+impl IntoLoggable for Isometry3 {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        self.into_homogeneous_matrix().into_loggable()
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// The severity of a log entry. Entries logged via [`houlog`](crate::houlog()) are [`Level::Info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Level {
+    /// Very verbose output, for example for every raycast.
+    Trace = 0,
+    Debug = 1,
+    Info = 2,
+}
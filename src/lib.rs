@@ -1,5 +1,62 @@
+pub use attribute_names::*;
+#[cfg(not(feature = "memory"))]
+pub use disabled::*;
+pub use drop_behavior::*;
+#[cfg(feature = "egui")]
+pub use egui_panel::*;
+pub use export_layout::*;
+pub use frame_mode::*;
+#[cfg(feature = "memory")]
 pub use houdini_debug_logger::*;
+pub use level::*;
+#[cfg(feature = "log")]
+pub use log_bridge::*;
 pub use loggable::*;
+pub use memory_policy::*;
+pub use metadata_format::*;
+pub use query::*;
+pub use recording::*;
+pub use recording_diff::*;
+pub use server::*;
+pub use snippets::*;
+pub use stats::*;
+pub use viewer::*;
 
+/// A Python script that receives recordings sent via `init_houlog_stream` and rebuilds them inside
+/// Houdini, without requiring a Houdini Engine license. See the README for how to set it up.
+pub const HOULOG_PYTHON_RECEIVER: &str = include_str!("../houdini/houlog_receiver.py");
+
+/// A Python script that builds the viewer HDA when run with hython, see [`write_viewer_hda`].
+pub const HOULOG_VIEWER_SCRIPT: &str = include_str!("../houdini/houlog_viewer.py");
+
+/// Whether logging is compiled in. Used by the macros to strip their arguments when it's not.
+#[doc(hidden)]
+pub const __HOULOG_ENABLED: bool = cfg!(feature = "memory");
+
+mod attribute_names;
+#[cfg(not(feature = "memory"))]
+mod disabled;
+mod drop_behavior;
+#[cfg(feature = "egui")]
+mod egui_panel;
+mod export_layout;
+mod frame_mode;
+#[cfg(feature = "memory")]
 mod houdini_debug_logger;
+mod interop;
+mod level;
+#[cfg(feature = "log")]
+mod log_bridge;
 mod loggable;
+mod macros;
+mod memory_policy;
+mod metadata_format;
+mod query;
+mod recording;
+mod recording_diff;
+mod server;
+mod snippets;
+mod stats;
+mod viewer;
+#[cfg(feature = "websocket")]
+mod websocket;
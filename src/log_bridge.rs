@@ -0,0 +1,102 @@
+//! A [`log`] logger that mirrors warnings and errors into the recording, see [`HoulogAppender`].
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// A logger for the [`log`] crate that records `warn!` and `error!` records (or whatever the
+/// minimum level is set to) as [`Event`](crate::Event) entries on the current frame, so textual
+/// errors show up in the Houdini timeline right next to the geometry that triggered them.
+///
+/// The events are logged into the `log` channel and named after the level (`log/warn`,
+/// `log/error`, ...), with the source location of the `log` call. Since only one logger can be
+/// installed, the appender can wrap another one (for example `env_logger`) that it forwards every
+/// record to.
+///
+/// ```no_run
+/// # use houdini_debug_logger::HoulogAppender;
+/// # struct Stderr;
+/// # impl log::Log for Stderr {
+/// #     fn enabled(&self, _metadata: &log::Metadata) -> bool { true }
+/// #     fn log(&self, record: &log::Record) { eprintln!("{}", record.args()) }
+/// #     fn flush(&self) {}
+/// # }
+/// # let stderr_logger = Stderr;
+/// HoulogAppender::new().wrapping(stderr_logger).init()?;
+/// # anyhow::Ok(())
+/// ```
+pub struct HoulogAppender {
+    level: Level,
+    inner: Option<Box<dyn Log>>,
+}
+
+impl Default for HoulogAppender {
+    fn default() -> Self {
+        HoulogAppender {
+            level: Level::Warn,
+            inner: None,
+        }
+    }
+}
+
+impl HoulogAppender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The least severe level that is recorded. Defaults to [`Level::Warn`].
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Forwards all records to another logger as well.
+    pub fn wrapping(mut self, inner: impl Log + 'static) -> Self {
+        self.inner = Some(Box::new(inner));
+        self
+    }
+
+    /// Installs the appender as the global logger.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = match &self.inner {
+            Some(_) => LevelFilter::Trace,
+            None => self.level.to_level_filter(),
+        };
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+
+    fn records(&self, metadata: &Metadata) -> bool {
+        // Records of Houdini Engine might be emitted while the logger is saving, so recording them
+        // could deadlock.
+        metadata.level() <= self.level && !metadata.target().starts_with("hapi_rs")
+    }
+}
+
+impl Log for HoulogAppender {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.records(metadata)
+            || self
+                .inner
+                .as_ref()
+                .is_some_and(|inner| inner.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        if self.records(record.metadata()) {
+            crate::houdini_debug_logger::log_record_event(
+                &format!("log/{}", record.level().as_str().to_lowercase()),
+                format!("{}: {}", record.target(), record.args()),
+                record.file_static().zip(record.line()),
+            );
+        }
+        if let Some(inner) = &self.inner {
+            inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(inner) = &self.inner {
+            inner.flush();
+        }
+    }
+}
@@ -1,22 +1,169 @@
-use crate::IntoLoggable;
-use glam::{Mat4, Quat, Vec3};
-use serde_json::json;
+use glam::{DMat4, DQuat, DVec3, EulerRot, Mat4, Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::mem::size_of_val;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The `pscale` of entries without a size of their own, unless changed via
+/// [`houlog_set_default_pscale`](crate::houlog_set_default_pscale()).
+pub(crate) const DEFAULT_PSCALE: f32 = 0.1;
 
 /// A trait for types that can be logged to Houdini. This must be kept in sync with the HDA or
 /// houdini node that parses the log data. For just logging a custom type, use the [`IntoLoggable`]
 /// trait if possible.
-pub trait DebugLoggable: Send {
-    /// The kind of the data, for example `mat4` or `vec3`.
+///
+/// Loggable types have to be `Sync`, since the `rayon` feature builds the exported attributes from
+/// multiple threads. The bound doesn't depend on the feature, so that turning it on can't break
+/// other crates.
+pub trait DebugLoggable: Send + Sync {
+    /// The kind of the data, for example `mat4` or `vec3`. This is called once when the data is
+    /// logged, so it should be cheap.
     fn kind(&self) -> String;
 
     /// The "root" position of the data. Not all data types necessarily have a meaningful position,
-    /// which is fine.
+    /// which is fine. This is called once when the data is logged, so it should be cheap.
     fn position(&self) -> Vec3 {
         Vec3::new(0.0, 0.0, 0.0)
     }
 
-    /// The metadata of the data, as a JSON string.
+    /// The metadata of the data, as a JSON string. This is only called when saving, so expensive
+    /// serialization doesn't slow down logging.
     fn as_json(&self) -> String;
+
+    /// The size of the data, for example the radius of a sphere. It's exported as the `pscale`
+    /// attribute, so that points are drawn with a meaningful size without the HDA parsing the
+    /// metadata. Data without a size uses the default set via
+    /// [`houlog_set_default_pscale`](crate::houlog_set_default_pscale()). This is called once when
+    /// the data is logged, so it should be cheap.
+    fn pscale(&self) -> Option<f32> {
+        None
+    }
+
+    /// The rotation of the data, for example of a transform. It's exported as the `orient`
+    /// attribute, so that Houdini's instancing and rotation visualizers work directly on the
+    /// points. Data without a rotation gets the identity. This is called once when the data is
+    /// logged, so it should be cheap.
+    fn orient(&self) -> Option<Quat> {
+        None
+    }
+
+    /// The velocity of the data, for example of a moving body. It's exported as the `v`
+    /// attribute, which Houdini uses for motion blur, trails and velocity visualization. Data
+    /// without a velocity gets zero. This is called once when the data is logged, so it should be
+    /// cheap.
+    fn velocity(&self) -> Option<Vec3> {
+        None
+    }
+
+    /// The surface normal of the data, for example of a raycast hit or a contact. It's exported as
+    /// the `N` attribute, so the viewport shows it without any extra setup. Data without a normal
+    /// gets zero. This is called once when the data is logged, so it should be cheap.
+    fn normal(&self) -> Option<Vec3> {
+        None
+    }
+
+    /// The actual geometry of complex data such as meshes, which is sent along with the entry and
+    /// turned into a packed primitive by the Python receiver when
+    /// [`houlog_set_stream_geometry`](crate::houlog_set_stream_geometry()) is turned on. Of the
+    /// built-in types, [`Polygon`] and [`Mesh`] implement it. This is only called when exporting.
+    fn geometry(&self) -> Option<EntryGeometry> {
+        None
+    }
+
+    /// A scalar that is also recorded into the channel with the name of the entry, so that it can
+    /// be plotted over the frames, see [`houlog_channel_value`](crate::houlog_channel_value()).
+    /// This is called once when the data is logged.
+    fn plot_value(&self) -> Option<f32> {
+        None
+    }
+
+    /// An estimate of the memory used by the data in bytes, including heap allocations. Used for
+    /// the memory budget, see [`houlog_set_memory_budget`](crate::houlog_set_memory_budget()).
+    fn memory_size(&self) -> usize {
+        size_of_val(self)
+    }
+
+    /// The address and estimated size in bytes of data that is shared with other entries, such as
+    /// the contents of an `Arc`. It's not part of [`DebugLoggable::memory_size`], since the memory
+    /// budget counts each shared allocation only once, no matter how many entries refer to it.
+    fn shared_memory(&self) -> Option<(usize, usize)> {
+        None
+    }
+}
+
+/// Trait that can be implemented for converting any types into a loggable type. Theoretically,
+/// DebugLoggable could be used instead, but that would require making the HDA aware of the new type.
+/// Using this one instead is typically preferred and mostly just a convenience helper to be able
+/// to pass custom types directly into [`houlog`](crate::houlog()).
+pub trait IntoLoggable {
+    /// The loggable type that this type can be converted into.
+    type LoggableType: DebugLoggable + 'static;
+
+    /// Convert the type into a loggable type.
+    fn into_loggable(self) -> Self::LoggableType;
+}
+
+impl<T: DebugLoggable + 'static> IntoLoggable for T {
+    type LoggableType = T;
+    fn into_loggable(self) -> Self::LoggableType {
+        self
+    }
+}
+
+/// The polygons of an entry, see [`DebugLoggable::geometry`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntryGeometry {
+    #[serde(rename = "P")]
+    pub points: Vec<[f32; 3]>,
+    /// The number of vertices of each polygon.
+    pub counts: Vec<usize>,
+    /// The point indices of the vertices of all polygons, one polygon after another.
+    pub vertices: Vec<usize>,
+}
+
+/// Shared data such as an `Arc<Mesh>` can be logged without copying it, which matters when logging
+/// the same big mesh every frame. The shared data counts towards the memory budget only once,
+/// however many entries refer to it.
+impl<T: DebugLoggable + ?Sized> DebugLoggable for Arc<T> {
+    fn kind(&self) -> String {
+        (**self).kind()
+    }
+    fn position(&self) -> Vec3 {
+        (**self).position()
+    }
+    fn pscale(&self) -> Option<f32> {
+        (**self).pscale()
+    }
+    fn orient(&self) -> Option<Quat> {
+        (**self).orient()
+    }
+    fn velocity(&self) -> Option<Vec3> {
+        (**self).velocity()
+    }
+    fn normal(&self) -> Option<Vec3> {
+        (**self).normal()
+    }
+    fn geometry(&self) -> Option<EntryGeometry> {
+        (**self).geometry()
+    }
+    fn plot_value(&self) -> Option<f32> {
+        (**self).plot_value()
+    }
+    fn memory_size(&self) -> usize {
+        size_of_val(self)
+    }
+    fn shared_memory(&self) -> Option<(usize, usize)> {
+        Some((
+            Arc::as_ptr(self) as *const () as usize,
+            (**self).memory_size(),
+        ))
+    }
+
+    fn as_json(&self) -> String {
+        (**self).as_json()
+    }
 }
 
 impl DebugLoggable for Vec3 {
@@ -44,6 +191,9 @@ impl DebugLoggable for Mat4 {
     fn position(&self) -> Vec3 {
         self.w_axis.truncate()
     }
+    fn orient(&self) -> Option<Quat> {
+        Some(self.to_scale_rotation_translation().1)
+    }
 
     fn as_json(&self) -> String {
         json!(
@@ -67,6 +217,9 @@ impl DebugLoggable for Quat {
     fn position(&self) -> Vec3 {
         Vec3::new(0.0, 0.0, 0.0)
     }
+    fn orient(&self) -> Option<Quat> {
+        Some(*self)
+    }
 
     fn as_json(&self) -> String {
         json!(
@@ -78,6 +231,102 @@ impl DebugLoggable for Quat {
     }
 }
 
+impl IntoLoggable for DVec3 {
+    type LoggableType = Vec3;
+    fn into_loggable(self) -> Self::LoggableType {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl IntoLoggable for DMat4 {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        Mat4::from_cols_array(&self.to_cols_array().map(|v| v as f32))
+    }
+}
+
+impl IntoLoggable for DQuat {
+    type LoggableType = Quat;
+    fn into_loggable(self) -> Self::LoggableType {
+        Quat::from_xyzw(self.x as f32, self.y as f32, self.z as f32, self.w as f32)
+    }
+}
+
+/// A decomposed transform given as `(translation, rotation)`.
+impl IntoLoggable for (Vec3, Quat) {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        let (translation, rotation) = self;
+        Mat4::from_rotation_translation(rotation, translation)
+    }
+}
+
+/// A decomposed transform given as `(translation, rotation, scale)`.
+impl IntoLoggable for (Vec3, Quat, Vec3) {
+    type LoggableType = Mat4;
+    fn into_loggable(self) -> Self::LoggableType {
+        let (translation, rotation, scale) = self;
+        Mat4::from_scale_rotation_translation(scale, rotation, translation)
+    }
+}
+
+/// A rotation given as euler angles (in radians). The rotation order is passed along to Houdini so
+/// that bugs caused by mismatched rotation orders can be inspected. The angles are always about the
+/// same axes, no matter the order: yaw about Y, pitch about X and roll about Z. The proper and
+/// extrinsic orders added in glam 0.29 (such as `ZXZ` or `XYZEx`) can't be mapped that way, so for
+/// them the yaw, pitch and roll are passed to glam as the first, second and third angle instead.
+#[derive(Debug, Clone, Copy)]
+pub struct EulerAngles {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub order: EulerRot,
+}
+
+impl EulerAngles {
+    /// The angles in the order in which the axes appear in the rotation order, as passed to
+    /// [`Quat::from_euler`].
+    fn ordered_angles(&self) -> (f32, f32, f32) {
+        let (yaw, pitch, roll) = (self.yaw, self.pitch, self.roll);
+        match self.order {
+            EulerRot::ZYX => (roll, yaw, pitch),
+            EulerRot::ZXY => (roll, pitch, yaw),
+            EulerRot::YXZ => (yaw, pitch, roll),
+            EulerRot::YZX => (yaw, roll, pitch),
+            EulerRot::XYZ => (pitch, yaw, roll),
+            EulerRot::XZY => (pitch, roll, yaw),
+            // Only reachable with glam 0.29 and newer, the order is still recorded in the metadata.
+            #[allow(unreachable_patterns)]
+            _ => (yaw, pitch, roll),
+        }
+    }
+}
+
+impl DebugLoggable for EulerAngles {
+    fn kind(&self) -> String {
+        "euler".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+    fn orient(&self) -> Option<Quat> {
+        let (a, b, c) = self.ordered_angles();
+        Some(Quat::from_euler(self.order, a, b, c))
+    }
+
+    fn as_json(&self) -> String {
+        json!(
+            {
+                "yaw": self.yaw,
+                "pitch": self.pitch,
+                "roll": self.roll,
+                "order": format!("{:?}", self.order),
+            }
+        )
+        .to_string()
+    }
+}
+
 impl DebugLoggable for f32 {
     fn kind(&self) -> String {
         "float".to_string()
@@ -91,6 +340,64 @@ impl DebugLoggable for f32 {
     }
 }
 
+macro_rules! impl_int_loggable {
+    ($($t:ty),*) => {
+        $(
+            impl DebugLoggable for $t {
+                fn kind(&self) -> String {
+                    "int".to_string()
+                }
+                fn position(&self) -> Vec3 {
+                    Vec3::new(0.0, 0.0, 0.0)
+                }
+
+                fn as_json(&self) -> String {
+                    json!({ "int": self }).to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_int_loggable!(i32, i64, u32);
+
+/// A timing, for example how long a system took this frame. It's also plotted over the frames.
+impl DebugLoggable for Duration {
+    fn kind(&self) -> String {
+        "duration".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        Vec3::ZERO
+    }
+    fn plot_value(&self) -> Option<f32> {
+        Some(self.as_secs_f32())
+    }
+
+    fn as_json(&self) -> String {
+        json!({ "seconds": self.as_secs_f64() }).to_string()
+    }
+}
+
+/// A textual marker without a meaningful position, for example "spawned boss" or
+/// "state transition". The HDA shows these on the timeline.
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub message: String,
+}
+
+impl DebugLoggable for Event {
+    fn kind(&self) -> String {
+        "event".to_string()
+    }
+
+    fn as_json(&self) -> String {
+        json!({ "message": self.message }).to_string()
+    }
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + self.message.capacity()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Polyline {
     pub points: Vec<Vec3>,
@@ -101,7 +408,7 @@ impl DebugLoggable for Polyline {
         "line".to_string()
     }
     fn position(&self) -> Vec3 {
-        self.points[0]
+        self.points.first().copied().unwrap_or(Vec3::ZERO)
     }
 
     fn as_json(&self) -> String {
@@ -116,6 +423,10 @@ impl DebugLoggable for Polyline {
         })
         .to_string()
     }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.points)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -143,7 +454,23 @@ impl DebugLoggable for Polygon {
         "polygon".to_string()
     }
     fn position(&self) -> Vec3 {
-        self.points[0]
+        self.points.first().copied().unwrap_or(Vec3::ZERO)
+    }
+    fn normal(&self) -> Option<Vec3> {
+        // Newell's method, which also works for slightly non-planar polygons.
+        self.points
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .map(|(a, b)| a.cross(*b))
+            .sum::<Vec3>()
+            .try_normalize()
+    }
+    fn geometry(&self) -> Option<EntryGeometry> {
+        Some(EntryGeometry {
+            points: self.points.iter().map(|pt| pt.to_array()).collect(),
+            counts: vec![self.points.len()],
+            vertices: (0..self.points.len()).collect(),
+        })
     }
 
     fn as_json(&self) -> String {
@@ -158,6 +485,10 @@ impl DebugLoggable for Polygon {
         })
         .to_string()
     }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.points)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -172,7 +503,14 @@ impl DebugLoggable for Mesh {
         "mesh".to_string()
     }
     fn position(&self) -> Vec3 {
-        self.vertices[0]
+        self.vertices.first().copied().unwrap_or(Vec3::ZERO)
+    }
+    fn geometry(&self) -> Option<EntryGeometry> {
+        Some(EntryGeometry {
+            points: self.vertices.iter().map(|pt| pt.to_array()).collect(),
+            counts: self.index_counts.clone(),
+            vertices: self.indices.clone(),
+        })
     }
 
     fn as_json(&self) -> String {
@@ -189,4 +527,1579 @@ impl DebugLoggable for Mesh {
         })
         .to_string()
     }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self)
+            + heap_size(&self.vertices)
+            + heap_size(&self.indices)
+            + heap_size(&self.index_counts)
+    }
+}
+
+/// The heap memory used by a vector.
+fn heap_size<T>(v: &Vec<T>) -> usize {
+    v.capacity() * std::mem::size_of::<T>()
+}
+
+/// Splits points into separate x, y and z arrays, which is the layout the HDA expects.
+fn split_components<'a>(points: impl Iterator<Item = &'a Vec3>) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+    let mut z = Vec::new();
+    for pt in points {
+        x.push(pt.x);
+        y.push(pt.y);
+        z.push(pt.z);
+    }
+    (x, y, z)
+}
+
+/// A bounding volume hierarchy. Every node is given by its axis aligned bounding box as
+/// `(min, max)`, the index of its parent node (-1 for the root) and its depth in the tree, so that
+/// the HDA can filter by depth.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    pub node_aabbs: Vec<(Vec3, Vec3)>,
+    pub parents: Vec<i32>,
+    pub depths: Vec<u32>,
+}
+
+impl DebugLoggable for Bvh {
+    fn kind(&self) -> String {
+        "bvh".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.node_aabbs
+            .first()
+            .map(|(min, max)| (*min + *max) * 0.5)
+            .unwrap_or(Vec3::ZERO)
+    }
+
+    fn as_json(&self) -> String {
+        let (min_x, min_y, min_z) = split_components(self.node_aabbs.iter().map(|(min, _)| min));
+        let (max_x, max_y, max_z) = split_components(self.node_aabbs.iter().map(|(_, max)| max));
+
+        json!({
+            "min_x": min_x,
+            "min_y": min_y,
+            "min_z": min_z,
+            "max_x": max_x,
+            "max_y": max_y,
+            "max_z": max_z,
+            "parent": self.parents,
+            "depth": self.depths,
+        })
+        .to_string()
+    }
+    fn memory_size(&self) -> usize {
+        size_of_val(self)
+            + heap_size(&self.node_aabbs)
+            + heap_size(&self.parents)
+            + heap_size(&self.depths)
+    }
+}
+
+/// The cells of a (sparse) octree. For every cell, the center, the edge length, the depth and
+/// whether the cell is occupied is recorded.
+#[derive(Debug, Clone)]
+pub struct Octree {
+    pub centers: Vec<Vec3>,
+    pub sizes: Vec<f32>,
+    pub depths: Vec<u32>,
+    pub occupied: Vec<bool>,
+}
+
+impl DebugLoggable for Octree {
+    fn kind(&self) -> String {
+        "octree".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.centers.first().copied().unwrap_or(Vec3::ZERO)
+    }
+
+    fn as_json(&self) -> String {
+        let (x, y, z) = split_components(self.centers.iter());
+
+        json!({
+            "x": x,
+            "y": y,
+            "z": z,
+            "size": self.sizes,
+            "depth": self.depths,
+            "occupied": self.occupied,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self)
+            + heap_size(&self.centers)
+            + heap_size(&self.sizes)
+            + heap_size(&self.depths)
+            + heap_size(&self.occupied)
+    }
+}
+
+/// A single splitting plane of a kd-tree, limited to the cell it splits.
+#[derive(Debug, Clone, Copy)]
+pub struct KdSplit {
+    /// The axis the plane is perpendicular to (0 = x, 1 = y, 2 = z).
+    pub axis: u8,
+    /// The position of the plane along its axis.
+    pub value: f32,
+    /// The minimum corner of the cell that is split.
+    pub cell_min: Vec3,
+    /// The maximum corner of the cell that is split.
+    pub cell_max: Vec3,
+}
+
+/// The trace of a nearest-neighbor query through a kd-tree: the splitting planes that were
+/// considered and the cells that were visited (in visiting order), so that the pruning behavior
+/// can be inspected.
+#[derive(Debug, Clone)]
+pub struct KdTreeTrace {
+    pub query: Vec3,
+    pub nearest: Option<Vec3>,
+    pub splits: Vec<KdSplit>,
+    pub visited_cells: Vec<(Vec3, Vec3)>,
+}
+
+impl DebugLoggable for KdTreeTrace {
+    fn kind(&self) -> String {
+        "kdtree_trace".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.query
+    }
+
+    fn as_json(&self) -> String {
+        let (split_min_x, split_min_y, split_min_z) =
+            split_components(self.splits.iter().map(|split| &split.cell_min));
+        let (split_max_x, split_max_y, split_max_z) =
+            split_components(self.splits.iter().map(|split| &split.cell_max));
+        let (min_x, min_y, min_z) = split_components(self.visited_cells.iter().map(|(min, _)| min));
+        let (max_x, max_y, max_z) = split_components(self.visited_cells.iter().map(|(_, max)| max));
+
+        let split_axis = self
+            .splits
+            .iter()
+            .map(|split| split.axis)
+            .collect::<Vec<u8>>();
+        let split_value = self
+            .splits
+            .iter()
+            .map(|split| split.value)
+            .collect::<Vec<f32>>();
+        let nearest = self.nearest.map(|pt| [pt.x, pt.y, pt.z]);
+
+        json!({
+            "query": [self.query.x, self.query.y, self.query.z],
+            "nearest": nearest,
+            "split_axis": split_axis,
+            "split_value": split_value,
+            "split_min_x": split_min_x,
+            "split_min_y": split_min_y,
+            "split_min_z": split_min_z,
+            "split_max_x": split_max_x,
+            "split_max_y": split_max_y,
+            "split_max_z": split_max_z,
+            "min_x": min_x,
+            "min_y": min_y,
+            "min_z": min_z,
+            "max_x": max_x,
+            "max_y": max_y,
+            "max_z": max_z,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.splits) + heap_size(&self.visited_cells)
+    }
+}
+
+/// The convex hull of a set of points, for example the support points of GJK/EPA or a contact
+/// manifold. Only the points are exported, the hull itself is built by the HDA.
+#[derive(Debug, Clone)]
+pub struct ConvexHull {
+    pub points: Vec<Vec3>,
+}
+
+impl DebugLoggable for ConvexHull {
+    fn kind(&self) -> String {
+        "convex_hull".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.points.first().copied().unwrap_or(Vec3::ZERO)
+    }
+
+    fn as_json(&self) -> String {
+        let (x, y, z) = split_components(self.points.iter());
+
+        json!({
+            "x": x,
+            "y": y,
+            "z": z,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.points)
+    }
+}
+
+/// The plane onto which 2D types such as [`Rect`] are mapped when they are logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Plane2D {
+    /// x maps to x, y maps to y.
+    XY,
+    /// x maps to x, y maps to z. This is the ground plane in Houdini.
+    #[default]
+    XZ,
+    /// x maps to y, y maps to z.
+    YZ,
+}
+
+impl Plane2D {
+    /// Maps a 2D point onto this plane.
+    pub fn to_3d(self, v: Vec2) -> Vec3 {
+        match self {
+            Plane2D::XY => Vec3::new(v.x, v.y, 0.0),
+            Plane2D::XZ => Vec3::new(v.x, 0.0, v.y),
+            Plane2D::YZ => Vec3::new(0.0, v.x, v.y),
+        }
+    }
+}
+
+static PLANE_2D: AtomicU8 = AtomicU8::new(Plane2D::XZ as u8);
+
+/// Set the plane onto which 2D types are mapped. Defaults to [`Plane2D::XZ`].
+pub fn houlog_set_2d_plane(plane: Plane2D) {
+    PLANE_2D.store(plane as u8, Ordering::Relaxed);
+}
+
+/// The plane onto which 2D types are currently mapped.
+pub fn houlog_2d_plane() -> Plane2D {
+    match PLANE_2D.load(Ordering::Relaxed) {
+        x if x == Plane2D::XY as u8 => Plane2D::XY,
+        x if x == Plane2D::YZ as u8 => Plane2D::YZ,
+        _ => Plane2D::XZ,
+    }
+}
+
+/// A 2D rectangle, for example a screen region or a UI layout box. It is logged as a [`Polygon`]
+/// on the plane configured via [`houlog_set_2d_plane`].
+#[derive(Debug, Clone, Copy)]
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl IntoLoggable for Rect {
+    type LoggableType = Polygon;
+    fn into_loggable(self) -> Self::LoggableType {
+        let plane = houlog_2d_plane();
+        Polygon {
+            points: vec![
+                plane.to_3d(self.min),
+                plane.to_3d(Vec2::new(self.max.x, self.min.y)),
+                plane.to_3d(self.max),
+                plane.to_3d(Vec2::new(self.min.x, self.max.y)),
+            ],
+        }
+    }
+}
+
+/// A reference grid spanned by `axis_u` and `axis_v` starting at `origin`, subdivided into
+/// `divisions_u` by `divisions_v` cells. Handy for visualizing chunk boundaries and spatial hash
+/// cells.
+#[derive(Debug, Clone, Copy)]
+pub struct GridLines {
+    pub origin: Vec3,
+    /// The full extent of the grid along its first axis.
+    pub axis_u: Vec3,
+    /// The full extent of the grid along its second axis.
+    pub axis_v: Vec3,
+    pub divisions_u: u32,
+    pub divisions_v: u32,
+}
+
+impl DebugLoggable for GridLines {
+    fn kind(&self) -> String {
+        "grid".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.origin
+    }
+
+    fn as_json(&self) -> String {
+        json!({
+            "origin": [self.origin.x, self.origin.y, self.origin.z],
+            "axis_u": [self.axis_u.x, self.axis_u.y, self.axis_u.z],
+            "axis_v": [self.axis_v.x, self.axis_v.y, self.axis_v.z],
+            "divisions_u": self.divisions_u,
+            "divisions_v": self.divisions_v,
+        })
+        .to_string()
+    }
+}
+
+/// Nodes connected by edges, for example a dependency or navigation graph. The edges are given as
+/// the indices of the nodes they connect.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    pub nodes: Vec<Vec3>,
+    pub edges: Vec<(usize, usize)>,
+    /// Whether the edges point from their first node to their second one.
+    pub directed: bool,
+}
+
+impl DebugLoggable for Graph {
+    fn kind(&self) -> String {
+        "graph".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.nodes.first().copied().unwrap_or(Vec3::ZERO)
+    }
+
+    fn as_json(&self) -> String {
+        let (x, y, z) = split_components(self.nodes.iter());
+        let from = self.edges.iter().map(|e| e.0).collect::<Vec<usize>>();
+        let to = self.edges.iter().map(|e| e.1).collect::<Vec<usize>>();
+
+        json!({
+            "x": x,
+            "y": y,
+            "z": z,
+            "from": from,
+            "to": to,
+            "directed": self.directed,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.nodes) + heap_size(&self.edges)
+    }
+}
+
+/// A regular grid of heights on the ground plane, for example a terrain or a noise texture. The
+/// heights are stored row by row, with rows along z and columns along x, starting at the origin.
+#[derive(Debug, Clone)]
+pub struct Heightfield {
+    pub width: usize,
+    pub height: usize,
+    /// The distance between neighbouring samples.
+    pub cell_size: f32,
+    pub heights: Vec<f32>,
+}
+
+impl DebugLoggable for Heightfield {
+    fn kind(&self) -> String {
+        "heightfield".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        Vec3::ZERO
+    }
+
+    fn as_json(&self) -> String {
+        json!({
+            "width": self.width,
+            "height": self.height,
+            "cell_size": self.cell_size,
+            "heights": self.heights,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.heights)
+    }
+}
+
+/// A regular grid of colored points, for example an image, on the given plane. The colors are
+/// stored row by row, starting at the origin.
+#[derive(Debug, Clone)]
+pub struct ColorGrid {
+    pub width: usize,
+    pub height: usize,
+    /// The distance between neighbouring points.
+    pub cell_size: f32,
+    pub plane: Plane2D,
+    /// The colors as RGB in the range 0 to 1.
+    pub colors: Vec<[f32; 3]>,
+}
+
+impl DebugLoggable for ColorGrid {
+    fn kind(&self) -> String {
+        "color_grid".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        Vec3::ZERO
+    }
+
+    fn as_json(&self) -> String {
+        let axis_u = self.plane.to_3d(Vec2::X) * self.cell_size;
+        let axis_v = self.plane.to_3d(Vec2::Y) * self.cell_size;
+        let r = self.colors.iter().map(|c| c[0]).collect::<Vec<f32>>();
+        let g = self.colors.iter().map(|c| c[1]).collect::<Vec<f32>>();
+        let b = self.colors.iter().map(|c| c[2]).collect::<Vec<f32>>();
+
+        json!({
+            "width": self.width,
+            "height": self.height,
+            "axis_u": [axis_u.x, axis_u.y, axis_u.z],
+            "axis_v": [axis_v.x, axis_v.y, axis_v.z],
+            "r": r,
+            "g": g,
+            "b": b,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.colors)
+    }
+}
+
+/// An unconnected set of points, for example samples of a distribution. Slices, `Vec`s and arrays
+/// of [`Vec3`] are automatically converted into a point cloud when logged.
+#[derive(Debug, Clone)]
+pub struct PointCloud {
+    pub points: Vec<Vec3>,
+}
+
+impl DebugLoggable for PointCloud {
+    fn kind(&self) -> String {
+        "points".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.points.first().copied().unwrap_or(Vec3::ZERO)
+    }
+
+    fn as_json(&self) -> String {
+        let (x, y, z) = split_components(self.points.iter());
+
+        json!({
+            "x": x,
+            "y": y,
+            "z": z,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + heap_size(&self.points)
+    }
+}
+
+impl IntoLoggable for Vec<Vec3> {
+    type LoggableType = PointCloud;
+    fn into_loggable(self) -> Self::LoggableType {
+        PointCloud { points: self }
+    }
+}
+
+impl IntoLoggable for &Vec<Vec3> {
+    type LoggableType = PointCloud;
+    fn into_loggable(self) -> Self::LoggableType {
+        PointCloud {
+            points: self.clone(),
+        }
+    }
+}
+
+impl IntoLoggable for &[Vec3] {
+    type LoggableType = PointCloud;
+    fn into_loggable(self) -> Self::LoggableType {
+        PointCloud {
+            points: self.to_vec(),
+        }
+    }
+}
+
+impl<const N: usize> IntoLoggable for [Vec3; N] {
+    type LoggableType = PointCloud;
+    fn into_loggable(self) -> Self::LoggableType {
+        PointCloud {
+            points: self.to_vec(),
+        }
+    }
+}
+
+impl<const N: usize> IntoLoggable for &[Vec3; N] {
+    type LoggableType = PointCloud;
+    fn into_loggable(self) -> Self::LoggableType {
+        PointCloud {
+            points: self.to_vec(),
+        }
+    }
+}
+
+/// A sphere, for example a ball collider.
+#[derive(Debug, Clone, Copy)]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl DebugLoggable for Sphere {
+    fn kind(&self) -> String {
+        "sphere".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.center
+    }
+    fn pscale(&self) -> Option<f32> {
+        Some(self.radius)
+    }
+
+    fn as_json(&self) -> String {
+        json!({ "radius": self.radius }).to_string()
+    }
+}
+
+/// A capsule given by the two end points of its segment and its radius.
+#[derive(Debug, Clone, Copy)]
+pub struct Capsule {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub radius: f32,
+}
+
+impl DebugLoggable for Capsule {
+    fn kind(&self) -> String {
+        "capsule".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.start
+    }
+    fn pscale(&self) -> Option<f32> {
+        Some(self.radius)
+    }
+
+    fn as_json(&self) -> String {
+        json!({
+            "start": [self.start.x, self.start.y, self.start.z],
+            "end": [self.end.x, self.end.y, self.end.z],
+            "radius": self.radius,
+        })
+        .to_string()
+    }
+}
+
+/// An oriented bounding box.
+#[derive(Debug, Clone, Copy)]
+pub struct Obb {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub rotation: Quat,
+}
+
+impl DebugLoggable for Obb {
+    fn kind(&self) -> String {
+        "obb".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.center
+    }
+    fn orient(&self) -> Option<Quat> {
+        Some(self.rotation)
+    }
+
+    fn as_json(&self) -> String {
+        json!({
+            "half_extents": [self.half_extents.x, self.half_extents.y, self.half_extents.z],
+            "quat": [self.rotation.x, self.rotation.y, self.rotation.z, self.rotation.w],
+        })
+        .to_string()
+    }
+}
+
+/// The transform of a moving body together with its linear and angular velocity, which the HDA
+/// displays as arrows.
+#[derive(Debug, Clone, Copy)]
+pub struct Kinematics {
+    pub transform: Mat4,
+    pub linear_velocity: Vec3,
+    pub angular_velocity: Vec3,
+}
+
+impl DebugLoggable for Kinematics {
+    fn kind(&self) -> String {
+        "kinematics".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.transform.w_axis.truncate()
+    }
+    fn orient(&self) -> Option<Quat> {
+        self.transform.orient()
+    }
+    fn velocity(&self) -> Option<Vec3> {
+        Some(self.linear_velocity)
+    }
+
+    fn as_json(&self) -> String {
+        let xform = self.transform.to_cols_array();
+        let linear = self.linear_velocity;
+        let angular = self.angular_velocity;
+
+        json!({
+            "xform": xform,
+            "linear_velocity": [linear.x, linear.y, linear.z],
+            "angular_velocity": [angular.x, angular.y, angular.z],
+        })
+        .to_string()
+    }
+}
+
+/// Many copies of the same shape, for example a crowd or foliage. Only the transforms are logged,
+/// while the shape itself is logged once under `template_name`, so that 10k copies don't blow up
+/// the recording. The HDA turns the transforms into points with an `orient` attribute, ready for
+/// instancing.
+#[derive(Debug, Clone)]
+pub struct Instances {
+    /// The name of the entry holding the shape that is instanced.
+    pub template_name: String,
+    pub xforms: Vec<Mat4>,
+}
+
+impl DebugLoggable for Instances {
+    fn kind(&self) -> String {
+        "instances".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.xforms
+            .first()
+            .map_or(Vec3::ZERO, |xform| xform.w_axis.truncate())
+    }
+
+    fn as_json(&self) -> String {
+        let xforms = self
+            .xforms
+            .iter()
+            .flat_map(|xform| xform.to_cols_array())
+            .collect::<Vec<f32>>();
+
+        json!({
+            "template": self.template_name,
+            "xforms": xforms,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + self.template_name.capacity() + heap_size(&self.xforms)
+    }
+}
+
+/// A point on a surface together with the surface normal there, for example a raycast hit or a
+/// contact point.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceHit {
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+impl DebugLoggable for SurfaceHit {
+    fn kind(&self) -> String {
+        "surface_hit".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.point
+    }
+    fn normal(&self) -> Option<Vec3> {
+        Some(self.normal)
+    }
+
+    fn as_json(&self) -> String {
+        let normal = self.normal;
+        json!({ "normal": [normal.x, normal.y, normal.z] }).to_string()
+    }
+}
+
+/// Any of the supported collision shapes. This is what physics engine shapes are converted into.
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Sphere(Sphere),
+    Capsule(Capsule),
+    Obb(Obb),
+    ConvexHull(ConvexHull),
+    Mesh(Mesh),
+    /// Multiple shapes that together form one shape.
+    Compound(Vec<Shape>),
+}
+
+impl DebugLoggable for Shape {
+    fn kind(&self) -> String {
+        match self {
+            Shape::Sphere(sphere) => sphere.kind(),
+            Shape::Capsule(capsule) => capsule.kind(),
+            Shape::Obb(obb) => obb.kind(),
+            Shape::ConvexHull(hull) => hull.kind(),
+            Shape::Mesh(mesh) => mesh.kind(),
+            Shape::Compound(_) => "compound".to_string(),
+        }
+    }
+    fn position(&self) -> Vec3 {
+        match self {
+            Shape::Sphere(sphere) => sphere.position(),
+            Shape::Capsule(capsule) => capsule.position(),
+            Shape::Obb(obb) => obb.position(),
+            Shape::ConvexHull(hull) => hull.position(),
+            Shape::Mesh(mesh) => mesh.position(),
+            Shape::Compound(shapes) => shapes
+                .first()
+                .map(|shape| shape.position())
+                .unwrap_or(Vec3::ZERO),
+        }
+    }
+    fn pscale(&self) -> Option<f32> {
+        match self {
+            Shape::Sphere(sphere) => sphere.pscale(),
+            Shape::Capsule(capsule) => capsule.pscale(),
+            _ => None,
+        }
+    }
+    fn orient(&self) -> Option<Quat> {
+        match self {
+            Shape::Obb(obb) => obb.orient(),
+            _ => None,
+        }
+    }
+
+    fn as_json(&self) -> String {
+        match self {
+            Shape::Sphere(sphere) => sphere.as_json(),
+            Shape::Capsule(capsule) => capsule.as_json(),
+            Shape::Obb(obb) => obb.as_json(),
+            Shape::ConvexHull(hull) => hull.as_json(),
+            Shape::Mesh(mesh) => mesh.as_json(),
+            Shape::Compound(shapes) => {
+                let shapes = shapes
+                    .iter()
+                    .map(|shape| {
+                        let pos = shape.position();
+                        let metadata: Value =
+                            serde_json::from_str(&shape.as_json()).unwrap_or(Value::Null);
+                        json!({
+                            "kind": shape.kind(),
+                            "pt": [pos.x, pos.y, pos.z],
+                            "metadata": metadata,
+                        })
+                    })
+                    .collect::<Vec<Value>>();
+
+                json!({ "shapes": shapes }).to_string()
+            }
+        }
+    }
+
+    fn memory_size(&self) -> usize {
+        match self {
+            Shape::Sphere(sphere) => sphere.memory_size(),
+            Shape::Capsule(capsule) => capsule.memory_size(),
+            Shape::Obb(obb) => obb.memory_size(),
+            Shape::ConvexHull(hull) => hull.memory_size(),
+            Shape::Mesh(mesh) => mesh.memory_size(),
+            Shape::Compound(shapes) => {
+                size_of_val(self)
+                    + shapes
+                        .iter()
+                        .map(|shape| shape.memory_size())
+                        .sum::<usize>()
+            }
+        }
+    }
+}
+
+/// Wraps any serializable value so that it can be logged without implementing [`DebugLoggable`]
+/// for it. The value is exported as the metadata, so it can be inspected in the geometry
+/// spreadsheet. Values that don't serialize into a JSON object are stored under the `value` key.
+#[derive(Debug, Clone)]
+pub struct Json<T: Serialize> {
+    pub value: T,
+    pub position: Option<Vec3>,
+}
+
+impl<T: Serialize> Json<T> {
+    pub fn new(value: T) -> Self {
+        Json {
+            value,
+            position: None,
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec3) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+impl<T: Serialize + Send + Sync> DebugLoggable for Json<T> {
+    fn kind(&self) -> String {
+        "json".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.position.unwrap_or(Vec3::ZERO)
+    }
+
+    fn as_json(&self) -> String {
+        match serde_json::to_value(&self.value) {
+            Ok(value @ Value::Object(_)) => value.to_string(),
+            Ok(value) => json!({ "value": value }).to_string(),
+            Err(e) => json!({ "error": e.to_string() }).to_string(),
+        }
+    }
+    /// The heap allocations of `T` aren't known, so its serialized size is used as an estimate.
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + serde_json::to_vec(&self.value).map_or(0, |json| json.len())
+    }
+}
+
+/// Wraps a value that was logged because an assertion failed, see
+/// [`houassert!`](crate::houassert!). The kind of the wrapped value and its metadata are kept in
+/// the metadata.
+pub struct AssertFailed {
+    pub condition: String,
+    pub value: Box<dyn DebugLoggable>,
+}
+
+impl DebugLoggable for AssertFailed {
+    fn kind(&self) -> String {
+        "assert_failed".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        self.value.position()
+    }
+    fn pscale(&self) -> Option<f32> {
+        self.value.pscale()
+    }
+    fn orient(&self) -> Option<Quat> {
+        self.value.orient()
+    }
+    fn velocity(&self) -> Option<Vec3> {
+        self.value.velocity()
+    }
+    fn normal(&self) -> Option<Vec3> {
+        self.value.normal()
+    }
+    fn geometry(&self) -> Option<EntryGeometry> {
+        self.value.geometry()
+    }
+    fn plot_value(&self) -> Option<f32> {
+        self.value.plot_value()
+    }
+
+    fn as_json(&self) -> String {
+        let metadata: Value = serde_json::from_str(&self.value.as_json()).unwrap_or(Value::Null);
+
+        json!({
+            "condition": self.condition,
+            "kind": self.value.kind(),
+            "metadata": metadata,
+        })
+        .to_string()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + self.condition.capacity() + self.value.memory_size()
+    }
+    fn shared_memory(&self) -> Option<(usize, usize)> {
+        self.value.shared_memory()
+    }
+}
+
+/// A value together with its velocity, which is exported as the `v` attribute. Otherwise, the
+/// value is exported as if it was logged directly. See
+/// [`houlog_with_velocity`](crate::houlog_with_velocity()).
+#[derive(Debug, Clone, Copy)]
+pub struct Moving<T> {
+    pub value: T,
+    pub velocity: Vec3,
+}
+
+impl<T: DebugLoggable> DebugLoggable for Moving<T> {
+    fn kind(&self) -> String {
+        self.value.kind()
+    }
+    fn position(&self) -> Vec3 {
+        self.value.position()
+    }
+    fn pscale(&self) -> Option<f32> {
+        self.value.pscale()
+    }
+    fn orient(&self) -> Option<Quat> {
+        self.value.orient()
+    }
+    fn velocity(&self) -> Option<Vec3> {
+        Some(self.velocity)
+    }
+    fn normal(&self) -> Option<Vec3> {
+        self.value.normal()
+    }
+    fn geometry(&self) -> Option<EntryGeometry> {
+        self.value.geometry()
+    }
+    fn plot_value(&self) -> Option<f32> {
+        self.value.plot_value()
+    }
+
+    fn as_json(&self) -> String {
+        self.value.as_json()
+    }
+
+    fn memory_size(&self) -> usize {
+        size_of_val(self) - size_of_val(&self.value) + self.value.memory_size()
+    }
+    fn shared_memory(&self) -> Option<(usize, usize)> {
+        self.value.shared_memory()
+    }
+}
+
+/// A timed section of code, see [`houlog_span`](crate::houlog_span()). Times are in seconds, with
+/// the start relative to the initialization of the logger.
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub start: f32,
+    pub duration: f32,
+    /// The number of spans this span is nested in.
+    pub depth: u32,
+    /// The name of the span this span is nested in.
+    pub parent: Option<String>,
+}
+
+impl DebugLoggable for Span {
+    fn kind(&self) -> String {
+        "span".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        Vec3::new(0.0, 0.0, 0.0)
+    }
+
+    fn as_json(&self) -> String {
+        json!({
+            "start": self.start,
+            "duration": self.duration,
+            "depth": self.depth,
+            "parent": self.parent,
+        })
+        .to_string()
+    }
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + self.parent.as_ref().map_or(0, String::capacity)
+    }
+}
+
+/// A [`Span`] as a bar of a per-frame profile, logged instead of the span when profiling is turned
+/// on via [`houlog_set_profiling`](crate::houlog_set_profiling()). The position is the lower left
+/// corner of the bar: X is the start in milliseconds since the frame started, Y the depth and Z the
+/// index of the track (the thread the span ran on). Bars are `duration * 1000` long and one unit
+/// high, so nested spans stack on top of each other.
+#[derive(Debug, Clone)]
+pub struct ProfileBar {
+    /// The start of the span in seconds since the frame started.
+    pub start: f32,
+    pub duration: f32,
+    pub depth: u32,
+    /// The name of the thread the span ran on.
+    pub track: String,
+    /// The index of the track, in the order in which the tracks first appeared.
+    pub track_index: u32,
+    /// The name of the span this span is nested in.
+    pub parent: Option<String>,
+}
+
+impl DebugLoggable for ProfileBar {
+    fn kind(&self) -> String {
+        "profile_bar".to_string()
+    }
+    fn position(&self) -> Vec3 {
+        Vec3::new(
+            self.start * 1000.0,
+            self.depth as f32,
+            self.track_index as f32,
+        )
+    }
+
+    fn as_json(&self) -> String {
+        json!({
+            "start": self.start,
+            "duration": self.duration,
+            "depth": self.depth,
+            "track": self.track,
+            "parent": self.parent,
+        })
+        .to_string()
+    }
+    fn memory_size(&self) -> usize {
+        size_of_val(self) + self.track.capacity() + self.parent.as_ref().map_or(0, String::capacity)
+    }
+}
+
+/// An aggregated numeric metric of a single frame, see [`houlog_counter`](crate::houlog_counter())
+/// and [`houlog_gauge`](crate::houlog_gauge()).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    /// The sum of all increments during the frame.
+    Counter(i64),
+    /// The last value set during the frame.
+    Gauge(f64),
+}
+
+impl DebugLoggable for Metric {
+    fn kind(&self) -> String {
+        match self {
+            Metric::Counter(_) => "counter".to_string(),
+            Metric::Gauge(_) => "gauge".to_string(),
+        }
+    }
+
+    fn as_json(&self) -> String {
+        match self {
+            Metric::Counter(v) => json!({ "value": v }).to_string(),
+            Metric::Gauge(v) => json!({ "value": v }).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The kind and the parsed metadata of the value, as the HDA sees them.
+    fn exported(value: impl IntoLoggable) -> (String, Value) {
+        let value = value.into_loggable();
+        let metadata = serde_json::from_str(&value.as_json()).expect("metadata should be JSON");
+        (value.kind(), metadata)
+    }
+
+    #[test]
+    fn arc_only_counts_the_pointer() {
+        let polyline = Arc::new(Polyline {
+            points: vec![Vec3::ZERO; 100],
+        });
+        assert_eq!(polyline.memory_size(), size_of::<usize>());
+        assert!(polyline.memory_size() < (*polyline).memory_size());
+    }
+
+    #[test]
+    fn heap_allocations_count_towards_the_memory_size() {
+        let message = "a".repeat(100);
+        let event = Event {
+            message: message.clone(),
+        };
+        assert!(event.memory_size() >= size_of::<Event>() + 100);
+
+        let bvh = Bvh {
+            node_aabbs: vec![(Vec3::ZERO, Vec3::ONE); 10],
+            parents: vec![0; 10],
+            depths: vec![0; 10],
+        };
+        assert!(bvh.memory_size() >= size_of::<Bvh>() + 10 * (24 + 4 + 4));
+
+        let span = Span {
+            start: 0.0,
+            duration: 0.0,
+            depth: 0,
+            parent: Some(message.clone()),
+        };
+        assert!(span.memory_size() >= size_of::<Span>() + 100);
+
+        let bar = ProfileBar {
+            start: 0.0,
+            duration: 0.0,
+            depth: 0,
+            track: message.clone(),
+            track_index: 0,
+            parent: None,
+        };
+        assert!(bar.memory_size() >= size_of::<ProfileBar>() + 100);
+
+        assert!(Json::new(vec![message]).memory_size() > 100);
+    }
+
+    #[test]
+    fn wrappers_forward_plot_values() {
+        let duration = Duration::from_millis(500);
+        let moving = Moving {
+            value: duration,
+            velocity: Vec3::X,
+        };
+        assert_eq!(moving.plot_value(), Some(0.5));
+        let failed = AssertFailed {
+            condition: "false".to_string(),
+            value: Box::new(duration),
+        };
+        assert_eq!(failed.plot_value(), Some(0.5));
+    }
+
+    #[test]
+    fn euler_angles_are_about_fixed_axes() {
+        let (yaw, pitch, roll) = (0.1, 0.2, 0.3);
+        let expected =
+            Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch) * Quat::from_rotation_z(roll);
+        let orient = |order| {
+            EulerAngles {
+                yaw,
+                pitch,
+                roll,
+                order,
+            }
+            .orient()
+            .unwrap()
+        };
+        assert!(orient(EulerRot::YXZ).abs_diff_eq(expected, 1e-6));
+
+        let expected =
+            Quat::from_rotation_z(roll) * Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch);
+        assert!(orient(EulerRot::ZYX).abs_diff_eq(expected, 1e-6));
+    }
+
+    #[test]
+    fn empty_shapes_dont_panic() {
+        let values: [Box<dyn DebugLoggable>; 3] = [
+            Box::new(Polyline { points: Vec::new() }),
+            Box::new(Polygon { points: Vec::new() }),
+            Box::new(Mesh {
+                vertices: Vec::new(),
+                indices: Vec::new(),
+                index_counts: Vec::new(),
+            }),
+        ];
+        for value in values {
+            assert_eq!(value.position(), Vec3::ZERO);
+            assert_eq!(value.normal(), None);
+            value.as_json();
+            value.geometry();
+        }
+    }
+
+    #[test]
+    fn ints_are_exported_as_ints() {
+        assert_eq!(exported(-3i32), ("int".to_string(), json!({ "int": -3 })));
+        assert_eq!(
+            exported(i64::MAX),
+            ("int".to_string(), json!({ "int": i64::MAX }))
+        );
+        assert_eq!(
+            exported(u32::MAX),
+            ("int".to_string(), json!({ "int": u32::MAX }))
+        );
+    }
+
+    #[test]
+    fn durations_are_exported_in_seconds() {
+        let duration = Duration::from_millis(250);
+        assert_eq!(
+            exported(duration),
+            ("duration".to_string(), json!({ "seconds": 0.25 }))
+        );
+    }
+
+    #[test]
+    fn events_export_their_message() {
+        let event = Event {
+            message: "spawned boss".to_string(),
+        };
+        assert_eq!(event.position(), Vec3::ZERO);
+        assert_eq!(
+            exported(event),
+            ("event".to_string(), json!({ "message": "spawned boss" }))
+        );
+    }
+
+    #[test]
+    fn bvh_nodes_are_split_into_components() {
+        let bvh = Bvh {
+            node_aabbs: vec![
+                (Vec3::ZERO, Vec3::splat(2.0)),
+                (Vec3::ONE, Vec3::splat(2.0)),
+            ],
+            parents: vec![-1, 0],
+            depths: vec![0, 1],
+        };
+        assert_eq!(bvh.position(), Vec3::ONE);
+        let expected = json!({
+            "min_x": [0.0, 1.0],
+            "min_y": [0.0, 1.0],
+            "min_z": [0.0, 1.0],
+            "max_x": [2.0, 2.0],
+            "max_y": [2.0, 2.0],
+            "max_z": [2.0, 2.0],
+            "parent": [-1, 0],
+            "depth": [0, 1],
+        });
+        assert_eq!(exported(bvh), ("bvh".to_string(), expected));
+    }
+
+    #[test]
+    fn octree_cells_are_exported() {
+        let octree = Octree {
+            centers: vec![Vec3::new(1.0, 2.0, 3.0)],
+            sizes: vec![4.0],
+            depths: vec![2],
+            occupied: vec![true],
+        };
+        let expected = json!({
+            "x": [1.0],
+            "y": [2.0],
+            "z": [3.0],
+            "size": [4.0],
+            "depth": [2],
+            "occupied": [true],
+        });
+        assert_eq!(exported(octree), ("octree".to_string(), expected));
+    }
+
+    #[test]
+    fn kd_tree_traces_export_splits_and_cells() {
+        let trace = KdTreeTrace {
+            query: Vec3::new(1.0, 2.0, 3.0),
+            nearest: None,
+            splits: vec![KdSplit {
+                axis: 1,
+                value: 0.5,
+                cell_min: Vec3::ZERO,
+                cell_max: Vec3::ONE,
+            }],
+            visited_cells: vec![(Vec3::ZERO, Vec3::ONE)],
+        };
+        assert_eq!(trace.position(), Vec3::new(1.0, 2.0, 3.0));
+        let (kind, metadata) = exported(trace);
+        assert_eq!(kind, "kdtree_trace");
+        assert_eq!(metadata["query"], json!([1.0, 2.0, 3.0]));
+        assert_eq!(metadata["nearest"], Value::Null);
+        assert_eq!(metadata["split_axis"], json!([1]));
+        assert_eq!(metadata["split_value"], json!([0.5]));
+        assert_eq!(metadata["split_max_y"], json!([1.0]));
+        assert_eq!(metadata["min_x"], json!([0.0]));
+        assert_eq!(metadata["max_z"], json!([1.0]));
+    }
+
+    #[test]
+    fn convex_hulls_and_point_clouds_export_their_points() {
+        let points = vec![Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0)];
+        let expected = json!({ "x": [1.0, 4.0], "y": [2.0, 5.0], "z": [3.0, 6.0] });
+        let hull = ConvexHull {
+            points: points.clone(),
+        };
+        assert_eq!(
+            exported(hull),
+            ("convex_hull".to_string(), expected.clone())
+        );
+        assert_eq!(exported(&points), ("points".to_string(), expected.clone()));
+        assert_eq!(
+            exported(points.as_slice()),
+            ("points".to_string(), expected.clone())
+        );
+        assert_eq!(exported(points), ("points".to_string(), expected));
+    }
+
+    #[test]
+    fn rects_are_polygons_on_the_2d_plane() {
+        let rect = Rect {
+            min: Vec2::ZERO,
+            max: Vec2::new(1.0, 2.0),
+        };
+        assert_eq!(houlog_2d_plane(), Plane2D::XZ);
+        let polygon = rect.into_loggable();
+        assert_eq!(polygon.kind(), "polygon");
+        assert_eq!(
+            polygon.points,
+            [
+                Vec3::ZERO,
+                Vec3::X,
+                Vec3::new(1.0, 0.0, 2.0),
+                Vec3::new(0.0, 0.0, 2.0)
+            ]
+        );
+        assert_eq!(
+            Plane2D::XY.to_3d(Vec2::new(1.0, 2.0)),
+            Vec3::new(1.0, 2.0, 0.0)
+        );
+        assert_eq!(
+            Plane2D::YZ.to_3d(Vec2::new(1.0, 2.0)),
+            Vec3::new(0.0, 1.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn grid_lines_are_exported() {
+        let grid = GridLines {
+            origin: Vec3::ONE,
+            axis_u: Vec3::X,
+            axis_v: Vec3::Z,
+            divisions_u: 4,
+            divisions_v: 2,
+        };
+        let expected = json!({
+            "origin": [1.0, 1.0, 1.0],
+            "axis_u": [1.0, 0.0, 0.0],
+            "axis_v": [0.0, 0.0, 1.0],
+            "divisions_u": 4,
+            "divisions_v": 2,
+        });
+        assert_eq!(exported(grid), ("grid".to_string(), expected));
+    }
+
+    #[test]
+    fn graphs_export_nodes_and_edges() {
+        let graph = Graph {
+            nodes: vec![Vec3::ZERO, Vec3::X],
+            edges: vec![(0, 1)],
+            directed: true,
+        };
+        let expected = json!({
+            "x": [0.0, 1.0],
+            "y": [0.0, 0.0],
+            "z": [0.0, 0.0],
+            "from": [0],
+            "to": [1],
+            "directed": true,
+        });
+        assert_eq!(exported(graph), ("graph".to_string(), expected));
+    }
+
+    #[test]
+    fn heightfields_and_color_grids_are_exported() {
+        let heightfield = Heightfield {
+            width: 2,
+            height: 1,
+            cell_size: 0.5,
+            heights: vec![1.0, 2.0],
+        };
+        let expected = json!({
+            "width": 2,
+            "height": 1,
+            "cell_size": 0.5,
+            "heights": [1.0, 2.0],
+        });
+        assert_eq!(exported(heightfield), ("heightfield".to_string(), expected));
+
+        let grid = ColorGrid {
+            width: 1,
+            height: 1,
+            cell_size: 2.0,
+            plane: Plane2D::XY,
+            colors: vec![[1.0, 0.5, 0.0]],
+        };
+        let expected = json!({
+            "width": 1,
+            "height": 1,
+            "axis_u": [2.0, 0.0, 0.0],
+            "axis_v": [0.0, 2.0, 0.0],
+            "r": [1.0],
+            "g": [0.5],
+            "b": [0.0],
+        });
+        assert_eq!(exported(grid), ("color_grid".to_string(), expected));
+    }
+
+    #[test]
+    fn collision_shapes_are_exported() {
+        let sphere = Sphere {
+            center: Vec3::ONE,
+            radius: 2.0,
+        };
+        assert_eq!(sphere.pscale(), Some(2.0));
+        assert_eq!(
+            exported(sphere),
+            ("sphere".to_string(), json!({ "radius": 2.0 }))
+        );
+
+        let capsule = Capsule {
+            start: Vec3::ZERO,
+            end: Vec3::Y,
+            radius: 0.5,
+        };
+        let expected = json!({ "start": [0.0, 0.0, 0.0], "end": [0.0, 1.0, 0.0], "radius": 0.5 });
+        assert_eq!(exported(capsule), ("capsule".to_string(), expected));
+
+        let obb = Obb {
+            center: Vec3::ZERO,
+            half_extents: Vec3::new(1.0, 2.0, 3.0),
+            rotation: Quat::IDENTITY,
+        };
+        let expected = json!({ "half_extents": [1.0, 2.0, 3.0], "quat": [0.0, 0.0, 0.0, 1.0] });
+        assert_eq!(exported(obb), ("obb".to_string(), expected));
+
+        let compound = Shape::Compound(vec![Shape::Sphere(sphere), Shape::Obb(obb)]);
+        assert_eq!(compound.position(), Vec3::ONE);
+        let expected = json!({
+            "shapes": [
+                { "kind": "sphere", "pt": [1.0, 1.0, 1.0], "metadata": { "radius": 2.0 } },
+                {
+                    "kind": "obb",
+                    "pt": [0.0, 0.0, 0.0],
+                    "metadata": { "half_extents": [1.0, 2.0, 3.0], "quat": [0.0, 0.0, 0.0, 1.0] },
+                },
+            ],
+        });
+        assert_eq!(exported(compound), ("compound".to_string(), expected));
+        assert_eq!(Shape::Sphere(sphere).kind(), "sphere");
+    }
+
+    #[test]
+    fn kinematics_export_their_velocities() {
+        let kinematics = Kinematics {
+            transform: Mat4::from_translation(Vec3::ONE),
+            linear_velocity: Vec3::X,
+            angular_velocity: Vec3::Y,
+        };
+        assert_eq!(kinematics.position(), Vec3::ONE);
+        assert_eq!(kinematics.velocity(), Some(Vec3::X));
+        let (kind, metadata) = exported(kinematics);
+        assert_eq!(kind, "kinematics");
+        assert_eq!(metadata["xform"][12], json!(1.0));
+        assert_eq!(metadata["linear_velocity"], json!([1.0, 0.0, 0.0]));
+        assert_eq!(metadata["angular_velocity"], json!([0.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn instances_export_the_template_and_the_transforms() {
+        let instances = Instances {
+            template_name: "tree".to_string(),
+            xforms: vec![Mat4::IDENTITY, Mat4::from_translation(Vec3::X)],
+        };
+        assert_eq!(instances.position(), Vec3::ZERO);
+        let (kind, metadata) = exported(instances);
+        assert_eq!(kind, "instances");
+        assert_eq!(metadata["template"], "tree");
+        assert_eq!(metadata["xforms"].as_array().map(Vec::len), Some(32));
+        assert_eq!(metadata["xforms"][28], json!(1.0));
+    }
+
+    #[test]
+    fn surface_hits_export_their_normal() {
+        let hit = SurfaceHit {
+            point: Vec3::ONE,
+            normal: Vec3::Y,
+        };
+        assert_eq!(hit.normal(), Some(Vec3::Y));
+        assert_eq!(
+            exported(hit),
+            (
+                "surface_hit".to_string(),
+                json!({ "normal": [0.0, 1.0, 0.0] })
+            )
+        );
+    }
+
+    #[test]
+    fn json_wraps_non_objects() {
+        #[derive(Serialize)]
+        struct State {
+            health: u32,
+        }
+        let state = Json::new(State { health: 3 }).with_position(Vec3::X);
+        assert_eq!(state.position(), Vec3::X);
+        assert_eq!(
+            exported(state),
+            ("json".to_string(), json!({ "health": 3 }))
+        );
+        assert_eq!(
+            exported(Json::new([1, 2])),
+            ("json".to_string(), json!({ "value": [1, 2] }))
+        );
+    }
+
+    #[test]
+    fn failed_assertions_keep_the_wrapped_value() {
+        let failed = AssertFailed {
+            condition: "x > 0".to_string(),
+            value: Box::new(Vec3::ONE),
+        };
+        assert_eq!(failed.position(), Vec3::ONE);
+        let expected = json!({
+            "condition": "x > 0",
+            "kind": "vec3",
+            "metadata": { "pt": [1.0, 1.0, 1.0] },
+        });
+        assert_eq!(exported(failed), ("assert_failed".to_string(), expected));
+    }
+
+    #[test]
+    fn moving_values_are_exported_like_the_value() {
+        let moving = Moving {
+            value: Vec3::ONE,
+            velocity: Vec3::X,
+        };
+        assert_eq!(moving.velocity(), Some(Vec3::X));
+        assert_eq!(exported(moving), exported(Vec3::ONE));
+    }
+
+    #[test]
+    fn spans_and_profile_bars_are_exported() {
+        let span = Span {
+            start: 1.0,
+            duration: 0.5,
+            depth: 1,
+            parent: Some("frame".to_string()),
+        };
+        let expected = json!({ "start": 1.0, "duration": 0.5, "depth": 1, "parent": "frame" });
+        assert_eq!(exported(span), ("span".to_string(), expected));
+
+        let bar = ProfileBar {
+            start: 0.25,
+            duration: 0.5,
+            depth: 2,
+            track: "main".to_string(),
+            track_index: 3,
+            parent: None,
+        };
+        assert_eq!(bar.position(), Vec3::new(250.0, 2.0, 3.0));
+        let expected = json!({
+            "start": 0.25,
+            "duration": 0.5,
+            "depth": 2,
+            "track": "main",
+            "parent": null,
+        });
+        assert_eq!(exported(bar), ("profile_bar".to_string(), expected));
+    }
+
+    #[test]
+    fn metrics_export_their_value() {
+        assert_eq!(
+            exported(Metric::Counter(3)),
+            ("counter".to_string(), json!({ "value": 3 }))
+        );
+        assert_eq!(
+            exported(Metric::Gauge(0.5)),
+            ("gauge".to_string(), json!({ "value": 0.5 }))
+        );
+    }
+
+    #[test]
+    fn euler_angles_export_the_order() {
+        let euler = EulerAngles {
+            yaw: 1.0,
+            pitch: 0.5,
+            roll: 0.25,
+            order: EulerRot::ZYX,
+        };
+        let expected = json!({ "yaw": 1.0, "pitch": 0.5, "roll": 0.25, "order": "ZYX" });
+        assert_eq!(exported(euler), ("euler".to_string(), expected));
+    }
+
+    #[test]
+    fn double_precision_and_decomposed_transforms_are_converted() {
+        assert_eq!(
+            DVec3::new(1.0, 2.0, 3.0).into_loggable(),
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(DQuat::IDENTITY.into_loggable(), Quat::IDENTITY);
+        let translation = DMat4::from_translation(DVec3::X).into_loggable();
+        assert_eq!(translation, Mat4::from_translation(Vec3::X));
+
+        let xform = (Vec3::X, Quat::IDENTITY).into_loggable();
+        assert_eq!(xform, Mat4::from_translation(Vec3::X));
+        let xform = (Vec3::X, Quat::IDENTITY, Vec3::splat(2.0)).into_loggable();
+        assert_eq!(
+            xform,
+            Mat4::from_scale_rotation_translation(Vec3::splat(2.0), Quat::IDENTITY, Vec3::X)
+        );
+    }
 }
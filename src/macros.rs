@@ -0,0 +1,42 @@
+/// Like [`houlog`](crate::houlog()), but the name is given as a format string, which avoids having
+/// to write `&format!(..)` at every call site. The last argument is the value to log.
+///
+/// ```no_run
+/// # use houdini_debug_logger::houlog;
+/// # let (agent_id, target, velocity) = (7, glam::Vec3::ZERO, glam::Vec3::X);
+/// houlog!("agent/{}/target", agent_id, target);
+/// houlog!("agent/{agent_id}/velocity", velocity);
+/// ```
+#[macro_export]
+macro_rules! houlog {
+    (@munch [$fmt:literal] [$($args:expr,)*] $value:expr $(,)?) => {
+        if $crate::__HOULOG_ENABLED {
+            $crate::houlog(&format!($fmt, $($args),*), $value)
+        }
+    };
+    (@munch [$fmt:literal] [$($args:expr,)*] $next:expr, $($rest:tt)+) => {
+        $crate::houlog!(@munch [$fmt] [$($args,)* $next,] $($rest)+)
+    };
+    ($fmt:literal, $($rest:tt)+) => {
+        $crate::houlog!(@munch [$fmt] [] $($rest)+)
+    };
+}
+
+/// Checks a condition and, if it doesn't hold, logs the value with the `assert_failed` kind, saves
+/// the recording and then panics (unless turned off via
+/// [`houlog_set_assert_panics`](crate::houlog_set_assert_panics())). This turns intermittent
+/// violations of geometric invariants into a saved Houdini scene.
+///
+/// ```no_run
+/// # use houdini_debug_logger::houassert;
+/// # let (normal, hit_point) = (glam::Vec3::Y, glam::Vec3::ZERO);
+/// houassert!(normal.is_normalized(), "normal", hit_point);
+/// ```
+#[macro_export]
+macro_rules! houassert {
+    ($cond:expr, $name:expr, $value:expr $(,)?) => {
+        if $crate::__HOULOG_ENABLED && !$cond {
+            $crate::__houassert_failed(stringify!($cond), $name, $value);
+        }
+    };
+}
@@ -0,0 +1,17 @@
+/// What to do when the in-memory recording exceeds its memory budget, see
+/// [`houlog_set_memory_budget`](crate::houlog_set_memory_budget()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MemoryPolicy {
+    /// Drop the oldest frames, like [`houlog_set_max_frames`](crate::houlog_set_max_frames()).
+    #[default]
+    DropOldest,
+    /// Drop every other entry of the kind that uses the most memory, thinning out dense data such
+    /// as point clouds while keeping all frames. Falls back to dropping the oldest frames once
+    /// there is nothing left to thin out.
+    Downsample,
+    /// Export what has been recorded so far and then clear it, like
+    /// [`houlog_snapshot`](crate::houlog_snapshot()) with the names `flush1`, `flush2` and so on,
+    /// so that each flush ends up in its own node or file instead of replacing the previous one.
+    /// When streaming, which doesn't support snapshots, the recording is saved as usual instead.
+    Flush,
+}
@@ -0,0 +1,11 @@
+/// How the metadata of each entry is exported, see
+/// [`houlog_set_metadata_format`](crate::houlog_set_metadata_format()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MetadataFormat {
+    /// A JSON string attribute. This is what the HDA expects.
+    #[default]
+    Json = 0,
+    /// A dictionary attribute, which keeps the types of the values and can be read in VEX without
+    /// parsing JSON. Requires Houdini 19.5 or newer.
+    Dictionary = 1,
+}
@@ -0,0 +1,106 @@
+use std::ops::{Bound, RangeBounds};
+
+/// Selects entries for [`houlog_query`](crate::houlog_query()). All conditions have to match, and
+/// the default filter matches every entry.
+#[derive(Debug, Clone, Default)]
+pub struct HoulogFilter {
+    name: Option<String>,
+    kind: Option<String>,
+    first_frame: usize,
+    /// The first frame after the selected ones, so that empty ranges match nothing.
+    end_frame: Option<usize>,
+}
+
+impl HoulogFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match entries with this name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only match entries of this kind, for example `vec3` or `mesh`.
+    pub fn with_kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    /// Only match entries of this (0-based) frame.
+    pub fn with_frame(self, frame: usize) -> Self {
+        self.with_frames(frame..=frame)
+    }
+
+    /// Only match entries of these (0-based) frames.
+    pub fn with_frames(mut self, frames: impl RangeBounds<usize>) -> Self {
+        self.first_frame = match frames.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        self.end_frame = match frames.end_bound() {
+            Bound::Included(&end) => end.checked_add(1),
+            Bound::Excluded(&end) => Some(end),
+            Bound::Unbounded => None,
+        };
+        self
+    }
+
+    #[cfg_attr(not(feature = "memory"), allow(dead_code))]
+    pub(crate) fn matches_frame(&self, frame: usize) -> bool {
+        frame >= self.first_frame && self.end_frame.iter().all(|&end| frame < end)
+    }
+
+    #[cfg_attr(not(feature = "memory"), allow(dead_code))]
+    pub(crate) fn matches(&self, name: &str, kind: &str) -> bool {
+        self.name.iter().all(|n| n == name) && self.kind.iter().all(|k| k == kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_ranges_match_their_frames() {
+        let filter = HoulogFilter::new().with_frames(2..4);
+        assert!(!filter.matches_frame(1));
+        assert!(filter.matches_frame(2));
+        assert!(filter.matches_frame(3));
+        assert!(!filter.matches_frame(4));
+
+        let filter = HoulogFilter::new().with_frame(3);
+        assert!(!filter.matches_frame(2));
+        assert!(filter.matches_frame(3));
+        assert!(!filter.matches_frame(4));
+
+        assert!(HoulogFilter::new()
+            .with_frames(5..)
+            .matches_frame(usize::MAX));
+        assert!(HoulogFilter::new()
+            .with_frames(..=usize::MAX)
+            .matches_frame(usize::MAX));
+        assert!(HoulogFilter::new().matches_frame(0));
+    }
+
+    #[test]
+    fn empty_frame_ranges_match_nothing() {
+        let filter = HoulogFilter::new().with_frames(0..0);
+        assert!(!filter.matches_frame(0));
+        assert!(!filter.matches_frame(1));
+
+        let filter = HoulogFilter::new().with_frames(3..3);
+        assert!((0..10).all(|frame| !filter.matches_frame(frame)));
+    }
+
+    #[test]
+    fn names_and_kinds_have_to_match() {
+        let filter = HoulogFilter::new().with_name("a").with_kind("vec3");
+        assert!(filter.matches("a", "vec3"));
+        assert!(!filter.matches("b", "vec3"));
+        assert!(!filter.matches("a", "mesh"));
+        assert!(HoulogFilter::new().matches("b", "mesh"));
+    }
+}
@@ -0,0 +1,565 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::loggable::{
+    EntryGeometry, Event, Mesh, Metric, Polygon, Polyline, Span, DEFAULT_PSCALE,
+};
+use anyhow::{anyhow, Result};
+use glam::{Mat4, Quat, Vec3};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A point of a saved recording. The fields match the point attributes of the exported geometry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedPoint {
+    #[serde(rename = "P")]
+    pub position: [f32; 3],
+    /// The size of the entry, see [`DebugLoggable::pscale`](crate::DebugLoggable::pscale).
+    #[serde(default = "default_pscale")]
+    pub pscale: f32,
+    /// The rotation of the entry as `[x, y, z, w]`, see
+    /// [`DebugLoggable::orient`](crate::DebugLoggable::orient).
+    #[serde(default = "default_orient")]
+    pub orient: [f32; 4],
+    /// The velocity of the entry, see
+    /// [`DebugLoggable::velocity`](crate::DebugLoggable::velocity).
+    #[serde(rename = "v", default)]
+    pub velocity: [f32; 3],
+    /// The normal of the entry, see [`DebugLoggable::normal`](crate::DebugLoggable::normal).
+    #[serde(rename = "N", default)]
+    pub normal: [f32; 3],
+    pub name: String,
+    pub kind: String,
+    /// The (1-based) frame of the entry, or 0 for the channel plots. Offset by the start frame of
+    /// `houlog_set_timeline`, if any, so that the first frame is at the start frame.
+    pub time: f32,
+    pub frame_label: String,
+    pub sim_time: f32,
+    pub channel: String,
+    pub level: i32,
+    /// The metadata as JSON, see [`DebugLoggable::as_json`](crate::DebugLoggable::as_json).
+    pub metadata: String,
+    /// The id set via `houlog_id`, or -1.
+    pub id: i64,
+    pub src_file: String,
+    pub src_line: u32,
+    /// The wall-clock time at which the entry was logged, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub realtime: f64,
+    /// The custom attributes set via `houlog_entry`, with their original names.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, Value>,
+    /// The geometry of the entry, which is only exported with `houlog_set_stream_geometry` turned
+    /// on, see [`DebugLoggable::geometry`](crate::DebugLoggable::geometry). It isn't read back
+    /// from `.bgeo` files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<EntryGeometry>,
+}
+
+/// An empty entry on the first frame, with the same defaults as a point without the optional
+/// attributes.
+impl Default for RecordedPoint {
+    fn default() -> Self {
+        RecordedPoint {
+            position: [0.0; 3],
+            pscale: default_pscale(),
+            orient: default_orient(),
+            velocity: [0.0; 3],
+            normal: [0.0; 3],
+            name: String::new(),
+            kind: String::new(),
+            time: 1.0,
+            frame_label: String::new(),
+            sim_time: 0.0,
+            channel: String::new(),
+            level: 0,
+            metadata: String::new(),
+            id: -1,
+            src_file: String::new(),
+            src_line: 0,
+            realtime: 0.0,
+            attributes: BTreeMap::new(),
+            geometry: None,
+        }
+    }
+}
+
+impl RecordedPoint {
+    /// The (0-based) frame of the entry, or `None` for the channel plots.
+    pub fn frame(&self) -> Option<u32> {
+        (self.time as u32).checked_sub(1)
+    }
+
+    /// Decodes the metadata back into the type that has been logged, for example a [`Mesh`].
+    /// Fails if the entry is of a different kind.
+    pub fn decode<T: DecodeLoggable>(&self) -> Result<T> {
+        if !T::KINDS.contains(&self.kind.as_str()) {
+            return Err(anyhow!(
+                "Can't decode entry {} of kind {} as {}",
+                self.name,
+                self.kind,
+                std::any::type_name::<T>()
+            ));
+        }
+        T::decode(self, &serde_json::from_str(&self.metadata)?)
+    }
+}
+
+/// A frame of a [`Recording`], see [`Recording::frames`].
+#[derive(Debug, Clone)]
+pub struct RecordedFrame<'a> {
+    /// The (0-based) index of the frame.
+    pub index: u32,
+    pub label: &'a str,
+    pub sim_time: f32,
+    pub entries: Vec<&'a RecordedPoint>,
+}
+
+/// A saved recording, for inspecting recordings in tests and tools, or for converting and merging
+/// them, for example via `houlog-cli`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    pub points: Vec<RecordedPoint>,
+}
+
+impl Recording {
+    /// Reads a recording with one JSON object per line, as written by [`Recording::write_ndjson`].
+    pub fn read_ndjson(path: impl AsRef<Path>) -> Result<Self> {
+        let mut points = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                points.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Recording { points })
+    }
+
+    /// Writes the recording with one JSON object per point and line (NDJSON), which is easy to
+    /// process with line-based tools.
+    pub fn write_ndjson(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for point in &self.points {
+            serde_json::to_writer(&mut writer, point)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a recording saved via `init_houlog`. This starts a Houdini Engine session.
+    #[cfg(feature = "enabled")]
+    pub fn read_bgeo(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Recording {
+            points: crate::houdini_debug_logger::read_bgeo(path.as_ref())?,
+        })
+    }
+
+    /// Writes the recording in the same format as `init_houlog`. This starts a Houdini Engine
+    /// session.
+    #[cfg(feature = "enabled")]
+    pub fn write_bgeo(&self, path: impl AsRef<Path>) -> Result<()> {
+        crate::houdini_debug_logger::write_bgeo(path.as_ref(), &self.points)
+    }
+
+    #[cfg(not(feature = "enabled"))]
+    pub fn read_bgeo(path: impl AsRef<Path>) -> Result<Self> {
+        Err(anyhow!(
+            "Reading {} requires the `enabled` feature",
+            path.as_ref().display()
+        ))
+    }
+
+    #[cfg(not(feature = "enabled"))]
+    pub fn write_bgeo(&self, path: impl AsRef<Path>) -> Result<()> {
+        Err(anyhow!(
+            "Writing {} requires the `enabled` feature",
+            path.as_ref().display()
+        ))
+    }
+
+    /// Loads a recording, choosing the format by the file extension: `.ndjson` and `.jsonl` for
+    /// NDJSON, everything else is read as geometry.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if is_ndjson(path) {
+            Self::read_ndjson(path)
+        } else {
+            Self::read_bgeo(path)
+        }
+    }
+
+    /// Saves the recording, choosing the format by the file extension like [`Recording::load`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if is_ndjson(path) {
+            self.write_ndjson(path)
+        } else {
+            self.write_bgeo(path)
+        }
+    }
+
+    /// All entries, including the channel plots.
+    pub fn entries(&self) -> impl Iterator<Item = &RecordedPoint> {
+        self.points.iter()
+    }
+
+    /// The entries with the given name.
+    pub fn entries_named<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a RecordedPoint> {
+        let name = name.to_string();
+        self.points.iter().filter(move |point| point.name == name)
+    }
+
+    /// The names of all entries, sorted.
+    pub fn names(&self) -> BTreeSet<&str> {
+        self.points
+            .iter()
+            .map(|point| point.name.as_str())
+            .collect()
+    }
+
+    /// Returns the first entry with the given name, and panics if there is none. Meant for tests,
+    /// for example with [`houlog_exported`](crate::houlog_exported()).
+    #[track_caller]
+    pub fn assert_logged(&self, name: &str) -> &RecordedPoint {
+        match self.entries_named(name).next() {
+            Some(point) => point,
+            None => panic!(
+                "Expected an entry named {:?}, but only got {:?}",
+                name,
+                self.names()
+            ),
+        }
+    }
+
+    /// Panics if there are entries with the given name.
+    #[track_caller]
+    pub fn assert_not_logged(&self, name: &str) {
+        let count = self.entries_named(name).count();
+        assert!(
+            count == 0,
+            "Expected no entries named {:?}, but got {}",
+            name,
+            count
+        );
+    }
+
+    /// Panics unless there are exactly `count` entries with the given name.
+    #[track_caller]
+    pub fn assert_count(&self, name: &str, count: usize) {
+        let actual = self.entries_named(name).count();
+        assert!(
+            actual == count,
+            "Expected {} entries named {:?}, but got {}",
+            count,
+            name,
+            actual
+        );
+    }
+
+    /// The channel plots, see `houlog_channel_value`.
+    pub fn channel_plots(&self) -> impl Iterator<Item = &RecordedPoint> {
+        self.points.iter().filter(|point| point.frame().is_none())
+    }
+
+    /// The frames that have entries, in order.
+    pub fn frames(&self) -> Vec<RecordedFrame<'_>> {
+        let mut frames = BTreeMap::<u32, RecordedFrame>::new();
+        for point in &self.points {
+            let Some(index) = point.frame() else {
+                continue;
+            };
+            frames
+                .entry(index)
+                .or_insert_with(|| RecordedFrame {
+                    index,
+                    label: &point.frame_label,
+                    sim_time: point.sim_time,
+                    entries: Vec::new(),
+                })
+                .entries
+                .push(point);
+        }
+        frames.into_values().collect()
+    }
+
+    /// Merges the recordings into one, interleaving their frames. Points of the same frame keep
+    /// the order of the recordings.
+    pub fn merge(recordings: impl IntoIterator<Item = Recording>) -> Self {
+        let mut points = recordings
+            .into_iter()
+            .flat_map(|recording| recording.points)
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Recording { points }
+    }
+}
+
+/// Loggable types that can be decoded from a saved recording, see [`RecordedPoint::decode`]. The
+/// implementations mirror [`DebugLoggable::as_json`](crate::DebugLoggable::as_json).
+pub trait DecodeLoggable: Sized {
+    /// The kinds of entries this type can be decoded from.
+    const KINDS: &'static [&'static str];
+
+    /// Decodes the entry, given its parsed metadata.
+    fn decode(point: &RecordedPoint, metadata: &Value) -> Result<Self>;
+}
+
+impl DecodeLoggable for Vec3 {
+    const KINDS: &'static [&'static str] = &["vec3"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Vec3::from(field::<[f32; 3]>(metadata, "pt")?))
+    }
+}
+
+impl DecodeLoggable for Mat4 {
+    const KINDS: &'static [&'static str] = &["mat4"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Mat4::from_cols_array(&field(metadata, "xform")?))
+    }
+}
+
+impl DecodeLoggable for Quat {
+    const KINDS: &'static [&'static str] = &["quat"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Quat::from_array(field(metadata, "quat")?))
+    }
+}
+
+impl DecodeLoggable for f32 {
+    const KINDS: &'static [&'static str] = &["float"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        field(metadata, "float")
+    }
+}
+
+impl DecodeLoggable for i64 {
+    const KINDS: &'static [&'static str] = &["int"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        field(metadata, "int")
+    }
+}
+
+impl DecodeLoggable for Event {
+    const KINDS: &'static [&'static str] = &["event"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Event {
+            message: field(metadata, "message")?,
+        })
+    }
+}
+
+impl DecodeLoggable for Polyline {
+    const KINDS: &'static [&'static str] = &["line"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Polyline {
+            points: points(metadata)?,
+        })
+    }
+}
+
+impl DecodeLoggable for Polygon {
+    const KINDS: &'static [&'static str] = &["polygon"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Polygon {
+            points: points(metadata)?,
+        })
+    }
+}
+
+impl DecodeLoggable for Mesh {
+    const KINDS: &'static [&'static str] = &["mesh"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Mesh {
+            vertices: points(metadata)?,
+            indices: field(metadata, "i")?,
+            index_counts: field(metadata, "c")?,
+        })
+    }
+}
+
+impl DecodeLoggable for Span {
+    const KINDS: &'static [&'static str] = &["span"];
+
+    fn decode(_point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        Ok(Span {
+            start: field(metadata, "start")?,
+            duration: field(metadata, "duration")?,
+            depth: field(metadata, "depth")?,
+            parent: field(metadata, "parent")?,
+        })
+    }
+}
+
+impl DecodeLoggable for Metric {
+    const KINDS: &'static [&'static str] = &["counter", "gauge"];
+
+    fn decode(point: &RecordedPoint, metadata: &Value) -> Result<Self> {
+        match point.kind.as_str() {
+            "counter" => Ok(Metric::Counter(field(metadata, "value")?)),
+            _ => Ok(Metric::Gauge(field(metadata, "value")?)),
+        }
+    }
+}
+
+/// Deserializes a field of the metadata.
+fn field<T: DeserializeOwned>(metadata: &Value, name: &str) -> Result<T> {
+    let value = metadata
+        .get(name)
+        .ok_or_else(|| anyhow!("Metadata is missing the field {}", name))?;
+    Ok(T::deserialize(value)?)
+}
+
+/// Joins the `x`, `y` and `z` arrays of the metadata back into points.
+fn points(metadata: &Value) -> Result<Vec<Vec3>> {
+    let x = field::<Vec<f32>>(metadata, "x")?;
+    let y = field::<Vec<f32>>(metadata, "y")?;
+    let z = field::<Vec<f32>>(metadata, "z")?;
+    Ok(x.into_iter()
+        .zip(y)
+        .zip(z)
+        .map(|((x, y), z)| Vec3::new(x, y, z))
+        .collect())
+}
+
+fn default_pscale() -> f32 {
+    DEFAULT_PSCALE
+}
+
+fn default_orient() -> [f32; 4] {
+    Quat::IDENTITY.to_array()
+}
+
+fn is_ndjson(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("ndjson" | "jsonl")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loggable::DebugLoggable;
+    use serde_json::json;
+
+    fn recorded(name: &str, time: f32, value: &dyn DebugLoggable) -> RecordedPoint {
+        RecordedPoint {
+            name: name.to_string(),
+            kind: value.kind(),
+            time,
+            metadata: value.as_json(),
+            ..RecordedPoint::default()
+        }
+    }
+
+    #[test]
+    fn ndjson_round_trips() -> Result<()> {
+        let mut point = recorded("target", 2.0, &Vec3::new(1.0, 2.0, 3.0));
+        point.attributes.insert("health".to_string(), json!(0.5));
+        let recording = Recording {
+            points: vec![point, recorded("plot", 0.0, &1.0f32)],
+        };
+        let path =
+            std::env::temp_dir().join(format!("houlog_recording_{}.ndjson", std::process::id()));
+        recording.save(&path)?;
+        let loaded = Recording::load(&path);
+        std::fs::remove_file(&path)?;
+        assert_eq!(loaded?, recording);
+        Ok(())
+    }
+
+    #[test]
+    fn ndjson_fills_in_missing_attributes() -> Result<()> {
+        let line = json!({
+            "P": [0.0, 0.0, 0.0], "name": "a", "kind": "vec3", "time": 1.0, "frame_label": "",
+            "sim_time": 0.0, "channel": "", "level": 0, "metadata": "{}", "id": -1,
+            "src_file": "", "src_line": 0,
+        });
+        let point = serde_json::from_value::<RecordedPoint>(line)?;
+        assert_eq!(
+            point,
+            RecordedPoint {
+                name: "a".to_string(),
+                kind: "vec3".to_string(),
+                metadata: "{}".to_string(),
+                ..RecordedPoint::default()
+            }
+        );
+        assert!(is_ndjson(Path::new("a.jsonl")));
+        assert!(!is_ndjson(Path::new("a.bgeo.sc")));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_restores_logged_values() -> Result<()> {
+        let mesh = Mesh {
+            vertices: vec![Vec3::ZERO, Vec3::X, Vec3::Y],
+            indices: vec![0, 1, 2],
+            index_counts: vec![3],
+        };
+        let decoded = recorded("mesh", 1.0, &mesh).decode::<Mesh>()?;
+        assert_eq!(decoded.vertices, mesh.vertices);
+        assert_eq!(decoded.indices, mesh.indices);
+        assert_eq!(decoded.index_counts, mesh.index_counts);
+
+        let xform = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(recorded("m", 1.0, &xform).decode::<Mat4>()?, xform);
+        assert_eq!(
+            recorded("c", 1.0, &Metric::Counter(3)).decode::<Metric>()?,
+            Metric::Counter(3)
+        );
+        assert_eq!(
+            recorded("g", 1.0, &Metric::Gauge(0.5)).decode::<Metric>()?,
+            Metric::Gauge(0.5)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_rejects_other_kinds_and_missing_fields() {
+        assert!(recorded("p", 1.0, &Vec3::ZERO).decode::<Mesh>().is_err());
+        let point = RecordedPoint {
+            kind: "line".to_string(),
+            metadata: json!({ "x": [0.0], "y": [0.0] }).to_string(),
+            ..RecordedPoint::default()
+        };
+        let error = point.decode::<Polyline>().unwrap_err();
+        assert_eq!(error.to_string(), "Metadata is missing the field z");
+    }
+
+    #[test]
+    fn frames_and_merge_keep_the_order() {
+        let a = Recording {
+            points: vec![recorded("a1", 1.0, &0.0f32), recorded("a2", 2.0, &0.0f32)],
+        };
+        let b = Recording {
+            points: vec![recorded("plot", 0.0, &0.0f32), recorded("b1", 1.0, &0.0f32)],
+        };
+        let merged = Recording::merge([a, b]);
+        let names = merged
+            .points
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["plot", "a1", "b1", "a2"]);
+
+        let frames = merged.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].index, 0);
+        assert_eq!(frames[0].entries.len(), 2);
+        assert_eq!(merged.channel_plots().count(), 1);
+    }
+}
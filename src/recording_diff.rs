@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::recording::{RecordedPoint, Recording};
+use serde_json::{json, Value};
+
+/// How an entry differs between two recordings, see [`Recording::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffStatus {
+    /// The entry only exists in the other recording.
+    Added,
+    /// The entry only exists in this recording.
+    Removed,
+    /// The entry exists in both recordings, but its position, metadata or attributes differ.
+    Changed,
+}
+
+impl DiffStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            DiffStatus::Added => "added",
+            DiffStatus::Removed => "removed",
+            DiffStatus::Changed => "changed",
+        }
+    }
+}
+
+/// An entry that differs between two recordings.
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    pub status: DiffStatus,
+    /// The entry in this recording, unless it has been added.
+    pub before: Option<RecordedPoint>,
+    /// The entry in the other recording, unless it has been removed.
+    pub after: Option<RecordedPoint>,
+}
+
+impl DiffEntry {
+    /// The entry in the other recording, or the one in this recording if it has been removed.
+    pub fn point(&self) -> &RecordedPoint {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .expect("diff entries have at least one side")
+    }
+}
+
+/// The result of [`Recording::diff`]. Printing it gives a short report with one line per
+/// difference.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingDiff {
+    pub entries: Vec<DiffEntry>,
+    /// The number of entries that are the same in both recordings.
+    pub unchanged: usize,
+}
+
+impl RecordingDiff {
+    /// Whether the recordings are the same within the tolerance.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of entries with the given status.
+    pub fn count(&self, status: DiffStatus) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status == status)
+            .count()
+    }
+
+    /// A recording with only the entries that differ, for inspecting the differences in Houdini.
+    /// Each entry gets a `diff` attribute that's `added`, `removed` or `changed`. Changed entries
+    /// are taken from the other recording.
+    pub fn to_recording(&self) -> Recording {
+        let points = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut point = entry.point().clone();
+                point
+                    .attributes
+                    .insert("diff".to_string(), json!(entry.status.as_str()));
+                point
+            })
+            .collect();
+        Recording { points }
+    }
+}
+
+impl fmt::Display for RecordingDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} added, {} removed, {} changed, {} unchanged",
+            self.count(DiffStatus::Added),
+            self.count(DiffStatus::Removed),
+            self.count(DiffStatus::Changed),
+            self.unchanged
+        )?;
+        for entry in &self.entries {
+            let point = entry.point();
+            let frame = match point.frame() {
+                Some(frame) => frame.to_string(),
+                None => "plots".to_string(),
+            };
+            writeln!(
+                f,
+                "{:>8} frame {}: {} ({})",
+                entry.status.as_str(),
+                frame,
+                point.name,
+                point.kind
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Recording {
+    /// Compares this recording with another one, for example to detect regressions in the output
+    /// of procedural generation between two code revisions. Entries are matched by frame, name and
+    /// id, and in the order in which they have been logged. Numbers in positions, metadata and
+    /// custom attributes may differ by up to `tolerance`. Source locations and wall-clock times
+    /// are ignored, since they change between revisions anyway.
+    pub fn diff(&self, other: &Recording, tolerance: f64) -> RecordingDiff {
+        type Key<'a> = (Option<u32>, &'a str, i64);
+        let mut groups = BTreeMap::<Key, (Vec<&RecordedPoint>, Vec<&RecordedPoint>)>::new();
+        for point in &self.points {
+            let key = (point.frame(), point.name.as_str(), point.id);
+            groups.entry(key).or_default().0.push(point);
+        }
+        for point in &other.points {
+            let key = (point.frame(), point.name.as_str(), point.id);
+            groups.entry(key).or_default().1.push(point);
+        }
+
+        let mut diff = RecordingDiff::default();
+        for (before, after) in groups.into_values() {
+            for i in 0..before.len().max(after.len()) {
+                let before = before.get(i).copied();
+                let after = after.get(i).copied();
+                let status = match (before, after) {
+                    (Some(before), Some(after)) if points_match(before, after, tolerance) => {
+                        diff.unchanged += 1;
+                        continue;
+                    }
+                    (Some(_), Some(_)) => DiffStatus::Changed,
+                    (Some(_), None) => DiffStatus::Removed,
+                    (None, _) => DiffStatus::Added,
+                };
+                diff.entries.push(DiffEntry {
+                    status,
+                    before: before.cloned(),
+                    after: after.cloned(),
+                });
+            }
+        }
+        diff
+    }
+}
+
+fn points_match(a: &RecordedPoint, b: &RecordedPoint, tolerance: f64) -> bool {
+    let metadata_matches = match (
+        serde_json::from_str::<Value>(&a.metadata),
+        serde_json::from_str::<Value>(&b.metadata),
+    ) {
+        (Ok(a), Ok(b)) => values_match(&a, &b, tolerance),
+        _ => a.metadata == b.metadata,
+    };
+    a.kind == b.kind
+        && a.channel == b.channel
+        && a.level == b.level
+        && a.frame_label == b.frame_label
+        && a.position
+            .iter()
+            .zip(&b.position)
+            .all(|(a, b)| (a - b).abs() as f64 <= tolerance)
+        && (a.pscale - b.pscale).abs() as f64 <= tolerance
+        && a.orient
+            .iter()
+            .zip(&b.orient)
+            .all(|(a, b)| (a - b).abs() as f64 <= tolerance)
+        && a.velocity
+            .iter()
+            .zip(&b.velocity)
+            .all(|(a, b)| (a - b).abs() as f64 <= tolerance)
+        && a.normal
+            .iter()
+            .zip(&b.normal)
+            .all(|(a, b)| (a - b).abs() as f64 <= tolerance)
+        && metadata_matches
+        && a.attributes.len() == b.attributes.len()
+        && a.attributes.iter().all(|(name, value)| {
+            b.attributes
+                .get(name)
+                .is_some_and(|other| values_match(value, other, tolerance))
+        })
+}
+
+/// Compares JSON values, allowing numbers to differ by up to `tolerance`.
+fn values_match(a: &Value, b: &Value, tolerance: f64) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => (a - b).abs() <= tolerance,
+            _ => a == b,
+        },
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| values_match(a, b, tolerance))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(name, value)| {
+                    b.get(name)
+                        .is_some_and(|other| values_match(value, other, tolerance))
+                })
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(name: &str, time: f32, x: f32) -> RecordedPoint {
+        RecordedPoint {
+            name: name.to_string(),
+            kind: "vec3".to_string(),
+            time,
+            position: [x, 0.0, 0.0],
+            metadata: json!({ "pt": [x, 0.0, 0.0] }).to_string(),
+            ..RecordedPoint::default()
+        }
+    }
+
+    #[test]
+    fn diff_matches_entries_by_frame_name_and_order() {
+        let before = Recording {
+            points: vec![
+                point("a", 1.0, 0.0),
+                point("a", 1.0, 1.0),
+                point("b", 1.0, 0.0),
+                point("c", 2.0, 0.0),
+            ],
+        };
+        let after = Recording {
+            points: vec![
+                point("a", 1.0, 0.0),
+                point("a", 1.0, 2.0),
+                point("c", 2.0, 0.0),
+                point("c", 3.0, 0.0),
+            ],
+        };
+
+        let diff = before.diff(&after, 0.0);
+        assert_eq!(diff.unchanged, 2);
+        assert_eq!(diff.count(DiffStatus::Changed), 1);
+        assert_eq!(diff.count(DiffStatus::Removed), 1);
+        assert_eq!(diff.count(DiffStatus::Added), 1);
+
+        let changed = &diff.entries[0];
+        assert_eq!(changed.status, DiffStatus::Changed);
+        assert_eq!(changed.before.as_ref().unwrap().position[0], 1.0);
+        assert_eq!(changed.point().position[0], 2.0);
+        let removed = &diff.entries[1];
+        assert_eq!(removed.status, DiffStatus::Removed);
+        assert_eq!(removed.point().name, "b");
+        assert_eq!(diff.entries[2].point().time, 3.0);
+    }
+
+    #[test]
+    fn diff_allows_numbers_within_the_tolerance() {
+        let before = Recording {
+            points: vec![point("a", 1.0, 1.0)],
+        };
+        let after = Recording {
+            points: vec![point("a", 1.0, 1.05)],
+        };
+        assert!(before.diff(&after, 0.1).is_empty());
+        assert!(!before.diff(&after, 0.01).is_empty());
+
+        let mut with_attribute = after.clone();
+        with_attribute.points[0]
+            .attributes
+            .insert("health".to_string(), json!(1));
+        assert_eq!(
+            before.diff(&with_attribute, 0.1).count(DiffStatus::Changed),
+            1
+        );
+    }
+
+    #[test]
+    fn values_match_compares_nested_numbers() {
+        let a = json!({ "points": [[0.0, 1.0]], "label": "x" });
+        let b = json!({ "points": [[0.0, 1.001]], "label": "x" });
+        assert!(values_match(&a, &b, 0.01));
+        assert!(!values_match(&a, &b, 0.0));
+        assert!(!values_match(&a, &json!({ "points": [[0.0, 1.0]] }), 0.01));
+        assert!(!values_match(&json!("1"), &json!(1), 0.01));
+    }
+
+    #[test]
+    fn to_recording_tags_the_differences() {
+        let before = Recording {
+            points: vec![point("a", 1.0, 0.0)],
+        };
+        let after = Recording {
+            points: vec![point("b", 1.0, 0.0)],
+        };
+        let diff = before.diff(&after, 0.0);
+        let recording = diff.to_recording();
+        let tags = recording
+            .points
+            .iter()
+            .map(|point| (point.name.as_str(), point.attributes["diff"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(tags, [("a", json!("removed")), ("b", json!("added"))]);
+        assert_eq!(
+            diff.to_string(),
+            "1 added, 1 removed, 0 changed, 0 unchanged\n \
+             removed frame 0: a (vec3)\n   \
+             added frame 0: b (vec3)\n"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! A server that collects the recordings of multiple processes into one, see [`HoulogServer`].
+
+use std::collections::BTreeMap;
+use std::io::{BufReader, Read};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use crate::recording::{RecordedPoint, Recording};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+/// The largest message a client may send, to avoid allocating arbitrary amounts of memory for a
+/// corrupted or malicious length prefix.
+const MAX_MESSAGE_SIZE: usize = 1 << 30;
+
+/// A message sent by [`init_houlog_remote`](crate::init_houlog_remote()) or
+/// [`init_houlog_stream`](crate::init_houlog_stream()), which contains the whole recording of the
+/// client, or only the changed frames when `frames` is set.
+#[derive(Deserialize)]
+struct StreamPayload {
+    points: Vec<RecordedPoint>,
+    /// The name of the client. Clients that don't send one (when using `init_houlog_stream`) are
+    /// named after their IP address, so that reconnecting doesn't show up as a new client.
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    frames: Option<StreamFrames>,
+}
+
+/// The frames of a message with only the changed frames, by their time.
+#[derive(Deserialize)]
+struct StreamFrames {
+    /// The first frame of the recording, earlier ones have been dropped.
+    first: f32,
+    /// The last frame of the recording, later ones have been cleared.
+    last: f32,
+    /// The frames contained in the message, which replace the previous ones.
+    updated: Vec<f32>,
+}
+
+impl StreamPayload {
+    /// Applies the message to the previous recording of the client.
+    fn merge_into(self, recording: &mut Recording) {
+        let Some(frames) = self.frames else {
+            recording.points = self.points;
+            return;
+        };
+        // Plots span the whole recording and are always sent.
+        recording.points.retain(|point| {
+            point.time != 0.0
+                && (frames.first..=frames.last).contains(&point.time)
+                && !frames.updated.contains(&point.time)
+        });
+        recording.points.extend(self.points);
+        // Ordered like a whole recording: by frame, followed by the plots.
+        recording.points.sort_by(|a, b| {
+            (a.time == 0.0)
+                .cmp(&(b.time == 0.0))
+                .then(a.time.total_cmp(&b.time))
+        });
+    }
+}
+
+/// Collects the recordings of multiple processes (for example a game client, a dedicated server
+/// and worker jobs) and exports them as a single recording. The clients connect via
+/// [`init_houlog_remote`](crate::init_houlog_remote()), or via
+/// [`init_houlog_stream`](crate::init_houlog_stream()) as if the server was the Python receiver.
+/// Each entry gets a `source` attribute with the name of the client it came from, and the frames
+/// of all clients are merged by their index. Custom attributes named `source` are renamed to
+/// `source_1`.
+///
+/// The combined recording is saved to `output` after clients send updates, as NDJSON or geometry
+/// depending on the extension, see [`Recording::save`]. Saving happens on a thread of its own, and
+/// updates that arrive during a save are combined into the next one. Recordings of clients that
+/// disconnect are kept. This is what `houlog-cli serve` runs.
+pub struct HoulogServer {
+    listener: TcpListener,
+    output: PathBuf,
+    recordings: Arc<Mutex<BTreeMap<String, Recording>>>,
+}
+
+impl HoulogServer {
+    pub fn bind(address: impl ToSocketAddrs, output: impl Into<PathBuf>) -> Result<Self> {
+        Ok(HoulogServer {
+            listener: TcpListener::bind(address)?,
+            output: output.into(),
+            recordings: Arc::default(),
+        })
+    }
+
+    /// The address the server is listening on, for example to find out the port when binding to
+    /// port 0.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Accepts clients until the listener fails, each of them on its own thread.
+    pub fn run(self) -> Result<()> {
+        let (updates, pending) = mpsc::channel();
+        let recordings = self.recordings.clone();
+        thread::spawn(move || save_updates(&recordings, &self.output, pending));
+
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let recordings = self.recordings.clone();
+            let updates = updates.clone();
+            thread::spawn(move || {
+                let address = stream.peer_addr().map(|address| address.to_string());
+                if let Err(e) = receive(stream, &recordings, &updates) {
+                    eprintln!(
+                        "houlog-server: connection to {} failed: {:#}",
+                        address.unwrap_or_default(),
+                        e
+                    );
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Receives updates from a client until it disconnects, notifying the saving thread about each.
+fn receive(
+    stream: TcpStream,
+    recordings: &Mutex<BTreeMap<String, Recording>>,
+    updates: &Sender<()>,
+) -> Result<()> {
+    let address = stream.peer_addr()?.ip().to_string();
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut length = [0; 4];
+        if reader.read_exact(&mut length).is_err() {
+            // Disconnected
+            return Ok(());
+        }
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_MESSAGE_SIZE {
+            return Err(anyhow!(
+                "Message of {} bytes exceeds the maximum of {} bytes",
+                length,
+                MAX_MESSAGE_SIZE
+            ));
+        }
+        let mut payload = vec![0; length];
+        reader.read_exact(&mut payload)?;
+        let payload = serde_json::from_slice::<StreamPayload>(&payload)?;
+
+        let source = payload.source.clone().unwrap_or_else(|| address.clone());
+        let mut recordings = recordings.lock().unwrap_or_else(PoisonError::into_inner);
+        payload.merge_into(recordings.entry(source).or_default());
+        drop(recordings);
+        updates.send(())?;
+    }
+}
+
+/// Saves the combined recording whenever clients sent updates, until all senders are gone. Only
+/// one save happens at a time, so they don't interleave, and the file is written without holding
+/// the lock that the clients need for their updates.
+fn save_updates(
+    recordings: &Mutex<BTreeMap<String, Recording>>,
+    output: &Path,
+    updates: Receiver<()>,
+) {
+    while updates.recv().is_ok() {
+        // Updates that arrived during the last save are covered by this one.
+        while updates.try_recv().is_ok() {}
+        let combined = combine(&recordings.lock().unwrap_or_else(PoisonError::into_inner));
+        if let Err(e) = combined.save(output) {
+            eprintln!(
+                "houlog-server: failed to save {}: {:#}",
+                output.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Merges the recordings of all clients, tagging each entry with its source. Custom attributes
+/// named `source` are moved to the first free name (`source_1`, `source_2`, ...), so they aren't
+/// overwritten.
+fn combine(recordings: &BTreeMap<String, Recording>) -> Recording {
+    Recording::merge(recordings.iter().map(|(source, recording)| {
+        let mut recording = recording.clone();
+        for point in &mut recording.points {
+            if let Some(custom) = point.attributes.remove("source") {
+                let name = (1..)
+                    .map(|suffix| format!("source_{}", suffix))
+                    .find(|name| !point.attributes.contains_key(name))
+                    .unwrap_or_default();
+                point.attributes.insert(name, custom);
+            }
+            point.attributes.insert("source".to_string(), json!(source));
+        }
+        recording
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_keeps_custom_source_attributes() {
+        let mut point = RecordedPoint::default();
+        point.attributes.insert("source".to_string(), json!("user"));
+        let recordings = BTreeMap::from([(
+            "client".to_string(),
+            Recording {
+                points: vec![point],
+            },
+        )]);
+
+        let combined = combine(&recordings);
+        let attributes = &combined.points[0].attributes;
+        assert_eq!(attributes["source"], json!("client"));
+        assert_eq!(attributes["source_1"], json!("user"));
+    }
+
+    #[test]
+    fn partial_messages_replace_the_updated_frames() {
+        let point = |name: &str, time: f32| RecordedPoint {
+            name: name.to_string(),
+            time,
+            ..RecordedPoint::default()
+        };
+        let mut recording = Recording {
+            points: vec![
+                point("a", 1.0),
+                point("b", 2.0),
+                point("c", 3.0),
+                point("plot", 0.0),
+            ],
+        };
+        let payload = StreamPayload {
+            points: vec![point("d", 2.0), point("plot", 0.0)],
+            source: None,
+            frames: Some(StreamFrames {
+                first: 1.0,
+                last: 2.0,
+                updated: vec![2.0],
+            }),
+        };
+
+        payload.merge_into(&mut recording);
+        let names = recording
+            .points
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "d", "plot"]);
+    }
+}
@@ -0,0 +1,429 @@
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::attribute_names::AttributeNames;
+use crate::loggable::*;
+use anyhow::Result;
+use glam::{EulerRot, Mat4, Quat, Vec3};
+use serde_json::{Map, Value};
+
+/// Writes snippets that unpack the metadata of each built-in kind into VEX variables
+/// (`<kind>.vfl`, for attribute wrangles) and Python variables (`<kind>.py`, for Python SOPs) into
+/// `dir`. The layouts are derived from the `as_json()` output of sample values, so regenerating
+/// the snippets keeps the Houdini side in sync with the crate.
+///
+/// The VEX snippets read the `metadata` attribute as a dictionary, so they require the
+/// [`MetadataFormat::Dictionary`](crate::MetadataFormat::Dictionary) format. The Python snippets
+/// work with both formats.
+pub fn generate_unpack_snippets(dir: impl AsRef<Path>) -> Result<()> {
+    generate_unpack_snippets_with(dir, &[])
+}
+
+/// Like [`generate_unpack_snippets`], but also writes snippets for custom loggable types, given
+/// as sample values. Arrays in the samples should have at least one element, since the type of
+/// the elements is determined from the first one.
+pub fn generate_unpack_snippets_with(
+    dir: impl AsRef<Path>,
+    custom: &[&dyn DebugLoggable],
+) -> Result<()> {
+    generate_unpack_snippets_with_names(dir, custom, &AttributeNames::default())
+}
+
+/// Like [`generate_unpack_snippets_with`], for recordings whose attributes have been renamed via
+/// [`houlog_set_attribute_names`](crate::houlog_set_attribute_names()). The snippets then read the
+/// `kind` and `metadata` attributes under their new names.
+pub fn generate_unpack_snippets_with_names(
+    dir: impl AsRef<Path>,
+    custom: &[&dyn DebugLoggable],
+    names: &AttributeNames,
+) -> Result<()> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let samples = kind_samples();
+    for sample in samples
+        .iter()
+        .map(|sample| sample.as_ref())
+        .chain(custom.iter().copied())
+    {
+        let kind = sample.kind();
+        let metadata = serde_json::from_str::<Map<String, Value>>(&sample.as_json())?;
+        let fields = metadata
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), FieldType::of(value)?)))
+            .collect::<Vec<_>>();
+        std::fs::write(
+            dir.join(format!("{}.vfl", kind)),
+            vex_snippet(&kind, &fields, names),
+        )?;
+        std::fs::write(
+            dir.join(format!("{}.py", kind)),
+            python_snippet(&kind, &fields, names),
+        )?;
+    }
+    Ok(())
+}
+
+/// One sample value per built-in kind. `Json` is left out, since its layout is up to the user.
+fn kind_samples() -> Vec<Box<dyn DebugLoggable>> {
+    let points = vec![Vec3::ZERO];
+    let sphere = Sphere {
+        center: Vec3::ZERO,
+        radius: 1.0,
+    };
+    vec![
+        Box::new(Vec3::ZERO),
+        Box::new(Mat4::IDENTITY),
+        Box::new(Quat::IDENTITY),
+        Box::new(EulerAngles {
+            yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            order: EulerRot::YXZ,
+        }),
+        Box::new(0.0f32),
+        Box::new(0i32),
+        Box::new(Duration::ZERO),
+        Box::new(Event {
+            message: String::new(),
+        }),
+        Box::new(Polyline {
+            points: points.clone(),
+        }),
+        Box::new(Polygon {
+            points: points.clone(),
+        }),
+        Box::new(Mesh {
+            vertices: points.clone(),
+            indices: vec![0],
+            index_counts: vec![1],
+        }),
+        Box::new(Bvh {
+            node_aabbs: vec![(Vec3::ZERO, Vec3::ONE)],
+            parents: vec![-1],
+            depths: vec![0],
+        }),
+        Box::new(Octree {
+            centers: points.clone(),
+            sizes: vec![1.0],
+            depths: vec![0],
+            occupied: vec![true],
+        }),
+        Box::new(KdTreeTrace {
+            query: Vec3::ZERO,
+            nearest: Some(Vec3::ZERO),
+            splits: vec![KdSplit {
+                axis: 0,
+                value: 0.0,
+                cell_min: Vec3::ZERO,
+                cell_max: Vec3::ONE,
+            }],
+            visited_cells: vec![(Vec3::ZERO, Vec3::ONE)],
+        }),
+        Box::new(ConvexHull {
+            points: points.clone(),
+        }),
+        Box::new(GridLines {
+            origin: Vec3::ZERO,
+            axis_u: Vec3::X,
+            axis_v: Vec3::Z,
+            divisions_u: 1,
+            divisions_v: 1,
+        }),
+        Box::new(Graph {
+            nodes: points.clone(),
+            edges: vec![(0, 0)],
+            directed: false,
+        }),
+        Box::new(Heightfield {
+            width: 1,
+            height: 1,
+            cell_size: 1.0,
+            heights: vec![0.0],
+        }),
+        Box::new(ColorGrid {
+            width: 1,
+            height: 1,
+            cell_size: 1.0,
+            plane: Plane2D::XZ,
+            colors: vec![[0.0; 3]],
+        }),
+        Box::new(PointCloud { points }),
+        Box::new(sphere),
+        Box::new(Capsule {
+            start: Vec3::ZERO,
+            end: Vec3::Y,
+            radius: 1.0,
+        }),
+        Box::new(Obb {
+            center: Vec3::ZERO,
+            half_extents: Vec3::ONE,
+            rotation: Quat::IDENTITY,
+        }),
+        Box::new(Kinematics {
+            transform: Mat4::IDENTITY,
+            linear_velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+        }),
+        Box::new(Instances {
+            template_name: String::new(),
+            xforms: vec![Mat4::IDENTITY],
+        }),
+        Box::new(SurfaceHit {
+            point: Vec3::ZERO,
+            normal: Vec3::Y,
+        }),
+        Box::new(Shape::Compound(vec![Shape::Sphere(sphere)])),
+        Box::new(AssertFailed {
+            condition: String::new(),
+            value: Box::new(Vec3::ZERO),
+        }),
+        Box::new(Span {
+            start: 0.0,
+            duration: 0.0,
+            depth: 0,
+            parent: Some(String::new()),
+        }),
+        Box::new(ProfileBar {
+            start: 0.0,
+            duration: 0.0,
+            depth: 0,
+            track: String::new(),
+            track_index: 0,
+            parent: Some(String::new()),
+        }),
+        Box::new(Metric::Counter(0)),
+        Box::new(Metric::Gauge(0.0)),
+    ]
+}
+
+/// The type of a metadata field, as far as the snippets are concerned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldType {
+    Float,
+    Int,
+    String,
+    /// A float array with three elements, such as a position.
+    Vector,
+    FloatArray,
+    IntArray,
+    StringArray,
+    Dict,
+    DictArray,
+}
+
+impl FieldType {
+    /// Determines the type of a field from its value in a sample, `None` for `null`.
+    fn of(value: &Value) -> Option<Self> {
+        Some(match value {
+            Value::Null => return None,
+            Value::Bool(_) => FieldType::Int,
+            Value::Number(n) if n.is_f64() => FieldType::Float,
+            Value::Number(_) => FieldType::Int,
+            Value::String(_) => FieldType::String,
+            Value::Object(_) => FieldType::Dict,
+            Value::Array(values) => match values.first().and_then(FieldType::of) {
+                Some(FieldType::Float) if values.len() == 3 => FieldType::Vector,
+                Some(FieldType::Float) => FieldType::FloatArray,
+                Some(FieldType::Int) => FieldType::IntArray,
+                Some(FieldType::String) => FieldType::StringArray,
+                Some(FieldType::Dict) => FieldType::DictArray,
+                _ => FieldType::FloatArray,
+            },
+        })
+    }
+
+    fn python_comment(self) -> &'static str {
+        match self {
+            FieldType::Float => "float",
+            FieldType::Int => "int",
+            FieldType::String => "str",
+            FieldType::Vector => "[x, y, z]",
+            FieldType::FloatArray => "list of float",
+            FieldType::IntArray => "list of int",
+            FieldType::StringArray => "list of str",
+            FieldType::Dict => "dict",
+            FieldType::DictArray => "list of dict",
+        }
+    }
+}
+
+/// Turns a metadata field name into a variable name that isn't a VEX or Python keyword, or one of
+/// the variables the snippets use themselves.
+fn variable_name(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "int", "float", "string", "vector", "vector2", "vector4", "matrix", "matrix3", "dict",
+        "export", "return", "break", "continue", "for", "while", "if", "else",
+    ];
+    const SNIPPET_VARIABLES: &[&str] = &["pt", "geo", "metadata", "json", "hou"];
+    if KEYWORDS.contains(&name) || SNIPPET_VARIABLES.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn vex_snippet(kind: &str, fields: &[(&str, FieldType)], names: &AttributeNames) -> String {
+    let mut code = format!(
+        "// Unpacks the metadata of `{}` entries. Generated by houdini-debug-logger, requires the\n\
+         // Dictionary metadata format.\n\
+         if (s@{} == \"{}\") {{\n    dict metadata = d@{};\n",
+        kind,
+        names.get("kind"),
+        kind,
+        names.get("metadata")
+    );
+    for &(name, field_type) in fields {
+        let variable = variable_name(name);
+        let _ = match field_type {
+            FieldType::Float => writeln!(code, "    float {} = metadata[\"{}\"];", variable, name),
+            FieldType::Int => writeln!(code, "    int {} = metadata[\"{}\"];", variable, name),
+            FieldType::String => {
+                writeln!(code, "    string {} = metadata[\"{}\"];", variable, name)
+            }
+            FieldType::Vector => writeln!(
+                code,
+                "    float {v}_values[] = metadata[\"{n}\"];\n    \
+                 vector {v} = set({v}_values[0], {v}_values[1], {v}_values[2]);",
+                v = variable,
+                n = name
+            ),
+            FieldType::FloatArray => {
+                writeln!(code, "    float {}[] = metadata[\"{}\"];", variable, name)
+            }
+            FieldType::IntArray => {
+                writeln!(code, "    int {}[] = metadata[\"{}\"];", variable, name)
+            }
+            FieldType::StringArray => {
+                writeln!(code, "    string {}[] = metadata[\"{}\"];", variable, name)
+            }
+            FieldType::Dict => writeln!(code, "    dict {} = metadata[\"{}\"];", variable, name),
+            FieldType::DictArray => {
+                writeln!(code, "    dict {}[] = metadata[\"{}\"];", variable, name)
+            }
+        };
+    }
+    code.push_str("}\n");
+    code
+}
+
+fn python_snippet(kind: &str, fields: &[(&str, FieldType)], names: &AttributeNames) -> String {
+    let mut code = format!(
+        "# Unpacks the metadata of `{}` entries. Generated by houdini-debug-logger, works with both\n\
+         # metadata formats.\n\
+         import json\n\n\
+         geo = hou.pwd().geometry()\n\
+         for pt in geo.points():\n    \
+         if pt.attribValue(\"{}\") != \"{}\":\n        continue\n    \
+         metadata = pt.attribValue(\"{}\")\n    \
+         if not isinstance(metadata, dict):\n        metadata = json.loads(metadata)\n",
+        kind,
+        names.get("kind"),
+        kind,
+        names.get("metadata")
+    );
+    for &(name, field_type) in fields {
+        let _ = writeln!(
+            code,
+            "    {} = metadata[\"{}\"]  # {}",
+            variable_name(name),
+            name,
+            field_type.python_comment()
+        );
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_read_renamed_attributes() {
+        let fields = [("pt", FieldType::Vector)];
+        let names = AttributeNames::with_prefix("houlog_").rename("kind", "type");
+
+        let vex = vex_snippet("vec3", &fields, &AttributeNames::default());
+        assert!(vex.contains("if (s@kind == \"vec3\") {\n    dict metadata = d@metadata;\n"));
+        let vex = vex_snippet("vec3", &fields, &names);
+        assert!(vex.contains("if (s@houlog_type == \"vec3\") {"));
+        assert!(vex.contains("dict metadata = d@houlog_metadata;"));
+        assert!(vex.contains("vector pt_ = set(pt__values[0], pt__values[1], pt__values[2]);"));
+
+        let python = python_snippet("vec3", &fields, &names);
+        assert!(python.contains("if pt.attribValue(\"houlog_type\") != \"vec3\":"));
+        assert!(python.contains("metadata = pt.attribValue(\"houlog_metadata\")"));
+        assert!(python.contains("    pt_ = metadata[\"pt\"]  # "));
+    }
+
+    #[test]
+    fn viewer_builds_every_kind() {
+        for sample in kind_samples() {
+            let builder = format!("\"{}\": build_", sample.kind());
+            assert!(
+                crate::HOULOG_VIEWER_SCRIPT.contains(&builder),
+                "the viewer has no builder for {}",
+                sample.kind()
+            );
+        }
+    }
+
+    struct Agent;
+
+    impl DebugLoggable for Agent {
+        fn kind(&self) -> String {
+            "agent".to_string()
+        }
+        fn as_json(&self) -> String {
+            serde_json::json!({ "goal": [1.0, 2.0, 3.0], "path": [1, 2], "state": "idle" })
+                .to_string()
+        }
+    }
+
+    #[test]
+    fn field_types_are_derived_from_the_samples() {
+        use serde_json::json;
+        assert_eq!(FieldType::of(&json!(1.5)), Some(FieldType::Float));
+        assert_eq!(FieldType::of(&json!(true)), Some(FieldType::Int));
+        assert_eq!(
+            FieldType::of(&json!([1.0, 2.0, 3.0])),
+            Some(FieldType::Vector)
+        );
+        assert_eq!(
+            FieldType::of(&json!([1.0, 2.0])),
+            Some(FieldType::FloatArray)
+        );
+        assert_eq!(FieldType::of(&json!(["a"])), Some(FieldType::StringArray));
+        assert_eq!(FieldType::of(&json!([{}])), Some(FieldType::DictArray));
+        assert_eq!(FieldType::of(&json!(null)), None);
+        assert_eq!(variable_name("int"), "int_");
+        assert_eq!(variable_name("pt"), "pt_");
+        assert_eq!(variable_name("metadata"), "metadata_");
+        assert_eq!(variable_name("points"), "points");
+    }
+
+    #[test]
+    fn snippets_are_written_for_every_kind() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("houlog_snippets_{}", std::process::id()));
+        let result = generate_unpack_snippets_with(&dir, &[&Agent]).and_then(|_| {
+            let vex = std::fs::read_to_string(dir.join("agent.vfl"))?;
+            let python = std::fs::read_to_string(dir.join("agent.py"))?;
+            let missing = kind_samples()
+                .iter()
+                .map(|sample| sample.kind())
+                .filter(|kind| !dir.join(format!("{}.vfl", kind)).exists())
+                .collect::<Vec<_>>();
+            Ok((vex, python, missing))
+        });
+        std::fs::remove_dir_all(&dir)?;
+
+        let (vex, python, missing) = result?;
+        assert!(missing.is_empty(), "missing snippets for {:?}", missing);
+        assert!(vex.contains("vector goal = set(goal_values[0], goal_values[1], goal_values[2]);"));
+        assert!(vex.contains("    int path[] = metadata[\"path\"];"));
+        assert!(vex.contains("    string state = metadata[\"state\"];"));
+        assert!(python.contains("    goal = metadata[\"goal\"]  # [x, y, z]"));
+        assert!(python.contains("    path = metadata[\"path\"]  # list of int"));
+        Ok(())
+    }
+}
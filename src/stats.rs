@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Statistics about the recording that is currently in memory, see
+/// [`houlog_stats`](crate::houlog_stats()). Entries that have been spilled to disk aren't included.
+/// Printing it gives a short report.
+#[derive(Debug, Clone, Default)]
+pub struct HoulogStats {
+    pub entries: usize,
+    pub frames: usize,
+    /// The estimated memory used by all entries in bytes, see
+    /// [`DebugLoggable::memory_size`](crate::DebugLoggable::memory_size).
+    pub memory: usize,
+    pub entries_per_kind: BTreeMap<String, usize>,
+    pub entries_per_name: BTreeMap<String, usize>,
+    /// The estimated memory used by the entries of each (0-based) frame that is still in memory.
+    pub bytes_per_frame: BTreeMap<usize, usize>,
+    /// The entries using the most memory, largest first.
+    pub largest_entries: Vec<EntryStats>,
+}
+
+/// An entry in [`HoulogStats::largest_entries`].
+#[derive(Debug, Clone)]
+pub struct EntryStats {
+    pub name: String,
+    pub kind: String,
+    pub frame: usize,
+    /// The estimated memory used by the entry in bytes.
+    pub bytes: usize,
+    /// The source location of the logging call.
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl fmt::Display for HoulogStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} entries in {} frames, {} bytes",
+            self.entries, self.frames, self.memory
+        )?;
+        writeln!(f, "largest entries:")?;
+        for entry in &self.largest_entries {
+            writeln!(
+                f,
+                "    {:>10} bytes  {} ({}) in frame {} at {}:{}",
+                entry.bytes, entry.name, entry.kind, entry.frame, entry.file, entry.line
+            )?;
+        }
+        Ok(())
+    }
+}
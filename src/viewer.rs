@@ -0,0 +1,34 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::HOULOG_VIEWER_SCRIPT;
+use anyhow::{anyhow, Context, Result};
+
+/// Writes the viewer HDA matching this version of the crate to `path`, so that it doesn't have to
+/// be distributed separately. This runs [`HOULOG_VIEWER_SCRIPT`] with `hython` from the Houdini
+/// installation in the `HFS` environment variable, which needs a regular Houdini license, but no
+/// Houdini Engine license.
+pub fn write_viewer_hda(path: impl AsRef<Path>) -> Result<()> {
+    let hfs = std::env::var_os("HFS").ok_or_else(|| anyhow!("HFS is not set"))?;
+    let hython = PathBuf::from(hfs).join("bin").join(if cfg!(windows) {
+        "hython.exe"
+    } else {
+        "hython"
+    });
+
+    let script = std::env::temp_dir().join(format!("houlog_viewer_{}.py", std::process::id()));
+    std::fs::write(&script, HOULOG_VIEWER_SCRIPT)?;
+    let status = Command::new(&hython)
+        .arg(&script)
+        .arg(path.as_ref())
+        .arg(env!("CARGO_PKG_VERSION"))
+        .status()
+        .with_context(|| format!("Could not run {}", hython.display()));
+    let _ = std::fs::remove_file(&script);
+
+    let status = status?;
+    if !status.success() {
+        return Err(anyhow!("hython failed with {}", status));
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! A minimal WebSocket server that broadcasts the recording to all subscribers, see
+//! [`houlog_serve_websocket`](crate::houlog_serve_websocket()).
+
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use tungstenite::{Message, WebSocket};
+
+/// How long sending to a client may block before the client is dropped, so that a stalled client
+/// can't hold up the save that broadcasts the recording. Also bounds the handshake.
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct ServerState {
+    clients: Vec<WebSocket<TcpStream>>,
+    /// The last broadcast payload, sent to new clients as soon as they connect.
+    latest: Option<String>,
+}
+
+pub(crate) struct WebSocketServer {
+    state: Arc<Mutex<ServerState>>,
+}
+
+impl WebSocketServer {
+    /// Starts accepting clients on a background thread. Each handshake runs on a thread of its
+    /// own, so that a client that never finishes it doesn't keep others from connecting.
+    pub(crate) fn bind(address: impl ToSocketAddrs) -> Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let state = Arc::new(Mutex::new(ServerState::default()));
+
+        let accept_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let state = accept_state.clone();
+                thread::spawn(move || {
+                    let _ = Self::connect(stream, &state);
+                });
+            }
+        });
+
+        Ok(WebSocketServer { state })
+    }
+
+    /// Performs the handshake with a new client, sends it the latest payload and adds it to the
+    /// clients. Errors just drop the client.
+    fn connect(stream: TcpStream, state: &Mutex<ServerState>) -> Result<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut client = tungstenite::accept(stream)
+            .map_err(|e| anyhow!("WebSocket handshake failed: {}", e))?;
+        let Ok(mut state) = state.lock() else {
+            return Ok(());
+        };
+        if let Some(latest) = &state.latest {
+            client.send(Message::Text(latest.clone()))?;
+        }
+        state.clients.push(client);
+        Ok(())
+    }
+
+    /// Sends the payload to all clients, dropping the ones that have disconnected or didn't accept
+    /// it within [`TIMEOUT`].
+    pub(crate) fn broadcast(&self, payload: String) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state
+            .clients
+            .retain_mut(|client| client.send(Message::Text(payload.clone())).is_ok());
+        state.latest = Some(payload);
+    }
+}